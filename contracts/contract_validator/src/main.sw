@@ -48,22 +48,54 @@ use intentswap_712_tools::{
         input_coin_amount,
         input_coin_asset_id,
         output_coin_asset_id,
+        output_coin_to,
         verify_input_coin,
         verify_output_coin,
         verify_output_change,
         input_txn_hash,
+        verify_input_message_coin,
+        input_message_nonce,
+        input_message_amount,
+        input_message_recipient,
+        find_input_index_by_id,
     },
     io_utils::{
         InpOut,
         process_assets,
         check_utxos,
-        find_input_assets_owner,
         verify_change_output,
+        verify_output_recipient,
+        calc_fee_amount,
+        verify_fee_output,
+        is_gas_sponsor_input,
+        mark_base_asset,
+        mark_intent_assets,
+        unmark_base_asset,
+        unmark_intent_assets,
+        find_intent_sender,
+        filter_inputs_by_owner,
+        verify_exact_input_total,
+        read_owner_predicate, OwnerPredicateLayout,
         reconstruct_intent, ReconstructIntentResult,
     },
 };
 
-const TEST_CONST_EVM_SINGER: b256 = 0x000000000000000000000000222227f7e08997ee7457a0a772e417ca5462906d;
+configurable {
+    // the address that receives the protocol fee.
+    FEE_RECIPIENT: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the protocol fee in bps, taken in the intent input asset. Zero disables the fee.
+    FEE_BPS: u64 = 0,
+    // the address of the gas sponsor, its base asset inputs are not part of the swap.
+    GAS_SPONSOR: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the sha256 of the owner predicate bytecode with zero configurables, see read_owner_predicate.
+    OWNER_PREDICATE_TEMPLATE: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the offsets of the owner EVM address, SwapVerifier id and protocol fee configurables in
+    // the owner predicate bytecode.
+    OWNER_PREDICATE_OFFSET: u64 = 0,
+    OWNER_PREDICATE_VERIFIER_OFFSET: u64 = 0,
+    OWNER_PREDICATE_FEE_RECIPIENT_OFFSET: u64 = 0,
+    OWNER_PREDICATE_FEE_BPS_OFFSET: u64 = 0,
+}
 
 
 abi SwapVerifier {
@@ -110,6 +142,7 @@ impl SwapVerifier for Contract {
                 io.outputasset,
                 io.outputamount,
                 io.tolerance,
+                io.recipient,
             ),
         );
         let encoded_hash = payload.encode_eip712();
@@ -143,6 +176,8 @@ impl SwapVerifier for Contract {
         let mut tx_change_to: Vec<b256> = Vec::new();
 
         let mut change_ok = false;
+        let mut recipient_ok = false;
+        let mut fee_ok = false;
         let mut sender = b256::zero();
         let mut recovered_signer = b256::zero();
         let mut signed_by_sender: bool = false;
@@ -152,10 +187,15 @@ impl SwapVerifier for Contract {
 
         let mut i = 0;
         while i < in_count {
-            // collect all the input coins
-            if verify_input_coin(i) {
+            // collect all the input coins, except the gas sponsor's gas inputs
+            if verify_input_coin(i) && !is_gas_sponsor_input(
+                input_coin_asset_id(i),
+                input_coin_owner(i),
+                AssetId::base().bits(),
+                GAS_SPONSOR,
+            ) {
                 let inp = InpOut::new(
-                    input_coin_asset_id(i),
+                    mark_base_asset(input_coin_asset_id(i)),
                     Some(input_coin_amount(i)),
                     None,
                     Some(input_txn_hash(i)),
@@ -164,6 +204,22 @@ impl SwapVerifier for Contract {
                 tx_inputs.push(inp);
 
             }
+            // collect the message coins (e.g. bridged in base asset), the nonce is the unique id.
+            if verify_input_message_coin(i) && !is_gas_sponsor_input(
+                AssetId::base().bits(),
+                Some(input_message_recipient(i)),
+                AssetId::base().bits(),
+                GAS_SPONSOR,
+            ) {
+                let inp = InpOut::new(
+                    mark_base_asset(AssetId::base().bits()),
+                    Some(input_message_amount(i)),
+                    None,
+                    Some(input_message_nonce(i)),
+                    Some(input_message_recipient(i))
+                );
+                tx_inputs.push(inp);
+            }
             i += 1;
         }
         let mut j = 0;
@@ -172,11 +228,11 @@ impl SwapVerifier for Contract {
             if verify_output_coin(j) {
 
                 let outp = InpOut::new(
-                    output_coin_asset_id(j).unwrap(),   // from tx_utls, return Option<b256>
+                    mark_base_asset(output_coin_asset_id(j).unwrap()),   // from tx_utls, return Option<b256>
                     Some(output_amount(j).unwrap()),
                     None,
                     None,
-                    None
+                    Some(Address::from(output_coin_to(j)))
                 );
                 tx_outputs.push(outp);
             }
@@ -186,7 +242,7 @@ impl SwapVerifier for Contract {
                 Some(is_change) => {
                     if is_change {
                         log(output_asset_id(j).unwrap());
-                        tx_change_assetid.push(output_asset_id(j).unwrap().into());
+                        tx_change_assetid.push(mark_base_asset(output_asset_id(j).unwrap().into()));
                         tx_change_to.push(output_asset_to(j).unwrap().into());
                     }
                 },
@@ -232,11 +288,30 @@ impl SwapVerifier for Contract {
         //reorder the inputs to match the expected utxo set:
         // reorder_inputs(tx_inputs, intent.io.inpututxoids);
 
+        // mark the base asset in the intent, so a zero (base) asset id is not taken as an empty slot.
+        let io_inputassets = mark_intent_assets(intent.io.inputassets, intent.io.inputamounts);
+        let io_outputasset = if intent.io.outputamount != b256::zero() {
+            mark_base_asset(intent.io.outputasset)
+        } else {
+            intent.io.outputasset
+        };
+        let base_asset = mark_base_asset(AssetId::base().bits());
+
+        // the sender owns the first intent utxo. Only the sender's inputs are swap inputs,
+        // the solver's inputs (including any gas input) are not counted against the intent.
+        let sender_inputs = match find_intent_sender(tx_inputs, intent.io.inpututxoids) {
+            Some(owner) => {
+                sender = owner.into(); //returned as Option<Address>
+                filter_inputs_by_owner(tx_inputs, owner)
+            },
+            None => Vec::new(),
+        };
+
         let input_result = match process_assets(
-            tx_inputs,
-            intent.io.inputassets,      // intent input assets
+            sender_inputs,
+            io_inputassets,             // intent input assets
             intent.io.inputamounts,     // intent input amounts
-            intent.io.outputasset,      // intent output assets
+            b256::zero(),               // the output asset is not an input of the sender
             intent.io.outputamount,     // intent output amounts
             tolerance_bps,              // tollerance bps as a u64
             true                        // is inputs?
@@ -255,9 +330,9 @@ impl SwapVerifier for Contract {
         };
         let output_result = match process_assets(
             tx_outputs,
-            intent.io.inputassets,      // intent input assets
+            io_inputassets,             // intent input assets
             intent.io.inputamounts,     // intent input amounts
-            intent.io.outputasset,      // intent output assets
+            io_outputasset,             // intent output assets
             intent.io.outputamount,     // intent output amounts
             tolerance_bps,              // tollerance bps as a u64
             false
@@ -274,7 +349,7 @@ impl SwapVerifier for Contract {
             },
         };
         // check utxos are ok
-        let (utxo_check_result, ordered_utxos, utxo_indices) = check_utxos(tx_inputs, intent.io.inpututxoids);
+        let (utxo_check_result, ordered_utxos, utxo_indices) = check_utxos(sender_inputs, intent.io.inpututxoids);
 
 
 
@@ -314,27 +389,50 @@ impl SwapVerifier for Contract {
         //-------------------------------------------- DEBUG end.
 
 
-        // check there is a change output of the asset_in to the sender
+        // check there is a change output of the asset_in to the sender.
         //
-        // find the tx_input that matches the assetid of input_result.match_asset, record the idx (k)
-        // as you iterate through tx_inputs.
-        // query the tx inputs at idx (k), and find the owner.
-        //
-        match find_input_assets_owner(tx_inputs, input_result.match_asset) {
-            Some(owner) => {
-                sender = owner.into(); //returned as Option<Address>
-                change_ok = verify_change_output( // use v4
-                    tx_change_assetid,
-                    tx_change_to,
-                    input_result.match_asset,
-                    sender,
-                );
-            },
-            None => {
-                sender = b256::zero();
-            },
+        // the base asset change output belongs to whoever pays the gas (there can only be one per asset),
+        // so when the sender swaps the base asset, the sender's inputs must be spent in full instead.
+        if sender == b256::zero() {
+            change_ok = false;
+        } else if input_result.match_asset == base_asset {
+            change_ok = verify_exact_input_total(
+                input_result.agg_assets,
+                input_result.agg_amounts,
+                input_result.match_asset,
+                io_inputassets,
+                intent.io.inputamounts,
+            );
+        } else {
+            change_ok = verify_change_output( // use v4
+                tx_change_assetid,
+                tx_change_to,
+                input_result.match_asset,
+                sender,
+            );
         }
 
+        // check the output asset is only sent to the recipient signed in the intent.
+        recipient_ok = verify_output_recipient(
+            tx_outputs,
+            io_outputasset,
+            intent.io.recipient,
+        );
+
+        // check the protocol fee is paid, in the input asset, to the fee recipient.
+        if FEE_BPS == 0 {
+            fee_ok = true;
+        } else {
+            fee_ok = match calc_fee_amount(io_inputassets, intent.io.inputamounts, input_result.match_asset, FEE_BPS) {
+                Some(fee_amount) => verify_fee_output(
+                    tx_outputs,
+                    input_result.match_asset,
+                    fee_amount,
+                    FEE_RECIPIENT,
+                ),
+                None => false,
+            };
+        }
 
         //-------------------------------------------- DEBUG:
             log(String::from_ascii_str("Sender:"));
@@ -344,17 +442,29 @@ impl SwapVerifier for Contract {
             } else {
                 log(String::from_ascii_str("Change : FAIL"));
             }
+            if recipient_ok {
+                log(String::from_ascii_str("Recipient: OK"));
+            } else {
+                log(String::from_ascii_str("Recipient: FAIL"));
+            }
+            if fee_ok {
+                log(String::from_ascii_str("Fee    : OK"));
+            } else {
+                log(String::from_ascii_str("Fee    : FAIL"));
+            }
         //-------------------------------------------- DEBUG end.
 
 
         if (input_result.amounts_match &&
             output_result.amounts_match &&
             utxo_check_result &&
-            change_ok
+            change_ok &&
+            recipient_ok &&
+            fee_ok
             ) {
 
             match reconstruct_intent(
-                tx_inputs,
+                sender_inputs,
                 input_result.agg_assets,
                 input_result.agg_amounts,
                 output_result.agg_assets,
@@ -409,12 +519,13 @@ impl SwapVerifier for Contract {
                     let payload = (
                         EIP712Domain::new(),
                         GenIO::new(
-                            recon_intent.input_assets,
+                            unmark_intent_assets(recon_intent.input_assets),
                             recon_intent.input_utxos,
                             recon_intent.input_amounts,
-                            recon_intent.output_asset,
+                            unmark_base_asset(recon_intent.output_asset),
                             recon_intent.output_amount,
                             intent.io.tolerance,
+                            intent.io.recipient,
                         )
                     );
                     let encoded_hash = match payload.encode_eip712() {
@@ -467,16 +578,40 @@ impl SwapVerifier for Contract {
                 revert(6664);
             } else if !change_ok {    // revert code for change failure
                 revert(6665);
+            } else if !recipient_ok {    // revert code for recipient failure
+                revert(6667);
+            } else if !fee_ok {    // revert code for protocol fee failure
+                revert(6668);
             }
 
         }
 
+        // the intent sender is the EVM address of the owner predicate that spends the
+        // intent inputs, so a valid signature by any other key does not spend them. The owner
+        // predicate must also be bound to this verifier.
+        let owner_predicate_layout = OwnerPredicateLayout {
+            owner: OWNER_PREDICATE_OFFSET,
+            verifier: OWNER_PREDICATE_VERIFIER_OFFSET,
+            fee_recipient: OWNER_PREDICATE_FEE_RECIPIENT_OFFSET,
+            fee_bps: OWNER_PREDICATE_FEE_BPS_OFFSET,
+        };
+        let sender_owns_inputs = match find_input_index_by_id(intent.io.inpututxoids[0]) {
+            Some(index) => match input_predicate(index) {
+                Some(predicate) => match read_owner_predicate(predicate, owner_predicate_layout, OWNER_PREDICATE_TEMPLATE) {
+                    Some(binding) => binding.owner == intent.sender && binding.verifier == ContractId::this().bits(),
+                    None => false,
+                },
+                None => false,
+            },
+            None => false,
+        };
+
         //-------------------------------------------- DEBUG:
             log(String::from_ascii_str("Recovered Signer:"));
             log(b256_to_hex(recovered_signer));
 
             // log(recovered_signer);   //NOTE - this gives compiler error
-            if (recovered_signer == TEST_CONST_EVM_SINGER) {
+            if (recovered_signer == intent.sender && sender_owns_inputs) {
                 signed_by_sender = true;
             } else {
                 revert(6661);
@@ -488,7 +623,7 @@ impl SwapVerifier for Contract {
             return signed_by_sender;
         //-------------------------------------------- DEBUG end.
 
-        // if(recovered_signer == TEST_CONST_EVM_SINGER) {
+        // if(recovered_signer == intent.sender) {
         //     return true;
         // } else {
         //     return false;
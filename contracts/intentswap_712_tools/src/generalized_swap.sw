@@ -72,6 +72,7 @@ pub struct GenIO {
     pub outputasset: b256,
     pub outputamount: b256,
     pub tolerance: b256,
+    pub recipient: b256,
 }
 
 impl GenIO {
@@ -83,6 +84,7 @@ impl GenIO {
         asset_out: b256,
         amount_out: b256,
         tolerance_bps: b256,
+        recipient: b256,
     ) -> GenIO {
         GenIO {
             inputassets: [assets_in[0], assets_in[1], assets_in[2], assets_in[3], assets_in[4]],
@@ -91,13 +93,14 @@ impl GenIO {
             outputasset: asset_out,
             outputamount: amount_out,
             tolerance: tolerance_bps,
+            recipient: recipient,
         }
     }
 
     /// hard coded type hash for GenIO
     pub fn type_hash() -> b256 {
-        // keccak256("GenIO(bytes32[5] inputassets,bytes32[5] inpututxoids,uint256[5] inputamounts,bytes32 outputasset,uint256 outputamount,uint256 tolerance)")
-        let type_hash: b256 = 0x2aba27466153a0e63c25c715bd2cd4807b7f9c8ca6938eb1c37f849c9c6c9fa9;
+        // keccak256("GenIO(bytes32[5] inputassets,bytes32[5] inpututxoids,uint256[5] inputamounts,bytes32 outputasset,uint256 outputamount,uint256 tolerance,bytes32 recipient)")
+        let type_hash: b256 = 0x8bcc15fb268f69e9bafa1c37e06e15fc77fed40ca81310ba8332a34003f2b34b;
         type_hash
     }
 
@@ -122,6 +125,7 @@ impl GenIO {
         extend(encoded, self.outputasset.to_be_bytes(), 32);
         extend(encoded, self.outputamount.to_be_bytes(), 32);
         extend(encoded, self.tolerance.to_be_bytes(), 32);
+        extend(encoded, self.recipient.to_be_bytes(), 32);
         hash_bytes(encoded)
     }
 }
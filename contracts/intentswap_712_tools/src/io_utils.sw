@@ -8,6 +8,7 @@ use std::{
 };
 use std::*;
 use std::bytes_conversions::{b256::*, u64::*};
+use std::hash::{Hash, sha256};
 use std::primitive_conversions::{u16::*, u32::*, u64::*};
 
 use helpers::numeric_utils::*;
@@ -647,6 +648,402 @@ pub fn verify_change_output(
 }
 
 
+/// Verifies that the intent output asset is only paid to the signed recipient.
+///
+/// # Arguments
+///
+/// * `tx_outputs`: Vector of transaction coin outputs, with the receiver stored as the owner.
+/// * `expected_asset_out`: The output asset ID from the intent.
+/// * `recipient`: The signed recipient address from the intent.
+///
+/// # Returns
+///
+/// * `bool`: Returns `true` if at least one coin output of the output asset exists and
+///           every coin output of that asset is sent to the recipient, `false` otherwise.
+///
+pub fn verify_output_recipient(
+    tx_outputs: Vec<InpOut>,
+    expected_asset_out: b256,
+    recipient: b256,
+) -> bool {
+    // A zero recipient can never be satisfied
+    if recipient == b256::zero() {
+        return false;
+    }
+
+    let mut found = false;
+    let mut i = 0;
+    while i < tx_outputs.len() {
+        let output = tx_outputs.get(i).unwrap();
+        if output.assetid == expected_asset_out {
+            match output.owner {
+                Some(to) => {
+                    let to: b256 = to.into();
+                    if to != recipient {
+                        return false;
+                    }
+                    found = true;
+                },
+                None => return false,
+            }
+        }
+        i += 1;
+    }
+
+    found
+}
+
+
+/// Checks if a transaction input is a base asset input that belongs to the gas sponsor.
+///
+/// # Arguments
+///
+/// * `assetid`: The asset ID of the input.
+/// * `owner`: The owner of the input.
+/// * `base_asset`: The base asset ID of the chain.
+/// * `gas_sponsor`: The address of the gas sponsor, b256::zero() if there is none.
+///
+/// # Returns
+///
+/// * `bool`: Returns `true` if the input only pays for gas and must not be counted as
+///           part of the swap, `false` otherwise.
+///
+pub fn is_gas_sponsor_input(
+    assetid: b256,
+    owner: Option<Address>,
+    base_asset: b256,
+    gas_sponsor: b256,
+) -> bool {
+    if gas_sponsor == b256::zero() || assetid != base_asset {
+        return false;
+    }
+    match owner {
+        Some(owner) => {
+            let owner: b256 = owner.into();
+            owner == gas_sponsor
+        },
+        None => false,
+    }
+}
+
+
+/// Stands in for the base asset id while the verifier processes a swap. Where the base asset
+/// id is b256::zero() it would otherwise be read as an empty slot of the intent arrays.
+pub const BASE_ASSET_MARKER: b256 = 0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff;
+
+/// Replaces a zero (base) asset id of a transaction input or output with the BASE_ASSET_MARKER.
+pub fn mark_base_asset(assetid: b256) -> b256 {
+    if assetid == b256::zero() {
+        BASE_ASSET_MARKER
+    } else {
+        assetid
+    }
+}
+
+/// Reverses mark_base_asset, so the intent can be hashed with the signed asset ids.
+pub fn unmark_base_asset(assetid: b256) -> b256 {
+    if assetid == BASE_ASSET_MARKER {
+        b256::zero()
+    } else {
+        assetid
+    }
+}
+
+/// Marks the zero (base) asset ids of the intent input slots that are in use,
+/// i.e. that have a non-zero amount. Empty slots are left as b256::zero().
+pub fn mark_intent_assets(
+    expected_assets: [b256; 5],
+    expected_amounts: [b256; 5],
+) -> [b256; 5] {
+    let mut marked = expected_assets;
+    let mut i = 0;
+    while i < 5 {
+        if expected_amounts[i] != b256::zero() {
+            marked[i] = mark_base_asset(expected_assets[i]);
+        }
+        i += 1;
+    }
+    marked
+}
+
+/// Reverses mark_intent_assets.
+pub fn unmark_intent_assets(assets: [b256; 5]) -> [b256; 5] {
+    let mut unmarked = assets;
+    let mut i = 0;
+    while i < 5 {
+        unmarked[i] = unmark_base_asset(assets[i]);
+        i += 1;
+    }
+    unmarked
+}
+
+
+/// Finds the intent sender, the owner of the input spending the first intent UTXO.
+///
+/// # Arguments
+///
+/// * `tx_inputs`: Vector of transaction inputs.
+/// * `expected_utxos`: The UTXO ids from the intent.
+///
+/// # Returns
+///
+/// * `Option<Address>`: The owner of the input, None if the UTXO is not an input of the tx.
+///
+pub fn find_intent_sender(
+    tx_inputs: Vec<InpOut>,
+    expected_utxos: [b256; 5],
+) -> Option<Address> {
+    let mut i = 0;
+    while i < 5 {
+        if expected_utxos[i] != b256::zero() {
+            let mut j = 0;
+            while j < tx_inputs.len() {
+                let input = tx_inputs.get(j).unwrap();
+                if let Some(tx_utxo) = input.uxtoid {
+                    if tx_utxo == expected_utxos[i] {
+                        return input.owner;
+                    }
+                }
+                j += 1;
+            }
+            return None;
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Returns only the transaction inputs owned by the given address. The swap inputs are the
+/// sender's inputs, solver and gas inputs are not counted against the intent.
+pub fn filter_inputs_by_owner(
+    tx_inputs: Vec<InpOut>,
+    owner: Address,
+) -> Vec<InpOut> {
+    let mut owned: Vec<InpOut> = Vec::new();
+    let mut i = 0;
+    while i < tx_inputs.len() {
+        let input = tx_inputs.get(i).unwrap();
+        if let Some(input_owner) = input.owner {
+            if input_owner == owner {
+                owned.push(input);
+            }
+        }
+        i += 1;
+    }
+    owned
+}
+
+/// Verifies that the aggregated inputs of an asset are exactly the intent amounts of that asset.
+///
+/// Used in place of the change check for the base asset. A transaction has a single base asset
+/// change output, which belongs to whoever pays the gas, so the sender's base asset inputs must be
+/// spent in full.
+///
+/// # Arguments
+///
+/// * `agg_assets`: An array of 3 b256 values representing the aggregated input asset IDs.
+/// * `agg_amounts`: An array of 3 b256 values representing the aggregated input amounts.
+/// * `expected_asset`: The input asset to check.
+/// * `expected_assets`: An array of 5 b256 values representing the intent input asset IDs.
+/// * `expected_amounts`: An array of 5 b256 values representing the intent input amounts.
+///
+/// # Returns
+///
+/// * `bool`: Returns `true` if the aggregated amount equals the intent total, `false` otherwise.
+///
+pub fn verify_exact_input_total(
+    agg_assets: [b256; 3],
+    agg_amounts: [b256; 3],
+    expected_asset: b256,
+    expected_assets: [b256; 5],
+    expected_amounts: [b256; 5],
+) -> bool {
+    let mut expected_total: u64 = 0;
+    let mut j = 0;
+    while j < 5 {
+        if expected_assets[j] == expected_asset {
+            expected_total += b256_to_u64(expected_amounts[j]);
+        }
+        j += 1;
+    }
+
+    let mut i = 0;
+    while i < 3 {
+        if agg_assets[i] == expected_asset {
+            return b256_to_u64(agg_amounts[i]) == expected_total;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+
+/// The offsets in bytes of the configurables of the owner predicate bytecode.
+pub struct OwnerPredicateLayout {
+    pub owner: u64,
+    pub verifier: u64,
+    pub fee_recipient: u64,
+    pub fee_bps: u64,
+}
+
+/// The configurables an owner predicate bytecode is bound to.
+pub struct OwnerPredicateBinding {
+    /// the owner EVM address, left padded to 32 bytes.
+    pub owner: b256,
+    /// the contract id of the SwapVerifier that must be an input of an intent spend.
+    pub verifier: b256,
+    pub fee_recipient: b256,
+    pub fee_bps: u64,
+}
+
+/// Reads the configurables from the bytecode of an owner predicate.
+///
+/// The owner predicate is compiled once, the owner EVM address, the SwapVerifier id and the
+/// protocol fee are configurables at fixed offsets of the bytecode. With those zeroed, the
+/// bytecode of every owner predicate hashes to the same template hash, which shows the input
+/// is an owner predicate and not some other bytecode that happens to hold the address at
+/// that offset.
+///
+/// # Arguments
+///
+/// * `predicate`: The bytecode of the predicate spending the input.
+/// * `layout`: The offsets of the configurables in the bytecode.
+/// * `template_hash`: The sha256 of the owner predicate bytecode with zero configurables.
+///
+/// # Returns
+///
+/// * `Option<OwnerPredicateBinding>`: The configurables, None if the bytecode is not an
+///                                    owner predicate.
+///
+pub fn read_owner_predicate(
+    predicate: Bytes,
+    layout: OwnerPredicateLayout,
+    template_hash: b256,
+) -> Option<OwnerPredicateBinding> {
+    let len = predicate.len();
+    if len < layout.owner + 32 || len < layout.verifier + 32 || len < layout.fee_recipient + 32 || len < layout.fee_bps + 8 {
+        return None;
+    }
+
+    let mut template = Bytes::new();
+    let mut owner = Bytes::new();
+    let mut verifier = Bytes::new();
+    let mut fee_recipient = Bytes::new();
+    let mut fee_bps = Bytes::new();
+    let mut i = 0;
+    while i < len {
+        let byte = predicate.get(i).unwrap();
+        if i >= layout.owner && i < layout.owner + 32 {
+            owner.push(byte);
+            template.push(0u8);
+        } else if i >= layout.verifier && i < layout.verifier + 32 {
+            verifier.push(byte);
+            template.push(0u8);
+        } else if i >= layout.fee_recipient && i < layout.fee_recipient + 32 {
+            fee_recipient.push(byte);
+            template.push(0u8);
+        } else if i >= layout.fee_bps && i < layout.fee_bps + 8 {
+            fee_bps.push(byte);
+            template.push(0u8);
+        } else {
+            template.push(byte);
+        }
+        i += 1;
+    }
+
+    if sha256(template) != template_hash {
+        return None;
+    }
+    Some(OwnerPredicateBinding {
+        owner: b256::from_be_bytes(owner),
+        verifier: b256::from_be_bytes(verifier),
+        fee_recipient: b256::from_be_bytes(fee_recipient),
+        fee_bps: u64::from_be_bytes(fee_bps),
+    })
+}
+
+/// Calculates the protocol fee owed on the total of the intent input amounts, which must all be
+/// of the fee asset: the fee of an intent with inputs of several assets is not defined.
+///
+/// # Arguments
+///
+/// * `expected_assets`: An array of 5 b256 values representing the intent input assets.
+/// * `expected_amounts`: An array of 5 b256 values representing the intent input amounts.
+/// * `fee_asset`: The asset ID the fee is paid in, compared as given (marked or not).
+/// * `fee_bps`: The fee rate in basis points (1 bps = 0.01%).
+///
+/// # Returns
+///
+/// * `Option<u64>`: The fee amount, rounded down. Split into quotient and remainder so the
+///          multiplication can not overflow. None if an input is of another asset, or the
+///          total of the input amounts overflows.
+///
+pub fn calc_fee_amount(
+    expected_assets: [b256; 5],
+    expected_amounts: [b256; 5],
+    fee_asset: b256,
+    fee_bps: u64,
+) -> Option<u64> {
+    let mut total: u64 = 0;
+    let mut i = 0;
+    while i < 5 {
+        let amount = b256_to_u64(expected_amounts[i]);
+        if amount != 0 {
+            if expected_assets[i] != fee_asset || amount > u64::max() - total {
+                return None;
+            }
+            total += amount;
+        }
+        i += 1;
+    }
+    Some((total / 10000) * fee_bps + ((total % 10000) * fee_bps) / 10000)
+}
+
+
+/// Verifies that the protocol fee is paid to the fee recipient.
+///
+/// # Arguments
+///
+/// * `tx_outputs`: Vector of transaction coin outputs, with the receiver stored as the owner.
+/// * `fee_asset`: The asset ID the fee is paid in (the intent input asset).
+/// * `fee_amount`: The minimum amount owed to the fee recipient.
+/// * `fee_recipient`: The address of the fee recipient.
+///
+/// # Returns
+///
+/// * `bool`: Returns `true` if the coin outputs of the fee asset sent to the fee recipient
+///           add up to at least the fee amount, `false` otherwise.
+///
+pub fn verify_fee_output(
+    tx_outputs: Vec<InpOut>,
+    fee_asset: b256,
+    fee_amount: u64,
+    fee_recipient: b256,
+) -> bool {
+    let mut paid: u64 = 0;
+    let mut i = 0;
+    while i < tx_outputs.len() {
+        let output = tx_outputs.get(i).unwrap();
+        if output.assetid == fee_asset {
+            match output.owner {
+                Some(to) => {
+                    let to: b256 = to.into();
+                    if to == fee_recipient {
+                        paid += output.amount.unwrap_or(0);
+                    }
+                },
+                None => {},
+            }
+        }
+        i += 1;
+    }
+
+    paid >= fee_amount
+}
+
+
 struct AssetProcessingResult {
     pub agg_assets: [b256; 3],
     pub agg_amounts: [b256; 3],
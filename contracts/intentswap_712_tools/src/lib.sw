@@ -4,3 +4,4 @@ pub mod transaction_utils;
 pub mod generalized_swap;
 pub mod test_genswap;
 pub mod io_utils;
+pub mod test_io_utils;
@@ -37,10 +37,10 @@ use helpers::hex::b256_to_hex;
 
 
 const TEST_CONST_DOMAIN_SEP_HASH: b256 = 0x47f9d229f5cbfdd9148072eb4928dc4f52479d3ea9d4e578743f6c51b959f445;
-const TEST_CONST_TYPE_HASH: b256 = 0x2aba27466153a0e63c25c715bd2cd4807b7f9c8ca6938eb1c37f849c9c6c9fa9;
-const TEST_CONST_STRUCT_HASH: b256 = 0xf265f6e2e330157d3a26de8f4fc1bb89065890cea9a1e868ed0ffa0b0f085df4;
-const TEST_CONST_ENCODED_HASH: b256 = 0xa73aaefb000a09cb89eecc08a6d17cc69a6741d6a8996aff2c48d4475030b2a1;
-const TEST_CONST_EVM_SINGER: b256 = 0x000000000000000000000000333339d42a89028ee29a9e9f4822e651bac7ba14;
+const TEST_CONST_TYPE_HASH: b256 = 0x8bcc15fb268f69e9bafa1c37e06e15fc77fed40ca81310ba8332a34003f2b34b;
+const TEST_CONST_STRUCT_HASH: b256 = 0x14343dfc07c18b0471c3fb068137d859faef3aac1ea1bd135349a83f41cb7135;
+const TEST_CONST_ENCODED_HASH: b256 = 0x043c311c150c000edd6522be0f4a25706a4fb53a3114e1c435893575596142bd;
+const TEST_CONST_EVM_SINGER: b256 = 0x000000000000000000000000222227f7e08997ee7457a0a772e417ca5462906d;
 
 
 // forc test domain_hash --logs
@@ -87,7 +87,7 @@ fn type_precalc(){
 #[test]
 fn struct_hash(){
 
-    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance) = get_setup_tx_params();
+    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance, recipient) = get_setup_tx_params();
     let tx = GenIO::new(
         [some_asset_in, some_asset_in, some_asset_in, some_asset_in, some_asset_in],
         [utxoid_in, utxoid_in, utxoid_in, utxoid_in, utxoid_in],
@@ -95,10 +95,11 @@ fn struct_hash(){
         asset_out,
         amount_out,
         tolerance,
+        recipient,
     );
 
     let struct_hash = tx.struct_hash();
-    // encoded struct: 2aba27466153a0e63c25c715bd2cd4807b7f9c8ca6938eb1c37f849c9c6c9fa9dfded4ed5ac76ba7379cfe7b3b0f53e768dca8d45a34854e649cfc3c18cbd9cda15d0f6fa074184fb0be73a3e2bda1b9463822845a24ab337720ad45fcf83e62fb660c034c9014d94d76f73c3a59458efd09c6bccfc3e6496bd9d98794dce3b50202020202020202020202020202020202020202020202020202020202020202000000000000000000000000000000000000000000000000000000007735940000000000000000000000000000000000000000000000000000000000000000fa
+    // encoded struct: 8bcc15fb268f69e9bafa1c37e06e15fc77fed40ca81310ba8332a34003f2b34bdfded4ed5ac76ba7379cfe7b3b0f53e768dca8d45a34854e649cfc3c18cbd9cda15d0f6fa074184fb0be73a3e2bda1b9463822845a24ab337720ad45fcf83e62fb660c034c9014d94d76f73c3a59458efd09c6bccfc3e6496bd9d98794dce3b50202020202020202020202020202020202020202020202020202020202020202000000000000000000000000000000000000000000000000000000007735940000000000000000000000000000000000000000000000000000000000000000fa0303030303030303030303030303030303030303030303030303030303030303
 
     log(struct_hash);
     let expected_struct_hash = TEST_CONST_STRUCT_HASH;
@@ -110,7 +111,7 @@ fn struct_hash(){
 #[test]
 fn hash_encode712(){
 
-    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance) = get_setup_tx_params();
+    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance, recipient) = get_setup_tx_params();
     let payload = (
         EIP712Domain::new(),
         GenIO::new(
@@ -119,7 +120,8 @@ fn hash_encode712(){
             [some_amount_in, some_amount_in, some_amount_in, some_amount_in, some_amount_in],
             asset_out,
             amount_out,
-            tolerance
+            tolerance,
+            recipient,
         )
     );
     let encoded_hash = match payload.encode_eip712() {
@@ -138,8 +140,8 @@ fn hash_encode712(){
 fn recover_signer_from_712tx(){
     // receive as params:
     // amount, receiver, compact signature.
-    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance) = get_setup_tx_params();
-    let mut compactsig_hex_string = String::from_ascii_str("13caf5acee7537d7c791c5854d4011257952d96d47f52f397ee9b90576e35449294c0a9c53fbeccf7319ef4ed1470d2dfddf9b803cb9a67e885e5a3851cfd9e0");
+    let (some_asset_in, some_amount_in, utxoid_in, asset_out, amount_out, tolerance, recipient) = get_setup_tx_params();
+    let mut compactsig_hex_string = String::from_ascii_str("bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020dd66275f5913bab0c328d51799ae20b67f0592c21c5cecf9da9a48496fe1911c6");
 
     let compactsig_bytes = hex_string_to_bytes(compactsig_hex_string).unwrap();
     let mut ptr: u64 = 0;
@@ -161,6 +163,7 @@ fn recover_signer_from_712tx(){
             asset_out,
             amount_out,
             tolerance,
+            recipient,
         )
     );
     let encoded_hash = match payload.encode_eip712() {
@@ -175,14 +178,15 @@ fn recover_signer_from_712tx(){
     assert(recovered_signer == expected_signer);
 }
 
-fn get_setup_tx_params() -> (b256, b256, b256, b256, b256, b256) {
+fn get_setup_tx_params() -> (b256, b256, b256, b256, b256, b256, b256) {
     let asset_in: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000;
     let utxoid_in: b256 = 0x0101010101010101010101010101010101010101010101010101010101010101;
     let amount_in: b256 = 0x000000000000000000000000000000000000000000000000000000003b9aca00; // 1 ETH, as U256 on Fuel, 9 decimals.
     let asset_out: b256 = 0x0202020202020202020202020202020202020202020202020202020202020202;
     let amount_out: b256 = 0x0000000000000000000000000000000000000000000000000000000077359400;  // 2x, make the price 0.5 ETH per
     let tolerance_bps: u64 = 250;  // 2.5% tolerance, convert to b256 big-endian
-    (asset_in, amount_in, utxoid_in, asset_out, amount_out, to_b256((0, 0, 0, tolerance_bps)))
+    let recipient: b256 = 0x0303030303030303030303030303030303030303030303030303030303030303;
+    (asset_in, amount_in, utxoid_in, asset_out, amount_out, to_b256((0, 0, 0, tolerance_bps)), recipient)
 }
 
 
@@ -243,6 +247,7 @@ fn struct_hash_blah1(){
         intent_output_asset,
         intent_output_amount,
         tolerance_bps,
+        sender,
     );
 
     let struct_hash = tx.struct_hash();
@@ -0,0 +1,307 @@
+library;
+
+use std::{
+    bytes::Bytes,
+    hash::{Hash, sha256},
+    option::Option,
+};
+
+use helpers::numeric_utils::to_b256;
+
+use ::io_utils::{
+    InpOut,
+    verify_output_recipient,
+    calc_fee_amount,
+    verify_fee_output,
+    is_gas_sponsor_input,
+    BASE_ASSET_MARKER,
+    mark_base_asset,
+    mark_intent_assets,
+    unmark_intent_assets,
+    find_intent_sender,
+    filter_inputs_by_owner,
+    verify_exact_input_total,
+    read_owner_predicate,
+    OwnerPredicateLayout,
+};
+
+
+const TEST_CONST_ASSET_IN: b256 = 0x0101010101010101010101010101010101010101010101010101010101010101;
+const TEST_CONST_ASSET_OUT: b256 = 0x0202020202020202020202020202020202020202020202020202020202020202;
+const TEST_CONST_RECIPIENT: b256 = 0x0303030303030303030303030303030303030303030303030303030303030303;
+const TEST_CONST_SOLVER: b256 = 0x0404040404040404040404040404040404040404040404040404040404040404;
+const TEST_CONST_FEE_RECIPIENT: b256 = 0x0505050505050505050505050505050505050505050505050505050505050505;
+const TEST_CONST_FEE_BPS: u64 = 30;
+const TEST_CONST_BASE_ASSET: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000;
+const TEST_CONST_GAS_SPONSOR: b256 = 0x0606060606060606060606060606060606060606060606060606060606060606;
+const TEST_CONST_SENDER: b256 = 0x0707070707070707070707070707070707070707070707070707070707070707;
+const TEST_CONST_VERIFIER: b256 = 0x0808080808080808080808080808080808080808080808080808080808080808;
+const TEST_CONST_UTXO_1: b256 = 0x1111111111111111111111111111111111111111111111111111111111111111;
+const TEST_CONST_UTXO_2: b256 = 0x2222222222222222222222222222222222222222222222222222222222222222;
+const TEST_CONST_UTXO_3: b256 = 0x3333333333333333333333333333333333333333333333333333333333333333;
+
+
+// forc test output_to_recipient --logs
+// the output asset is sent to the signed recipient, the input asset goes to the solver.
+#[test]
+fn output_to_recipient() {
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 2000000000, TEST_CONST_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000, TEST_CONST_SOLVER));
+
+    assert(verify_output_recipient(tx_outputs, TEST_CONST_ASSET_OUT, TEST_CONST_RECIPIENT));
+}
+
+// forc test output_to_other_address --logs
+// a solver can not redirect the output asset to any address other than the recipient.
+#[test]
+fn output_to_other_address() {
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 2000000000, TEST_CONST_SOLVER));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000, TEST_CONST_SOLVER));
+
+    assert(!verify_output_recipient(tx_outputs, TEST_CONST_ASSET_OUT, TEST_CONST_RECIPIENT));
+}
+
+// forc test output_split_to_other_address --logs
+// splitting the output asset between the recipient and another address fails.
+#[test]
+fn output_split_to_other_address() {
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 1950000000, TEST_CONST_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 50000000, TEST_CONST_SOLVER));
+
+    assert(!verify_output_recipient(tx_outputs, TEST_CONST_ASSET_OUT, TEST_CONST_RECIPIENT));
+}
+
+// forc test output_missing --logs
+// no output of the output asset at all fails.
+#[test]
+fn output_missing() {
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000, TEST_CONST_SOLVER));
+
+    assert(!verify_output_recipient(tx_outputs, TEST_CONST_ASSET_OUT, TEST_CONST_RECIPIENT));
+}
+
+// forc test fee_amount --logs
+// 30 bps of the total intent input amount (1520000000), rounded down.
+#[test]
+fn fee_amount() {
+    let fee = calc_fee_amount(get_intent_input_assets(), get_intent_input_amounts(), TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).unwrap();
+    log(fee);
+    assert(fee == 4560000);
+
+    // the fee on an amount close to u64::max() does not overflow.
+    let large_amounts = [to_b256((0, 0, 0, u64::max())), b256::zero(), b256::zero(), b256::zero(), b256::zero()];
+    let fee = calc_fee_amount(get_intent_input_assets(), large_amounts, TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).unwrap();
+    assert(fee == 55340232221128654);
+}
+
+// forc test fee_amount_mixed_assets --logs
+// the fee is not defined for inputs of another asset than the fee asset.
+#[test]
+fn fee_amount_mixed_assets() {
+    let mixed_assets = [TEST_CONST_ASSET_IN, TEST_CONST_ASSET_OUT, b256::zero(), b256::zero(), b256::zero()];
+    assert(calc_fee_amount(mixed_assets, get_intent_input_amounts(), TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).is_none());
+    assert(calc_fee_amount(get_intent_input_assets(), get_intent_input_amounts(), TEST_CONST_ASSET_OUT, TEST_CONST_FEE_BPS).is_none());
+}
+
+// forc test fee_amount_overflow --logs
+// input amounts whose total overflows u64 have no fee.
+#[test]
+fn fee_amount_overflow() {
+    let overflow_amounts = [to_b256((0, 0, 0, u64::max())), to_b256((0, 0, 0, 1)), b256::zero(), b256::zero(), b256::zero()];
+    assert(calc_fee_amount(get_intent_input_assets(), overflow_amounts, TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).is_none());
+}
+
+// forc test fee_paid --logs
+// the fee output pays the fee recipient the full fee in the input asset.
+#[test]
+fn fee_paid() {
+    let fee = calc_fee_amount(get_intent_input_assets(), get_intent_input_amounts(), TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).unwrap();
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 2000000000, TEST_CONST_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000 - fee, TEST_CONST_SOLVER));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, fee, TEST_CONST_FEE_RECIPIENT));
+
+    assert(verify_fee_output(tx_outputs, TEST_CONST_ASSET_IN, fee, TEST_CONST_FEE_RECIPIENT));
+}
+
+// forc test fee_missing --logs
+// a transaction without the fee output is rejected.
+#[test]
+fn fee_missing() {
+    let fee = calc_fee_amount(get_intent_input_assets(), get_intent_input_amounts(), TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).unwrap();
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 2000000000, TEST_CONST_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000, TEST_CONST_SOLVER));
+
+    assert(!verify_fee_output(tx_outputs, TEST_CONST_ASSET_IN, fee, TEST_CONST_FEE_RECIPIENT));
+}
+
+// forc test fee_underpaid --logs
+// a fee output below the fee amount, or in the wrong asset, is rejected.
+#[test]
+fn fee_underpaid() {
+    let fee = calc_fee_amount(get_intent_input_assets(), get_intent_input_amounts(), TEST_CONST_ASSET_IN, TEST_CONST_FEE_BPS).unwrap();
+    let mut tx_outputs: Vec<InpOut> = Vec::new();
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, 2000000000, TEST_CONST_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, 1520000000 - fee + 1, TEST_CONST_SOLVER));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_IN, fee - 1, TEST_CONST_FEE_RECIPIENT));
+    tx_outputs.push(coin_output(TEST_CONST_ASSET_OUT, fee, TEST_CONST_FEE_RECIPIENT));
+
+    assert(!verify_fee_output(tx_outputs, TEST_CONST_ASSET_IN, fee, TEST_CONST_FEE_RECIPIENT));
+}
+
+// forc test gas_sponsor_input --logs
+// only the base asset inputs owned by the gas sponsor are left out of the swap.
+#[test]
+fn gas_sponsor_input() {
+    let sponsor = Some(Address::from(TEST_CONST_GAS_SPONSOR));
+    let solver = Some(Address::from(TEST_CONST_SOLVER));
+
+    assert(is_gas_sponsor_input(TEST_CONST_BASE_ASSET, sponsor, TEST_CONST_BASE_ASSET, TEST_CONST_GAS_SPONSOR));
+    // the sponsor's non base asset inputs, and other owners base asset inputs are counted.
+    assert(!is_gas_sponsor_input(TEST_CONST_ASSET_OUT, sponsor, TEST_CONST_BASE_ASSET, TEST_CONST_GAS_SPONSOR));
+    assert(!is_gas_sponsor_input(TEST_CONST_BASE_ASSET, solver, TEST_CONST_BASE_ASSET, TEST_CONST_GAS_SPONSOR));
+    // no sponsor configured.
+    assert(!is_gas_sponsor_input(TEST_CONST_BASE_ASSET, sponsor, TEST_CONST_BASE_ASSET, b256::zero()));
+}
+
+// forc test base_asset_marker --logs
+// a zero (base) input asset is marked only in the intent slots in use, and unmarked back for the hash.
+#[test]
+fn base_asset_marker() {
+    let assets = [TEST_CONST_BASE_ASSET, TEST_CONST_BASE_ASSET, b256::zero(), b256::zero(), b256::zero()];
+    let marked = mark_intent_assets(assets, get_intent_input_amounts());
+
+    assert(marked[0] == BASE_ASSET_MARKER);
+    assert(marked[1] == BASE_ASSET_MARKER);
+    assert(marked[2] == b256::zero());
+    assert(mark_base_asset(TEST_CONST_ASSET_IN) == TEST_CONST_ASSET_IN);
+
+    let unmarked = unmark_intent_assets(marked);
+    assert(unmarked[0] == TEST_CONST_BASE_ASSET);
+    assert(unmarked[1] == TEST_CONST_BASE_ASSET);
+    assert(unmarked[2] == b256::zero());
+}
+
+// forc test intent_sender_inputs --logs
+// the sender owns the first intent utxo, the solver's base asset gas input is not a swap input.
+#[test]
+fn intent_sender_inputs() {
+    let mut tx_inputs: Vec<InpOut> = Vec::new();
+    tx_inputs.push(coin_input(BASE_ASSET_MARKER, 600000000, TEST_CONST_UTXO_3, TEST_CONST_SOLVER));
+    tx_inputs.push(coin_input(BASE_ASSET_MARKER, 1000000000, TEST_CONST_UTXO_1, TEST_CONST_SENDER));
+    tx_inputs.push(coin_input(BASE_ASSET_MARKER, 520000000, TEST_CONST_UTXO_2, TEST_CONST_SENDER));
+
+    let utxos = [TEST_CONST_UTXO_1, TEST_CONST_UTXO_2, b256::zero(), b256::zero(), b256::zero()];
+    let sender = find_intent_sender(tx_inputs, utxos).unwrap();
+    assert(sender == Address::from(TEST_CONST_SENDER));
+
+    let sender_inputs = filter_inputs_by_owner(tx_inputs, sender);
+    assert(sender_inputs.len() == 2);
+
+    // an intent utxo that is not spent in the tx has no sender.
+    let utxos = [TEST_CONST_ASSET_IN, b256::zero(), b256::zero(), b256::zero(), b256::zero()];
+    assert(find_intent_sender(tx_inputs, utxos).is_none());
+}
+
+// forc test exact_input_total --logs
+// the sender's base asset inputs must be spent in full, there is no change back to the sender.
+#[test]
+fn exact_input_total() {
+    let assets = [BASE_ASSET_MARKER, BASE_ASSET_MARKER, b256::zero(), b256::zero(), b256::zero()];
+    let agg_assets = [BASE_ASSET_MARKER, b256::zero(), b256::zero()];
+
+    let agg_amounts = [to_b256((0, 0, 0, 1520000000)), b256::zero(), b256::zero()];
+    assert(verify_exact_input_total(agg_assets, agg_amounts, BASE_ASSET_MARKER, assets, get_intent_input_amounts()));
+
+    let agg_amounts = [to_b256((0, 0, 0, 1520000001)), b256::zero(), b256::zero()];
+    assert(!verify_exact_input_total(agg_assets, agg_amounts, BASE_ASSET_MARKER, assets, get_intent_input_amounts()));
+}
+
+// forc test predicate_owner --logs
+// the owner and its binding are read from an owner predicate bytecode, other bytecode is not
+// an owner predicate.
+#[test]
+fn predicate_owner() {
+    let layout = test_layout(8);
+    let template = predicate_bytecode(0u8, 0u8, 0u8, layout);
+    let template_hash = sha256(template);
+
+    let binding = read_owner_predicate(predicate_bytecode(7u8, 8u8, 5u8, layout), layout, template_hash).unwrap();
+    assert(binding.owner == TEST_CONST_SENDER);
+    assert(binding.verifier == TEST_CONST_VERIFIER);
+    assert(binding.fee_recipient == TEST_CONST_FEE_RECIPIENT);
+    assert(binding.fee_bps == 0x0505050505050505);
+
+    // the same owner at another offset is a different bytecode.
+    let other = read_owner_predicate(predicate_bytecode(7u8, 8u8, 5u8, test_layout(9)), layout, template_hash);
+    assert(other.is_none());
+
+    // bytecode shorter than the configurables.
+    assert(read_owner_predicate(Bytes::new(), layout, template_hash).is_none());
+}
+
+fn get_intent_input_assets() -> [b256; 5] {
+    [TEST_CONST_ASSET_IN, TEST_CONST_ASSET_IN, b256::zero(), b256::zero(), b256::zero()]
+}
+
+fn get_intent_input_amounts() -> [b256; 5] {
+    [
+        to_b256((0, 0, 0, 1000000000)),
+        to_b256((0, 0, 0, 520000000)),
+        b256::zero(), b256::zero(), b256::zero()
+    ]
+}
+
+fn coin_output(asset: b256, amount: u64, to: b256) -> InpOut {
+    InpOut::new(
+        asset,
+        Some(amount),
+        None,
+        None,
+        Some(Address::from(to))
+    )
+}
+
+fn coin_input(asset: b256, amount: u64, utxo: b256, owner: b256) -> InpOut {
+    InpOut::new(
+        asset,
+        Some(amount),
+        None,
+        Some(utxo),
+        Some(Address::from(owner))
+    )
+}
+
+fn test_layout(owner_offset: u64) -> OwnerPredicateLayout {
+    OwnerPredicateLayout {
+        owner: owner_offset,
+        verifier: owner_offset + 32,
+        fee_recipient: owner_offset + 64,
+        fee_bps: owner_offset + 96,
+    }
+}
+
+// a stand in bytecode of 120 bytes, with the owner, verifier and fee bytes at the offsets of
+// the layout.
+fn predicate_bytecode(owner_byte: u8, verifier_byte: u8, fee_byte: u8, layout: OwnerPredicateLayout) -> Bytes {
+    let mut bytecode = Bytes::new();
+    let mut i = 0;
+    while i < 120 {
+        if i >= layout.owner && i < layout.owner + 32 {
+            bytecode.push(owner_byte);
+        } else if i >= layout.verifier && i < layout.verifier + 32 {
+            bytecode.push(verifier_byte);
+        } else if i >= layout.fee_recipient && i < layout.fee_bps + 8 {
+            bytecode.push(fee_byte);
+        } else {
+            bytecode.push(0xAAu8);
+        }
+        i += 1;
+    }
+    bytecode
+}
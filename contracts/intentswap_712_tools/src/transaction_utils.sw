@@ -1,9 +1,12 @@
 library;
 
 use std::{
+    bytes::Bytes,
+    hash::{Hash, sha256},
     inputs::{
         GTF_INPUT_COIN_AMOUNT,
         GTF_INPUT_COIN_ASSET_ID,
+        input_coin_owner,
         input_type,
         Input,
     },
@@ -32,6 +35,17 @@ use std::{
 const GTF_INPUT_TYPE = 0x200;   // sway 0.63.1
 
 const GTF_INPUT_COIN_TX_ID = 0x201;
+const GTF_INPUT_COIN_PREDICATE_DATA_LENGTH = 0x20A;
+const GTF_INPUT_COIN_PREDICATE_DATA = 0x20C;
+
+const GTF_INPUT_MESSAGE_RECIPIENT = 0x241;
+const GTF_INPUT_MESSAGE_AMOUNT = 0x242;
+const GTF_INPUT_MESSAGE_NONCE = 0x243;
+const GTF_INPUT_MESSAGE_DATA_LENGTH = 0x245;
+const GTF_INPUT_MESSAGE_PREDICATE_DATA_LENGTH = 0x247;
+const GTF_INPUT_MESSAGE_PREDICATE_DATA = 0x24A;
+
+const GTF_INPUT_CONTRACT_ID = 0x225;
 
 const OUTPUT_TYPE_COIN = 0u64;
 const OUTPUT_TYPE_CHANGE = 3u64;
@@ -73,6 +87,14 @@ pub fn verify_input_coin(index: u64) -> bool {
         _ => false,
     }
 }
+/// Verifies an input at the given index is a Message input without data (a message coin).
+/// Messages with data are not spendable balance, so they can not be swapped.
+pub fn verify_input_message_coin(index: u64) -> bool {
+    match input_type(index) {
+        Some(Input::Message) => __gtf::<u64>(index, GTF_INPUT_MESSAGE_DATA_LENGTH) == 0,
+        _ => false,
+    }
+}
 /// Verifies an input at the given index is a Contract input
 pub fn verify_input_contract(index: u64) -> bool {
     match input_type(index) {
@@ -81,6 +103,24 @@ pub fn verify_input_contract(index: u64) -> bool {
     }
 }
 
+/// Get the contract id of a contract input
+pub fn input_contract_id(index: u64) -> b256 {
+    __gtf::<b256>(index, GTF_INPUT_CONTRACT_ID)
+}
+
+/// Verifies the contract is an input of the tx, i.e. the script can call it.
+pub fn has_contract_input(contract_id: b256) -> bool {
+    let count = input_count();
+    let mut i = 0;
+    while i < count {
+        if verify_input_contract(i) && input_contract_id(i) == contract_id {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
 /// Get the asset ID of a coin input
 pub fn input_coin_asset_id(index: u64) -> b256 {
     __gtf::<b256>(index, GTF_INPUT_COIN_ASSET_ID)
@@ -140,4 +180,64 @@ pub fn output_coin_to(index: u64) -> b256 {
 /// return the utxo id from the coin input at index.
 pub fn input_txn_hash(index: u64) -> b256 {
      __gtf::<b256>(index, GTF_INPUT_COIN_TX_ID)
-}
\ No newline at end of file
+}
+
+/// return the nonce from the message input at index, the unique id of the message.
+pub fn input_message_nonce(index: u64) -> b256 {
+     __gtf::<b256>(index, GTF_INPUT_MESSAGE_NONCE)
+}
+
+/// Get the amount of a message input, always in the base asset.
+pub fn input_message_amount(index: u64) -> u64 {
+    __gtf::<u64>(index, GTF_INPUT_MESSAGE_AMOUNT)
+}
+
+/// Get the recipient (the owner) of a message input.
+pub fn input_message_recipient(index: u64) -> Address {
+    Address::from(__gtf::<b256>(index, GTF_INPUT_MESSAGE_RECIPIENT))
+}
+
+/// Get the owner of a coin input or the recipient of a message coin input, None for other inputs.
+pub fn input_owner(index: u64) -> Option<Address> {
+    if verify_input_coin(index) {
+        input_coin_owner(index)
+    } else if verify_input_message_coin(index) {
+        Some(input_message_recipient(index))
+    } else {
+        None
+    }
+}
+
+/// Get the sha256 of the predicate data of a coin or message coin input, None for other inputs.
+pub fn input_predicate_data_hash(index: u64) -> Option<b256> {
+    let (data, length) = if verify_input_coin(index) {
+        (
+            __gtf::<raw_ptr>(index, GTF_INPUT_COIN_PREDICATE_DATA),
+            __gtf::<u64>(index, GTF_INPUT_COIN_PREDICATE_DATA_LENGTH),
+        )
+    } else if verify_input_message_coin(index) {
+        (
+            __gtf::<raw_ptr>(index, GTF_INPUT_MESSAGE_PREDICATE_DATA),
+            __gtf::<u64>(index, GTF_INPUT_MESSAGE_PREDICATE_DATA_LENGTH),
+        )
+    } else {
+        return None;
+    };
+    Some(sha256(Bytes::from(raw_slice::from_parts::<u8>(data, length))))
+}
+
+/// Find the index of the coin input (by utxo id) or message input (by nonce) with the given id.
+pub fn find_input_index_by_id(utxo_id: b256) -> Option<u64> {
+    let count = input_count();
+    let mut i = 0;
+    while i < count {
+        if verify_input_coin(i) && input_txn_hash(i) == utxo_id {
+            return Some(i);
+        }
+        if verify_input_message_coin(i) && input_message_nonce(i) == utxo_id {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
@@ -0,0 +1,268 @@
+predicate;
+
+use std::{
+    b512::B512,
+    tx::{
+        tx_id,
+        tx_witness_data,
+    },
+    vm::evm::{
+        ecr::ec_recover_evm_address,
+        evm_address::EvmAddress,
+    },
+    bytes::Bytes,
+    outputs::output_count,
+    string::String,
+};
+use std::bytes_conversions::b256::*;
+
+use helpers::{
+    general_helpers::{
+        hex_string_to_bytes,
+        bytes_read_b256,
+        extend,
+        hash_bytes,
+    },
+    numeric_utils::b256_to_u64,
+};
+use intentswap_712_tools::{
+    generalized_swap::{
+        GenIO, Intent, EIP712Domain, Eip712,
+    },
+    transaction_utils::{
+        verify_input_coin,
+        verify_input_message_coin,
+        input_txn_hash,
+        input_message_nonce,
+        input_coin_asset_id,
+        input_coin_amount,
+        input_message_amount,
+        has_contract_input,
+        input_count,
+        input_owner,
+        input_predicate_data_hash,
+        verify_output_coin,
+        output_coin_asset_id,
+        output_coin_amount,
+        output_coin_to,
+    },
+    io_utils::calc_fee_amount,
+};
+
+configurable {
+    // the EVM address (left padded to 32 bytes) of the owner of the predicate.
+    OWNER_EVM_ADDRESS: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the id of the SwapVerifier that must be an input of an intent spend.
+    SWAP_VERIFIER: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the protocol fee of the SwapVerifier, paid by an intent spend. Zero disables the fee.
+    FEE_RECIPIENT: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    FEE_BPS: u64 = 0,
+}
+
+/// How the owner authorizes spending an input of the predicate.
+enum OwnerSpend {
+    /// An EIP-712 intent signed by the owner, which lists the input.
+    Intent: Intent,
+    /// A plain transfer, the owner's personal_sign of the tx id is the witness at this index.
+    Transfer: u64,
+}
+
+/// The owner's predicate wallet, controlled by an EVM signature.
+fn main(spend: OwnerSpend) -> bool {
+    match spend {
+        OwnerSpend::Intent(intent) => verify_intent_spend(intent),
+        OwnerSpend::Transfer(witness_index) => verify_transfer_spend(witness_index),
+    }
+}
+
+/// The intent is signed by the owner, the SwapVerifier is an input of the tx, the input being
+/// verified is one of the intent inputs with its signed asset and amount, every input of the
+/// predicate in the tx is spent for this intent, the tx pays the recipient at least the signed
+/// output amount less the tolerance, and the protocol fee is paid.
+///
+/// The verifier checks the whole swap (inputs, change, fee) in `validate_solution`, but the
+/// predicate can not see that the script calls it. These checks keep the owner's inputs safe
+/// on their own, in a tx running any script.
+fn verify_intent_spend(intent: Intent) -> bool {
+    // the verifier binds the sender to this predicate, it must be the owner.
+    if intent.sender != OWNER_EVM_ADDRESS {
+        return false;
+    }
+    // the verifier reads this predicate's SWAP_VERIFIER, but only runs if the script calls it,
+    // which an input of the contract does not prove.
+    if !has_contract_input(SWAP_VERIFIER) {
+        return false;
+    }
+
+    let payload = (
+        EIP712Domain::new(),
+        GenIO::new(
+            intent.io.inputassets,
+            intent.io.inpututxoids,
+            intent.io.inputamounts,
+            intent.io.outputasset,
+            intent.io.outputamount,
+            intent.io.tolerance,
+            intent.io.recipient,
+        ),
+    );
+    let encoded_hash = match payload.encode_eip712() {
+        Some(hash) => hash,
+        None => return false,
+    };
+    if !signed_by_owner(intent.compsig, encoded_hash) {
+        return false;
+    }
+
+    // the input is listed with exactly its asset and amount, so no change is left unsigned,
+    // and no other intent of the owner is spent in the tx.
+    if !spends_one_intent(intent, predicate_input_index()) {
+        return false;
+    }
+
+    // the recipient is paid at least the lower bound of the output amount.
+    let amount_out = b256_to_u64(intent.io.outputamount);
+    let tolerance_bps = b256_to_u64(intent.io.tolerance);
+    let tolerance_amount = (amount_out / 10000) * tolerance_bps + ((amount_out % 10000) * tolerance_bps) / 10000;
+    let lower_bound = if amount_out > tolerance_amount { amount_out - tolerance_amount } else { 0 };
+
+    if paid_to(intent.io.outputasset, intent.io.recipient) < lower_bound {
+        return false;
+    }
+
+    // the protocol fee is paid in the intent input asset.
+    if FEE_BPS == 0 {
+        return true;
+    }
+    match calc_fee_amount(intent.io.inputassets, intent.io.inputamounts, intent.io.inputassets[0], FEE_BPS) {
+        Some(fee_amount) => paid_to(intent.io.inputassets[0], FEE_RECIPIENT) >= fee_amount,
+        None => false,
+    }
+}
+
+/// Every input of this predicate in the tx has the same predicate data, i.e. the same intent,
+/// so a payment to the recipient or the fee recipient can not also be counted for another
+/// intent of the owner. The input at index is listed in the intent, and spent by no more
+/// inputs than the intent lists it, as the coins of one tx have the same id.
+fn spends_one_intent(intent: Intent, index: u64) -> bool {
+    let (owner, data_hash) = match (input_owner(index), input_predicate_data_hash(index)) {
+        (Some(owner), Some(data_hash)) => (owner, data_hash),
+        _ => return false,
+    };
+    let (input_id, input_asset, input_amount) = match input_id_asset_amount(index) {
+        Some(input) => input,
+        None => return false,
+    };
+
+    let mut listed = 0;
+    let mut i = 0;
+    while i < 5 {
+        if intent.io.inpututxoids[i] != b256::zero() &&
+           intent.io.inpututxoids[i] == input_id &&
+           intent.io.inputassets[i] == input_asset &&
+           b256_to_u64(intent.io.inputamounts[i]) == input_amount {
+            listed += 1;
+        }
+        i += 1;
+    }
+
+    let mut spent = 0;
+    let in_count = input_count();
+    let mut j = 0;
+    while j < in_count {
+        let same_owner = match input_owner(j) {
+            Some(other) => other == owner,
+            None => false,
+        };
+        if same_owner {
+            match input_predicate_data_hash(j) {
+                Some(other_hash) => {
+                    if other_hash != data_hash {
+                        return false;
+                    }
+                },
+                None => return false,
+            }
+            match input_id_asset_amount(j) {
+                Some((id, asset, amount)) => {
+                    if id == input_id && asset == input_asset && amount == input_amount {
+                        spent += 1;
+                    }
+                },
+                None => {},
+            }
+        }
+        j += 1;
+    }
+    listed > 0 && spent <= listed
+}
+
+/// The id, asset and amount of a coin or message coin input.
+fn input_id_asset_amount(index: u64) -> Option<(b256, b256, u64)> {
+    if verify_input_coin(index) {
+        Some((input_txn_hash(index), input_coin_asset_id(index), input_coin_amount(index)))
+    } else if verify_input_message_coin(index) {
+        Some((input_message_nonce(index), AssetId::base().bits(), input_message_amount(index)))
+    } else {
+        None
+    }
+}
+
+/// The total of the coin outputs of an asset to an address.
+fn paid_to(asset: b256, to: b256) -> u64 {
+    let mut paid: u64 = 0;
+    let out_count = output_count().as_u64();
+    let mut j = 0;
+    while j < out_count {
+        if verify_output_coin(j) &&
+           output_coin_asset_id(j).unwrap() == asset &&
+           output_coin_to(j) == to {
+            paid += output_coin_amount(j);
+        }
+        j += 1;
+    }
+    paid
+}
+
+/// The witness is the owner's personal_sign (EIP-191) of the tx id.
+fn verify_transfer_spend(witness_index: u64) -> bool {
+    let compactsig: B512 = match tx_witness_data::<B512>(witness_index) {
+        Some(sig) => sig,
+        None => return false,
+    };
+    // keccak256("\x19Ethereum Signed Message:\n32" ++ tx_id)
+    let mut message = hex_string_to_bytes(
+        String::from_ascii_str("19457468657265756d205369676e6564204d6573736167653a0a3332")
+    ).unwrap();
+    extend(message, tx_id().to_be_bytes(), 32);
+
+    match ec_recover_evm_address(compactsig, hash_bytes(message)) {
+        Ok(signer) => {
+            let signer: b256 = signer.into();
+            signer == OWNER_EVM_ADDRESS
+        },
+        Err(_) => false,
+    }
+}
+
+fn signed_by_owner(compsig: Bytes, digest: b256) -> bool {
+    let (cs_lhs, ptr) = bytes_read_b256(compsig, 0, 32);
+    let (cs_rhs, _ptr) = bytes_read_b256(compsig, ptr, 32);
+    let compactsig = B512::from((cs_lhs, cs_rhs));
+
+    match ec_recover_evm_address(compactsig, digest) {
+        Ok(signer) => {
+            let signer: b256 = signer.into();
+            signer == OWNER_EVM_ADDRESS
+        },
+        Err(_) => false,
+    }
+}
+
+/// The index of the input whose predicate is being verified.
+fn predicate_input_index() -> u64 {
+    asm(r1) {
+        gm r1 i3;
+        r1: u64
+    }
+}
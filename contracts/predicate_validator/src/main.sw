@@ -48,24 +48,44 @@ use intentswap_712_tools::{
         input_coin_amount,
         input_coin_asset_id,
         output_coin_asset_id,
+        output_coin_to,
         verify_input_coin,
         verify_output_coin,
         verify_output_change,
         input_txn_hash,
+        verify_input_message_coin,
+        input_message_nonce,
+        input_message_amount,
+        input_message_recipient,
     },
     io_utils::{
         InpOut,
         process_assets,
         check_utxos,
-        find_input_assets_owner,
         verify_change_output,
+        verify_output_recipient,
+        calc_fee_amount,
+        verify_fee_output,
+        mark_base_asset,
+        mark_intent_assets,
+        unmark_intent_assets,
+        find_intent_sender,
+        filter_inputs_by_owner,
+        verify_exact_input_total,
         reconstruct_intent_lite, ReconstructIntentResult,
     },
 };
 
-const TEST_CONST_EVM_SINGER: b256 = 0x000000000000000000000000222227f7e08997ee7457a0a772e417ca5462906d;
+configurable {
+    // the EVM address (left padded to 32 bytes) of the owner, who signs the intents.
+    OWNER: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    // the protocol fee, paid in the intent input asset to the fee recipient. Zero disables the fee.
+    FEE_RECIPIENT: b256 = 0x0000000000000000000000000000000000000000000000000000000000000000,
+    FEE_BPS: u64 = 0,
+}
 
-//FIXME - Untested.
+/// The intent predicate, spends the owner's inputs for a transaction that fills an intent signed
+/// by the OWNER. The Intent is the predicate data.
 fn main( intent: Intent ) -> bool {
 
     let in_count: u64 = input_count().into();
@@ -80,12 +100,34 @@ fn main( intent: Intent ) -> bool {
     let mut recovered_signer = b256::zero();
     let mut signed_by_sender: bool = false;
 
+    // the input being verified must be one of the intent inputs, the checks below only
+    // cover the inputs the intent lists.
+    let self_index = predicate_input_index();
+    let self_id = if verify_input_coin(self_index) {
+        input_txn_hash(self_index)
+    } else if verify_input_message_coin(self_index) {
+        input_message_nonce(self_index)
+    } else {
+        return false;
+    };
+    let mut self_listed = false;
+    let mut k = 0;
+    while k < 5 {
+        if intent.io.inpututxoids[k] != b256::zero() && intent.io.inpututxoids[k] == self_id {
+            self_listed = true;
+        }
+        k += 1;
+    }
+    if !self_listed {
+        return false;
+    }
+
     let mut i = 0;
     while i < in_count {
         // collect all the input coins
         if verify_input_coin(i) {
             let inp = InpOut::new(
-                input_coin_asset_id(i),
+                mark_base_asset(input_coin_asset_id(i)),
                 Some(input_coin_amount(i)),
                 None,
                 Some(input_txn_hash(i)),
@@ -94,6 +136,17 @@ fn main( intent: Intent ) -> bool {
             tx_inputs.push(inp);
 
         }
+        // collect the message coins, the nonce is the unique id.
+        if verify_input_message_coin(i) {
+            let inp = InpOut::new(
+                mark_base_asset(AssetId::base().bits()),
+                Some(input_message_amount(i)),
+                None,
+                Some(input_message_nonce(i)),
+                Some(input_message_recipient(i))
+            );
+            tx_inputs.push(inp);
+        }
         i += 1;
     }
     let mut j = 0;
@@ -101,11 +154,11 @@ fn main( intent: Intent ) -> bool {
         // collect all the output coins
         if verify_output_coin(j) {
             let outp = InpOut::new(
-                output_coin_asset_id(j).unwrap(),   // from tx_utls, return Option<b256>
+                mark_base_asset(output_coin_asset_id(j).unwrap()),   // from tx_utls, return Option<b256>
                 Some(output_amount(j).unwrap()),
                 None,
                 None,
-                None
+                Some(Address::from(output_coin_to(j)))
             );
             tx_outputs.push(outp);
         }
@@ -114,7 +167,7 @@ fn main( intent: Intent ) -> bool {
         match verify_output_change(j) {
             Some(is_change) => {
                 if is_change {
-                    tx_change_assetid.push(output_asset_id(j).unwrap().into());
+                    tx_change_assetid.push(mark_base_asset(output_asset_id(j).unwrap().into()));
                     tx_change_to.push(output_asset_to(j).unwrap().into());
                 }
             },
@@ -131,11 +184,30 @@ fn main( intent: Intent ) -> bool {
     //reorder the inputs to match the expected utxo set:
     // reorder_inputs(tx_inputs, intent.io.inpututxoids);
 
+    // mark the base asset in the intent, so a zero (base) asset id is not taken as an empty slot.
+    let io_inputassets = mark_intent_assets(intent.io.inputassets, intent.io.inputamounts);
+    let io_outputasset = if intent.io.outputamount != b256::zero() {
+        mark_base_asset(intent.io.outputasset)
+    } else {
+        intent.io.outputasset
+    };
+
+    // the sender owns the first intent utxo, only the sender's inputs are swap inputs.
+    let sender_inputs = match find_intent_sender(tx_inputs, intent.io.inpututxoids) {
+        Some(owner) => {
+            sender = owner.into(); //returned as Option<Address>
+            filter_inputs_by_owner(tx_inputs, owner)
+        },
+        None => {
+            return false;
+        },
+    };
+
     let input_result = match process_assets(
-        tx_inputs,
-        intent.io.inputassets,      // intent input assets
+        sender_inputs,
+        io_inputassets,             // intent input assets
         intent.io.inputamounts,     // intent input amounts
-        intent.io.outputasset,      // intent output assets
+        b256::zero(),               // the output asset is not an input of the sender
         intent.io.outputamount,     // intent output amounts
         tolerance_bps,              // tollerance bps as a u64
         true                        // is inputs?
@@ -150,9 +222,9 @@ fn main( intent: Intent ) -> bool {
     };
     let output_result = match process_assets(
         tx_outputs,
-        intent.io.inputassets,      // intent input assets
+        io_inputassets,             // intent input assets
         intent.io.inputamounts,     // intent input amounts
-        intent.io.outputasset,      // intent output assets
+        io_outputasset,             // intent output assets
         intent.io.outputamount,     // intent output amounts
         tolerance_bps,              // tollerance bps as a u64
         false
@@ -167,31 +239,41 @@ fn main( intent: Intent ) -> bool {
         },
     };
     // check utxos are ok
-    let (utxo_check_result, ordered_utxos, utxo_indices) = check_utxos(tx_inputs, intent.io.inpututxoids);
+    let (utxo_check_result, ordered_utxos, utxo_indices) = check_utxos(sender_inputs, intent.io.inpututxoids);
 
-    // check there is a change output of the asset_in to the sender
-    //
-    // find the tx_input that matches the assetid of input_result.match_asset, record the idx (k)
-    // as you iterate through tx_inputs.
-    // query the tx inputs at idx (k), and find the owner.
-    //
-    match find_input_assets_owner(tx_inputs, input_result.match_asset) {
-        Some(owner) => {
-            sender = owner.into(); //returned as Option<Address>
+    // check there is a change output of the asset_in to the sender, or for the base asset
+    // (where the change output belongs to the gas payer) that the sender's inputs are spent in full.
+    if input_result.match_asset == mark_base_asset(AssetId::base().bits()) {
+        change_ok = verify_exact_input_total(
+            input_result.agg_assets,
+            input_result.agg_amounts,
+            input_result.match_asset,
+            io_inputassets,
+            intent.io.inputamounts,
+        );
+    } else {
+        change_ok = verify_change_output( // use v4
+            tx_change_assetid,
+            tx_change_to,
+            input_result.match_asset,
+            sender,
+        );
+    }
 
-            // see Note 1 below.
-            /*
-            change_ok = verify_change_output( // use v4
-                tx_change_assetid,
-                tx_change_to,
-                input_result.match_asset,
-                sender,
-            );
-            */
-        },
-        None => {
+    // check the output asset is only sent to the recipient signed in the intent.
+    if !verify_output_recipient(tx_outputs, io_outputasset, intent.io.recipient) {
+        return false;
+    }
+
+    // check the protocol fee is paid, in the input asset, to the fee recipient.
+    if FEE_BPS != 0 {
+        let fee_paid = match calc_fee_amount(io_inputassets, intent.io.inputamounts, input_result.match_asset, FEE_BPS) {
+            Some(fee_amount) => verify_fee_output(tx_outputs, input_result.match_asset, fee_amount, FEE_RECIPIENT),
+            None => false,
+        };
+        if !fee_paid {
             return false;
-        },
+        }
     }
 
     //-------------------------------------------
@@ -208,10 +290,10 @@ fn main( intent: Intent ) -> bool {
         // data passed in using reconstruct_intent_lite(). We only use the tx_inputs
         // as tx specific data here. See the contract validator and reconstruct_intent()
         // for true reconstruction mechanism, that takes all (* see Note 2 below) the data obtained from
-        // transaction introspection.
+        // transaction introspection. gas-bench reports the size of the predicate binary.
         //
         match reconstruct_intent_lite(
-            tx_inputs,
+            sender_inputs,
             input_result.agg_assets,
             intent.io.inputamounts,
             intent.io.outputasset,
@@ -229,12 +311,13 @@ fn main( intent: Intent ) -> bool {
                 let payload = (
                     EIP712Domain::new(),
                     GenIO::new(
-                        recon_intent.input_assets,
+                        unmark_intent_assets(recon_intent.input_assets),
                         recon_intent.input_utxos,
                         recon_intent.input_amounts,
                         recon_intent.output_asset,
                         recon_intent.output_amount,
                         intent.io.tolerance,
+                        intent.io.recipient,
                     )
                 );
                 let encoded_hash = match payload.encode_eip712() {
@@ -262,13 +345,23 @@ fn main( intent: Intent ) -> bool {
         // revert code for output failure
         // revert code for utxo failure
         // revert code for change failure
+        // revert code for recipient failure
         return false;
     }
 
 
-    if (recovered_signer == TEST_CONST_EVM_SINGER) {
+    // the intent must name the owner as the sender, as signed.
+    if (recovered_signer == OWNER && intent.sender == OWNER) {
         signed_by_sender = true;
     }
 
     return signed_by_sender;
-}
\ No newline at end of file
+}
+
+/// The index of the input whose predicate is being verified.
+fn predicate_input_index() -> u64 {
+    asm(r1) {
+        gm r1 i3;
+        r1: u64
+    }
+}
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+use fuels::prelude::*;
+use futures::StreamExt;
+use tracing::{info, warn};
+
+use intent_swap::{
+    config::Config,
+    interfaces::{
+        dummy_pwallet::get_dummypwallet_info,
+        generalized_swap_verifier_interface::{ProtocolFee, SwapVerifierClient},
+    },
+    network::{connect_provider, verifier_deployment},
+    relay::{client::RelayClient, intent_book::IntentEvent},
+    solver::fill_intent,
+    telemetry::init_tracing,
+    sponsor::{GasSponsor, SponsorAccount},
+    wallets::get_fuel_wallet,
+};
+
+/// a solver on the intent relay: fill every accepted intent from the local test solver predicate,
+/// with the gas paid by the FUEL_WALLET_SK wallet. The intents buying the base asset are left out,
+/// the solver would need to pay the gas to take the base asset change.
+///
+/// relay.url, network.provider_url and network.verifier_contract_id, the SwapVerifier the
+/// intents are filled through, from intent-swap.toml or the environment (RELAY_URL,
+/// FUEL_PROVIDER_URL, VERIFIER_CONTRACT_ID). SELL_ASSET optionally limits the intents followed.
+///
+///     cargo run --example solver
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+
+    let config = Config::load(None).unwrap();
+    init_tracing(&config.logging).unwrap();
+    let relay_url = config.relay.url.clone();
+    let verifier_id = config.verifier_contract_id().unwrap().expect("VERIFIER_CONTRACT_ID is not set");
+    let sell_asset = std::env::var("SELL_ASSET").ok().map(|a| AssetId::from_str(&a).unwrap());
+
+    let provider = connect_provider(&config.network.provider_url, &config).await.unwrap();
+    // the contract must be the local build, the fills are built against its ABI.
+    let verifier = SwapVerifierClient::connect(
+        verifier_id,
+        get_fuel_wallet(&provider, &config),
+        &verifier_deployment(&config).unwrap(),
+    ).await.unwrap();
+    let protocol_fee = ProtocolFee::from_config(&config);
+
+    let (solver_bytecode, solver_b32addr, _) = get_dummypwallet_info(&config.artifacts, 1u8);
+    config.secrets.fuel_wallet_sk().expect("FUEL_WALLET_SK is not set, it pays the gas of the fills");
+    let mut gas_payer = GasSponsor::new(
+        SponsorAccount::Wallet(get_fuel_wallet(&provider, &config)),
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let client = RelayClient::new(&relay_url);
+    let events = client.subscribe(sell_asset, None).await.unwrap();
+    futures::pin_mut!(events);
+    info!(solver = %solver_b32addr, relay_url = %relay_url, "solver following the relay");
+
+    while let Some(event) = events.next().await {
+        let IntentEvent::Accepted(entry) = event else {
+            info!(intent_id = %event.entry().intent_id, status = ?event.entry().status, "intent event");
+            continue;
+        };
+        if matches!(entry.intent.output_asset(), Ok(asset) if asset == *AssetId::BASE) {
+            info!(intent_id = %entry.intent_id, "intent buys the base asset, not filled");
+            continue;
+        }
+
+        match fill_intent(
+            &provider,
+            &config,
+            &verifier.instance,
+            &protocol_fee,
+            &mut gas_payer,
+            (&solver_bytecode, &solver_b32addr),
+            &entry.intent,
+        ).await {
+            Ok(tx_id) => info!(intent_id = %entry.intent_id, tx_id = %hex::encode(*tx_id), "intent filled"),
+            Err(e) => warn!(intent_id = %entry.intent_id, error = %e, "intent not filled"),
+        }
+    }
+}
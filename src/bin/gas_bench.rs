@@ -0,0 +1,327 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Parser;
+use fuels::{
+    prelude::*,
+    tx::Receipt,
+    types::{
+        output::Output as SdkOutput,
+        transaction::Transaction,
+    },
+};
+use serde::Serialize;
+use tracing::{info, instrument};
+
+use intent_swap::config::Config;
+use intent_swap::wallets::test_sender_evm_sk;
+use intent_swap::evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio;
+use intent_swap::helpers::swap_math;
+use intent_swap::interfaces::generalized_swap_verifier_interface::{
+    build_recipient_output, call_validate_solution, genio_for_inputs, ProtocolFee, SwapVerifier,
+};
+use intent_swap::interfaces::owner_predicate;
+use intent_swap::interfaces::predicate_validator::evm_address_to_bits256;
+use intent_swap::io::utxo_input_utils;
+use intent_swap::network::{NetworkError, NetworkTarget};
+use intent_swap::receipt_show::receipt_sniffer::{wait_for_tx_success, TxStatusResult};
+use intent_swap::scenario::ScenarioBuilder;
+use intent_swap::sponsor::GasSponsor;
+use intent_swap::telemetry::init_tracing;
+use intent_swap::tx_policy::SwapTxPolicy;
+use intent_swap::wallets::get_evm_addr;
+
+/// the amount of every user utxo of the asset in.
+const USER_UTXO_AMOUNT: u64 = 200_000_000;
+/// the amount of the asset out the solver delivers, over all its inputs.
+const AMOUNT_OUT: u64 = 1_000_000_000;
+const TOLERANCE_BPS: u64 = 100;
+/// the base asset sent to the solver and gas sponsor predicates for every case.
+const GAS_FUNDING: u64 = 5_000_001;
+
+/// run validate_solution swaps on a new in-process node for every case, and record their gas.
+#[derive(Parser)]
+#[command(name = "gas-bench")]
+struct Cli {
+    /// the largest number of user utxos, from 1 up to 5.
+    #[arg(long, default_value_t = 5)]
+    max_user_utxos: usize,
+    /// the largest number of solver asset out inputs, from 1.
+    #[arg(long, default_value_t = 3)]
+    max_solver_inputs: usize,
+    /// the largest number of extra solver asset in outputs, from 0.
+    #[arg(long, default_value_t = 2)]
+    max_extra_outputs: usize,
+    /// write the runs as CSV to this file.
+    #[arg(long)]
+    csv: Option<String>,
+    /// write the runs and artifact sizes as JSON to this file, stdout when neither is set.
+    #[arg(long)]
+    json: Option<String>,
+}
+
+/// the byte sizes of the artifacts the gas depends on.
+#[derive(Debug, Serialize)]
+struct ArtifactSizes {
+    verifier: u64,
+    owner_predicate: u64,
+    predicate_validator: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct BenchCase {
+    user_utxos: usize,
+    solver_inputs: usize,
+    extra_outputs: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchRun {
+    #[serde(flatten)]
+    case: BenchCase,
+    tx_inputs: usize,
+    tx_outputs: usize,
+    script_gas_used: u64,
+    predicate_gas_used: u64,
+    max_fee: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    /// unix seconds.
+    created_at: u64,
+    sizes: ArtifactSizes,
+    runs: Vec<BenchRun>,
+}
+
+impl BenchReport {
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "user_utxos,solver_inputs,extra_outputs,tx_inputs,tx_outputs,script_gas_used,predicate_gas_used,max_fee,verifier_size,owner_predicate_size,predicate_validator_size\n",
+        );
+        for run in &self.runs {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{}\n",
+                run.case.user_utxos,
+                run.case.solver_inputs,
+                run.case.extra_outputs,
+                run.tx_inputs,
+                run.tx_outputs,
+                run.script_gas_used,
+                run.predicate_gas_used,
+                run.max_fee,
+                self.sizes.verifier,
+                self.sizes.owner_predicate,
+                self.sizes.predicate_validator,
+            ));
+        }
+        csv
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let config = Config::load(None).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    init_tracing(&config.logging).unwrap();
+
+    // every case runs on its own node, so the utxos of the owner and solver are only the minted ones.
+    match NetworkTarget::from_config(&config) {
+        Ok(NetworkTarget::InProcess) => {}
+        Ok(NetworkTarget::External(_)) => {
+            eprintln!("{}", NetworkError::InProcessOnly("gas-bench"));
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let sizes = ArtifactSizes {
+        verifier: file_size(&config.artifacts.verifier_binary),
+        owner_predicate: file_size(&config.artifacts.owner_predicate_binary),
+        predicate_validator: file_size(&config.artifacts.predicate_validator_binary),
+    };
+
+    let mut runs = vec![];
+    for user_utxos in 1..=cli.max_user_utxos.clamp(1, 5) {
+        for solver_inputs in 1..=cli.max_solver_inputs.max(1) {
+            for extra_outputs in 0..=cli.max_extra_outputs {
+                let case = BenchCase { user_utxos, solver_inputs, extra_outputs };
+                let run = run_case(&config, case).await;
+                info!(?run, "bench run");
+                runs.push(run);
+            }
+        }
+    }
+
+    let created_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let report = BenchReport { created_at, sizes, runs };
+    let json = serde_json::to_string_pretty(&report).unwrap();
+    if let Some(path) = &cli.csv {
+        std::fs::write(path, report.to_csv()).unwrap();
+    }
+    match &cli.json {
+        Some(path) => std::fs::write(path, json).unwrap(),
+        None if cli.csv.is_none() => println!("{}", json),
+        None => {}
+    }
+}
+
+/// the size of the artifact, a missing one ends the bench before any run is recorded.
+fn file_size(path: &str) -> u64 {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .unwrap_or_else(|e| {
+            eprintln!("artifact {}: {}", path, e);
+            std::process::exit(1);
+        })
+}
+
+/// a new node with the verifier deployed, the owner holding case.user_utxos coins of the asset in
+/// and the solver case.solver_inputs coins of the asset out, then one swap of all of them.
+#[instrument(skip_all, fields(
+    user_utxos = case.user_utxos,
+    solver_inputs = case.solver_inputs,
+    extra_outputs = case.extra_outputs,
+))]
+async fn run_case(config: &Config, case: BenchCase) -> BenchRun {
+    let protocol_fee = ProtocolFee::from_config(config);
+
+    // every solver coin is needed to cover the amount out.
+    let solver_coin = AMOUNT_OUT / case.solver_inputs as u64 + 1;
+    let scenario = ScenarioBuilder::new(config)
+        .protocol_fee(&protocol_fee)
+        .owner_predicate("owner", test_sender_evm_sk())
+        .dummy_predicate("solver", 1u8)
+        .funder("sponsor")
+        .gas_sponsor("sponsor")
+        .base_coins("solver", &[GAS_FUNDING])
+        .coins("asset_in", "owner", &vec![USER_UTXO_AMOUNT; case.user_utxos])
+        .coins("asset_out", "solver", &vec![solver_coin; case.solver_inputs])
+        .build()
+        .await
+        .unwrap();
+    let provider = scenario.provider.clone();
+
+    let signer_evm_address = get_evm_addr(test_sender_evm_sk());
+    let (owner_bytecode, owner_b32addr) = scenario.predicate("owner").unwrap();
+    let (solver_bytecode, solver_b32addr) = scenario.predicate("solver").unwrap();
+    let sponsor_account = scenario.wallet("sponsor").unwrap().sponsor_account();
+    let owner_address = Address::from(&owner_b32addr);
+    let solver_address = Address::from(&solver_b32addr);
+    let asset_in_id = scenario.asset_id("asset_in").unwrap();
+    let asset_out_id = scenario.asset_id("asset_out").unwrap();
+    let swapverifier_instance = SwapVerifier::new(scenario.verifier_id.unwrap(), scenario.funder.clone());
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &owner_bytecode,
+        None,
+        asset_in_id,
+        USER_UTXO_AMOUNT * case.user_utxos as u64,
+    ).await.unwrap();
+    assert_eq!(owner_inputs.len(), case.user_utxos);
+
+    let (gio_tx, total_amount_in) = genio_for_inputs(
+        &owner_inputs,
+        asset_in_id,
+        asset_out_id,
+        AMOUNT_OUT,
+        TOLERANCE_BPS,
+        &owner_b32addr,
+    ).unwrap();
+
+    let (solver_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &solver_bytecode,
+        None,
+        asset_out_id,
+        AMOUNT_OUT,
+    ).await.unwrap();
+    assert_eq!(solver_inputs.len(), case.solver_inputs);
+
+    let compact_sig = get_sig_eip712_by_ethers_genio(
+        test_sender_evm_sk(),
+        gio_tx.inputassets.map(|asset| asset.0),
+        gio_tx.inpututxoids.map(|utxoid| utxoid.0),
+        gio_tx.inputamounts.map(|amount| amount.0),
+        gio_tx.outputasset.0,
+        gio_tx.outputamount.0,
+        gio_tx.tolerance.0,
+        gio_tx.recipient.0,
+    ).await;
+
+    // the verifier sums the outputs by asset, so the solver proceeds can be split over extra outputs.
+    let amount_to_solver = swap_math::solver_net_amount_in(total_amount_in, protocol_fee.bps);
+    let solver_outputs = 1 + case.extra_outputs as u64;
+    let mut tx_outputs: Vec<SdkOutput> = vec![build_recipient_output(&gio_tx, AMOUNT_OUT)];
+    for i in 0..solver_outputs {
+        let amount = amount_to_solver / solver_outputs
+            + if i == 0 { amount_to_solver % solver_outputs } else { 0 };
+        tx_outputs.push(SdkOutput::Coin { to: solver_address, amount, asset_id: asset_in_id });
+    }
+    tx_outputs.push(SdkOutput::change(owner_address, 0, asset_in_id));
+    tx_outputs.push(SdkOutput::change(solver_address, 0, asset_out_id));
+
+    let owner_predicate_data = owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(signer_evm_address.0),
+        compact_sig.clone(),
+        &gio_tx,
+    );
+    let tx_inputs = utxo_input_utils::with_predicate_data(owner_inputs, owner_predicate_data)
+        .into_iter()
+        .chain(solver_inputs)
+        .collect();
+
+    let tx_policy = SwapTxPolicy::for_intent(&provider, &config.tx_policy, None).await.unwrap();
+    let stb = call_validate_solution(
+        swapverifier_instance,
+        signer_evm_address.0,
+        gio_tx,
+        compact_sig,
+        tx_inputs,
+        tx_outputs,
+        &protocol_fee,
+        tx_policy.tx_policies(),
+    ).await;
+    let stb = tx_policy.apply(&provider, stb, config.tx_policy.gas_margin_bps).await.unwrap();
+
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+    let tx = gas_sponsor.sponsor(&provider, stb).await.unwrap();
+    let tx_inputs = tx.inputs().len();
+    let tx_outputs = tx.outputs().len();
+    let predicate_gas_used = tx.inputs().iter().filter_map(|input| input.predicate_gas_used()).sum();
+    let max_fee = tx.max_fee().unwrap_or_default();
+
+    let tx_id = provider.send_transaction(tx).await.unwrap();
+    let receipts = match wait_for_tx_success(&provider, &tx_id, &config.polling).await {
+        TxStatusResult::Ok { receipts, .. } => receipts,
+        TxStatusResult::Err { error, .. } => panic!("bench swap failed: {:?}", error),
+    };
+    let script_gas_used = receipts
+        .iter()
+        .find_map(|receipt| match receipt {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    BenchRun {
+        case,
+        tx_inputs,
+        tx_outputs,
+        script_gas_used,
+        predicate_gas_used,
+        max_fee,
+    }
+}
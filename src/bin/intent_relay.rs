@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use intent_swap::config::Config;
+use intent_swap::indexer::IntentIndexer;
+use intent_swap::interfaces::generalized_swap_verifier_interface::SwapVerifierClient;
+use intent_swap::network::{connect_provider, owner_binding, verifier_deployment};
+use intent_swap::telemetry::init_tracing;
+use intent_swap::relay::{
+    intent_book::IntentBook,
+    server::{serve, RelayState},
+};
+use intent_swap::wallets::get_fuel_wallet;
+
+/// intent relay, a local book of signed intents for solvers.
+///
+/// relay.addr: the address to listen on, network.provider_url: the fuel node the intent inputs
+/// are checked against. With network.verifier_contract_id set, the intents published through its
+/// intent_messenger are indexed into the book too, resuming from relay.indexer_cursor.
+/// All are read from intent-swap.toml and the environment, see Config::load.
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let config = Config::load(None).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    init_tracing(&config.logging).unwrap();
+
+    let provider = connect_provider(&config.network.provider_url, &config).await.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let listener = tokio::net::TcpListener::bind(&config.relay.addr).await.unwrap();
+    info!(addr = %config.relay.addr, provider_url = %config.network.provider_url, "intent relay listening");
+
+    let binding = owner_binding(&config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let state = RelayState {
+        book: IntentBook::new(),
+        provider: provider.clone(),
+        config: Arc::new(config.clone()),
+        binding: binding.clone(),
+    };
+
+    if let Some(verifier_id) = config.verifier_contract_id().unwrap() {
+        let cursor_path = config.relay.indexer_cursor.clone();
+        let deployment = verifier_deployment(&config).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        let verifier = SwapVerifierClient::connect(
+            verifier_id,
+            get_fuel_wallet(&provider, &config),
+            &deployment,
+        ).await.unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        let indexer = IntentIndexer::new(
+            provider,
+            verifier.instance,
+            config.artifacts.clone(),
+            binding,
+            cursor_path.clone().into(),
+        ).unwrap();
+        info!(
+            verifier_id = %verifier_id,
+            from_height = indexer.next_height(),
+            cursor = %cursor_path,
+            "indexing intents",
+        );
+        tokio::spawn(indexer.run(state.book.clone(), Duration::from_secs(config.relay.sweep_interval_secs)));
+    }
+
+    serve(listener, state).await.unwrap();
+}
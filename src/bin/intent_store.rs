@@ -0,0 +1,106 @@
+use clap::{Parser, Subcommand};
+use fuels::prelude::Provider;
+use tracing::error;
+
+use intent_swap::config::Config;
+use intent_swap::network::{self, owner_binding};
+use intent_swap::relay::intent_book::parse_hex;
+use intent_swap::store::IntentStore;
+use intent_swap::telemetry::init_tracing;
+use intent_swap::watcher::UtxoWatcher;
+
+/// query the intent store, and settle the intents whose fill was submitted.
+#[derive(Parser)]
+#[command(name = "intent-store")]
+struct Cli {
+    /// the sled database, relay.store_path of the config by default.
+    #[arg(long)]
+    db: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// the stored intents as JSON, optionally only the ones in a state, e.g. submitted.
+    List {
+        #[arg(long)]
+        state: Option<String>,
+    },
+    /// one stored intent as JSON.
+    Show {
+        intent_id: String,
+    },
+    /// wait for the fill tx of every submitted intent on the node of the config.
+    Track {
+        /// polling.timeout_secs of the config by default.
+        #[arg(long)]
+        timeout_secs: Option<u64>,
+    },
+    /// invalidate the open intents whose inputs get spent, printing each as JSON.
+    Watch,
+}
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let config = or_exit(Config::load(None));
+    init_tracing(&config.logging).unwrap();
+
+    let db_path = cli.db.unwrap_or(config.relay.store_path.clone());
+    let store = or_exit(IntentStore::open(&db_path));
+
+    match cli.command {
+        Command::List { state } => {
+            let stored = match state {
+                Some(state) => or_exit(store.list_by_state(&state)),
+                None => or_exit(store.list()),
+            };
+            println!("{}", serde_json::to_string_pretty(&stored).unwrap());
+        }
+        Command::Show { intent_id } => {
+            let intent_id: [u8; 32] = or_exit(parse_hex("intent_id", &intent_id));
+            match or_exit(store.get(&intent_id)) {
+                Some(stored) => println!("{}", serde_json::to_string_pretty(&stored).unwrap()),
+                None => {
+                    eprintln!("intent not found in {}", db_path);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Track { timeout_secs } => {
+            let provider = connect_provider(&config).await;
+            let mut polling = config.polling.clone();
+            polling.timeout_secs = timeout_secs.unwrap_or(polling.timeout_secs);
+            let tracked = or_exit(store.resume_submitted(&provider, &polling).await);
+            println!("{}", serde_json::to_string_pretty(&tracked).unwrap());
+        }
+        Command::Watch => {
+            let binding = or_exit(owner_binding(&config));
+            let mut watcher = UtxoWatcher::new(connect_provider(&config).await, store, config.artifacts.clone(), binding);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(config.relay.sweep_interval_secs));
+            loop {
+                interval.tick().await;
+                match watcher.on_new_block().await {
+                    Ok(invalidated) => invalidated
+                        .iter()
+                        .for_each(|event| println!("{}", serde_json::to_string(event).unwrap())),
+                    Err(e) => error!(error = %e, "utxo watcher failed"),
+                }
+            }
+        }
+    }
+}
+
+async fn connect_provider(config: &Config) -> Provider {
+    or_exit(network::connect_provider(&config.network.provider_url, config).await)
+}
+
+/// the value, or the error printed to stderr and exit code 1.
+fn or_exit<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
@@ -0,0 +1,478 @@
+use std::io::Read;
+use std::path::PathBuf;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+use clap::{Args, Parser, Subcommand};
+use serde_json::{json, Value};
+use thiserror::Error;
+use fuels::{
+    prelude::*,
+    tx::Receipt,
+    types::{
+        Bytes32,
+        tx_status::TxStatus,
+    },
+};
+
+use crate::{
+    config::{Config, ConfigError},
+    interfaces::{
+        dummy_pwallet::get_dummypwallet_info,
+        generalized_swap_verifier_interface::{
+            verifier_revert_reason,
+            ProtocolFee,
+            SwapVerifier,
+            SwapVerifierClient,
+            VerifierError,
+        },
+        owner_predicate::get_owner_predicate_info,
+    },
+    network::{connect_provider, deploy_contracts, owner_binding, verifier_deployment, NetworkError},
+    relay::{
+        RelayError,
+        client::{sign_intent, RelayClient},
+        intent_book::{parse_hex, to_hex, GenIOJson, SignedIntent},
+    },
+    setup_01,
+    solver::{fill_intent, SolverError},
+    sponsor::{GasSponsor, SponsorAccount},
+    trace::{TraceError, VerifierTrace},
+    wallets::get_fuel_wallet,
+};
+
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Relay(#[from] RelayError),
+    #[error(transparent)]
+    Solver(#[from] SolverError),
+    #[error(transparent)]
+    Verifier(#[from] VerifierError),
+    #[error(transparent)]
+    Trace(#[from] TraceError),
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+    #[error("Provider error: {0}")]
+    Provider(String),
+}
+
+
+/// intent swaps on fuel with EVM signed intents.
+#[derive(Parser)]
+#[command(name = "intent-swap", version)]
+pub struct Cli {
+    /// print the result as one line of JSON, for scripts.
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// the config file, intent-swap.toml by default when it exists.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// the fuel node, instead of network.provider_url of the config.
+    #[arg(long, global = true)]
+    pub provider: Option<String>,
+    /// the tracing filter, instead of logging.level of the config, e.g. debug.
+    #[arg(long, global = true)]
+    pub log_level: Option<String>,
+    /// runs the scenarios when no command is given.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// run the swap scenarios against in-process nodes.
+    Scenarios,
+    /// start a local node with the SwapVerifier, the predicates and the test assets deployed.
+    Node,
+    /// deploy the SwapVerifier and the TokenMinter to the node, paid by FUEL_WALLET_SK, for
+    /// network.target = "external".
+    Deploy,
+    /// build a GenIO and sign it with the EVM key in INTENT_SIGNER_SK or INTENT_SIGNER_KEYSTORE.
+    Sign(SignArgs),
+    /// check the signature of a signed intent, and that its inputs are unspent.
+    Verify {
+        /// a SignedIntent JSON file, or - for stdin.
+        #[arg(default_value = "-")]
+        intent: String,
+        /// only check the signature.
+        #[arg(long)]
+        offline: bool,
+    },
+    /// decode a signed intent.
+    InspectIntent {
+        /// a SignedIntent JSON file, or - for stdin.
+        #[arg(default_value = "-")]
+        intent: String,
+    },
+    /// fill a signed intent through the SwapVerifier, from the local test solver predicate.
+    Solve {
+        /// a SignedIntent JSON file, or - for stdin.
+        #[arg(default_value = "-")]
+        intent: String,
+        /// instead of network.verifier_contract_id of the config.
+        #[arg(long)]
+        verifier: Option<String>,
+    },
+    /// submit a signed intent to the relay.
+    Submit {
+        /// a SignedIntent JSON file, or - for stdin.
+        #[arg(default_value = "-")]
+        intent: String,
+        /// instead of relay.url of the config.
+        #[arg(long)]
+        relay: Option<String>,
+    },
+    /// the status of a transaction, with the verifier revert code and logs decoded.
+    Status {
+        tx_id: String,
+    },
+    /// the balances of an address, as hex or bech32.
+    Balances {
+        address: String,
+    },
+}
+
+#[derive(Args)]
+pub struct SignArgs {
+    /// a GenIO JSON file, or - for stdin, instead of the flags.
+    #[arg(long, conflicts_with_all = ["inputs", "output_asset", "recipient"])]
+    pub genio: Option<String>,
+    /// an intent input, up to 5.
+    #[arg(long = "input", value_name = "ASSET:UTXO_ID:AMOUNT")]
+    pub inputs: Vec<String>,
+    #[arg(long)]
+    pub output_asset: Option<String>,
+    #[arg(long, default_value_t = 0)]
+    pub output_amount: u64,
+    /// swap.tolerance_bps of the config by default.
+    #[arg(long)]
+    pub tolerance_bps: Option<u64>,
+    /// the address the output asset is sent to, as hex or bech32.
+    #[arg(long)]
+    pub recipient: Option<String>,
+    /// the block height the relay drops the intent at.
+    #[arg(long)]
+    pub expires_at: Option<u32>,
+}
+
+
+impl Cli {
+
+    /// the config of --config, with --provider over the provider url and --log-level over
+    /// the tracing filter.
+    pub fn load_config(&self) -> StdResult<Config, CliError> {
+        let mut config = Config::load(self.config.as_deref())?;
+        if let Some(provider_url) = &self.provider {
+            config.network.provider_url = provider_url.clone();
+        }
+        if let Some(level) = &self.log_level {
+            config.logging.level = level.clone();
+            config.validate()?;
+        }
+        Ok(config)
+    }
+}
+
+/// run a command other than scenarios and node, which run for longer than a result.
+pub async fn run_command(command: Command, config: &Config) -> StdResult<Value, CliError> {
+    match command {
+        Command::Deploy => deploy(config).await,
+        Command::Sign(args) => sign(args, config).await,
+        Command::Verify { intent, offline } => verify(&read_intent(&intent)?, config, offline).await,
+        Command::InspectIntent { intent } => inspect_intent(&read_intent(&intent)?, config),
+        Command::Solve { intent, verifier } => solve(&read_intent(&intent)?, config, verifier.as_deref()).await,
+        Command::Submit { intent, relay } => {
+            let relay_url = relay.as_deref().unwrap_or(&config.relay.url);
+            let entry = RelayClient::new(relay_url).submit(&read_intent(&intent)?).await?;
+            Ok(serde_json::to_value(entry)?)
+        }
+        Command::Status { tx_id } => status(&tx_id, config).await,
+        Command::Balances { address } => balances(&address, config).await,
+        Command::Scenarios | Command::Node => Err(CliError::Usage("not a one shot command".to_string())),
+    }
+}
+
+/// one line of JSON, or one line per field for reading.
+pub fn print_output(value: &Value, json: bool) {
+    if json {
+        println!("{}", value);
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                match field {
+                    Value::String(s) => println!("{}: {}", key, s),
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{}: {}", key, serde_json::to_string_pretty(field).unwrap())
+                    }
+                    other => println!("{}: {}", key, other),
+                }
+            }
+        }
+        other => println!("{}", serde_json::to_string_pretty(other).unwrap()),
+    }
+}
+
+/// start the local node with the setup, print its addresses, and keep it up until ctrl-c.
+pub async fn run_node(config: &Config, json: bool) -> StdResult<(), CliError> {
+    let (
+        provider,
+        giov_cid,
+        _predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        _predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &ProtocolFee::from_config(config),
+        None,
+    ).await?;
+
+    print_output(&json!({
+        "provider_url": provider.url(),
+        "verifier_contract_id": to_hex(&*giov_cid),
+        "owner_predicate": predicate_owner_b32addr.to_string(),
+        "solver_predicate": predicate_solver_b32addr.to_string(),
+        "gas_sponsor": Bech32Address::from(sponsor_account.address()).to_string(),
+        "asset_in": to_hex(&*asset_in_id),
+        "asset_out": to_hex(&*asset_out_id),
+    }), json);
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+async fn deploy(config: &Config) -> StdResult<Value, CliError> {
+    if config.secrets.fuel_wallet_sk().is_none() {
+        return Err(CliError::Usage("deploy needs a funded wallet, set FUEL_WALLET_SK".to_string()));
+    }
+    let provider = connect_provider(&config.network.provider_url, config).await?;
+    let (verifier_id, token_minter_id) = deploy_contracts(config, &get_fuel_wallet(&provider, config)).await?;
+    Ok(json!({
+        "verifier_contract_id": to_hex(&*verifier_id),
+        "token_minter_contract_id": to_hex(&*token_minter_id),
+    }))
+}
+
+async fn sign(args: SignArgs, config: &Config) -> StdResult<Value, CliError> {
+    // the key is never a flag, so it does not end up in the shell history.
+    let evm_sk = config.secrets.signer_evm_sk()?;
+
+    let io = match &args.genio {
+        Some(path) => serde_json::from_str::<GenIOJson>(&read_input(path)?)?,
+        None => genio_from_flags(&args, config)?,
+    };
+    let signed_intent = sign_intent(evm_sk, &io.genio()?).await;
+    let signed_intent = match args.expires_at {
+        Some(block_height) => signed_intent.with_expiry(block_height),
+        None => signed_intent,
+    };
+    Ok(serde_json::to_value(signed_intent)?)
+}
+
+fn genio_from_flags(args: &SignArgs, config: &Config) -> StdResult<GenIOJson, CliError> {
+    if args.inputs.is_empty() || args.inputs.len() > 5 {
+        return Err(CliError::Usage("between 1 and 5 --input are needed".to_string()));
+    }
+    let zero = to_hex(&[0u8; 32]);
+    let mut io = GenIOJson {
+        inputassets: std::array::from_fn(|_| zero.clone()),
+        inpututxoids: std::array::from_fn(|_| zero.clone()),
+        inputamounts: [0; 5],
+        outputasset: args.output_asset.clone().ok_or(CliError::Usage("--output-asset is needed".to_string()))?,
+        outputamount: args.output_amount,
+        tolerance: args.tolerance_bps.unwrap_or(config.swap.tolerance_bps),
+        recipient: to_hex(&parse_address(
+            args.recipient.as_deref().ok_or(CliError::Usage("--recipient is needed".to_string()))?,
+        )?),
+    };
+    for (i, input) in args.inputs.iter().enumerate() {
+        let parts: Vec<&str> = input.split(':').collect();
+        let [asset, utxo_id, amount] = parts[..] else {
+            return Err(CliError::Usage(format!("--input {} is not ASSET:UTXO_ID:AMOUNT", input)));
+        };
+        io.inputassets[i] = asset.to_string();
+        io.inpututxoids[i] = utxo_id.to_string();
+        io.inputamounts[i] = amount
+            .parse()
+            .map_err(|_| CliError::Usage(format!("--input {} has an invalid amount", input)))?;
+    }
+    Ok(io)
+}
+
+async fn verify(intent: &SignedIntent, config: &Config, offline: bool) -> StdResult<Value, CliError> {
+    let intent_id = intent.verify_signature()?;
+    if !offline {
+        let provider = connect_provider(&config.network.provider_url, config).await?;
+        intent.verify_inputs(&provider, &config.artifacts, &owner_binding(config)?).await?;
+    }
+    Ok(json!({
+        "intent_id": to_hex(&intent_id),
+        "sender": intent.sender,
+        "signature_valid": true,
+        "inputs_unspent": if offline { Value::Null } else { Value::Bool(true) },
+    }))
+}
+
+fn inspect_intent(intent: &SignedIntent, config: &Config) -> StdResult<Value, CliError> {
+    let (_, owner_b32addr, owner_address) = get_owner_predicate_info(&config.artifacts, &owner_binding(config)?, intent.sender_address()?);
+    let inputs: Vec<Value> = intent
+        .utxos()?
+        .iter()
+        .map(|(asset_id, utxo_id, amount)| json!({
+            "asset": to_hex(&**asset_id),
+            "utxo_id": to_hex(utxo_id),
+            "amount": amount,
+        }))
+        .collect();
+    Ok(json!({
+        "intent_id": to_hex(&intent.intent_id()?),
+        "sender": intent.sender,
+        "owner_predicate": owner_b32addr.to_string(),
+        "owner_predicate_hex": to_hex(&*owner_address),
+        "inputs": inputs,
+        "output_asset": intent.io.outputasset,
+        "output_amount": intent.io.outputamount,
+        "tolerance_bps": intent.io.tolerance,
+        "recipient": intent.io.recipient,
+        "expires_at": intent.expires_at,
+        "signature_valid": intent.verify_signature().is_ok(),
+    }))
+}
+
+async fn solve(intent: &SignedIntent, config: &Config, verifier_id: Option<&str>) -> StdResult<Value, CliError> {
+    let provider = connect_provider(&config.network.provider_url, config).await?;
+    let verifier_id = match verifier_id {
+        Some(verifier_id) => ContractId::from_str(verifier_id)
+            .map_err(|_| CliError::Usage(format!("invalid verifier contract id {}", verifier_id)))?,
+        None => config
+            .verifier_contract_id()?
+            .ok_or(CliError::Usage("--verifier or network.verifier_contract_id is needed".to_string()))?,
+    };
+    let verifier = SwapVerifierClient::connect(
+        verifier_id,
+        get_fuel_wallet(&provider, config),
+        &verifier_deployment(config)?,
+    ).await?;
+
+    // the solver pays its own gas, so it can also deliver the base asset.
+    let (solver_bytecode, solver_b32addr, _) = get_dummypwallet_info(&config.artifacts, 1u8);
+    let mut gas_payer = GasSponsor::new(
+        SponsorAccount::Predicate(
+            Predicate::from_code(solver_bytecode.clone()).with_provider(provider.clone())
+        ),
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let tx_id = fill_intent(
+        &provider,
+        config,
+        &verifier.instance,
+        &ProtocolFee::from_config(config),
+        &mut gas_payer,
+        (&solver_bytecode, &solver_b32addr),
+        intent,
+    ).await?;
+    Ok(json!({
+        "intent_id": to_hex(&intent.intent_id()?),
+        "tx_id": to_hex(&*tx_id),
+    }))
+}
+
+async fn status(tx_id: &str, config: &Config) -> StdResult<Value, CliError> {
+    let tx_id = Bytes32::from(parse_hex::<32>("tx_id", tx_id)?);
+    let provider = connect_provider(&config.network.provider_url, config).await?;
+    let status = provider
+        .tx_status(&tx_id)
+        .await
+        .map_err(|e| CliError::Provider(e.to_string()))?;
+
+    let verifier_report = |receipts: &[Receipt]| -> StdResult<Option<Value>, CliError> {
+        let Some(verifier_id) = config.verifier_contract_id()? else {
+            return Ok(None);
+        };
+        let swapverifier_instance = SwapVerifier::new(verifier_id, get_fuel_wallet(&provider, config));
+        let trace = VerifierTrace::decode(&swapverifier_instance, receipts)?;
+        if trace.logs.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_value(trace.report())?))
+    };
+
+    Ok(match status {
+        TxStatus::Success { receipts } => json!({
+            "tx_id": to_hex(&*tx_id),
+            "status": "success",
+            "verifier": verifier_report(&receipts)?,
+            "receipts": receipts,
+        }),
+        TxStatus::Revert { reason, revert_id, receipts } => json!({
+            "tx_id": to_hex(&*tx_id),
+            "status": "revert",
+            "revert_id": revert_id,
+            "reason": verifier_revert_reason(revert_id).map(String::from).unwrap_or(reason),
+            "verifier": verifier_report(&receipts)?,
+            "receipts": receipts,
+        }),
+        TxStatus::SqueezedOut { reason } => json!({
+            "tx_id": to_hex(&*tx_id),
+            "status": "squeezed_out",
+            "reason": reason,
+        }),
+        TxStatus::Submitted => json!({
+            "tx_id": to_hex(&*tx_id),
+            "status": "submitted",
+        }),
+    })
+}
+
+async fn balances(address: &str, config: &Config) -> StdResult<Value, CliError> {
+    let b32addr = Bech32Address::from(Address::from(parse_address(address)?));
+    let balances = connect_provider(&config.network.provider_url, config)
+        .await?
+        .get_balances(&b32addr)
+        .await
+        .map_err(|e| CliError::Provider(e.to_string()))?;
+    Ok(json!({
+        "address": b32addr.to_string(),
+        "balances": balances,
+    }))
+}
+
+/// a fuel address as bech32 (fuel1..) or hex.
+fn parse_address(address: &str) -> StdResult<[u8; 32], CliError> {
+    if address.starts_with("fuel1") {
+        let b32addr = Bech32Address::from_str(address)
+            .map_err(|_| CliError::Usage(format!("invalid bech32 address {}", address)))?;
+        return Ok(*b32addr.hash);
+    }
+    Ok(parse_hex("address", address)?)
+}
+
+fn read_intent(path: &str) -> StdResult<SignedIntent, CliError> {
+    Ok(serde_json::from_str(&read_input(path)?)?)
+}
+
+/// the file at path, or stdin for -.
+fn read_input(path: &str) -> StdResult<String, CliError> {
+    if path == "-" {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    Ok(std::fs::read_to_string(path)?)
+}
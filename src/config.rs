@@ -0,0 +1,675 @@
+use std::fmt;
+use std::path::Path;
+use std::result::Result as StdResult;
+use std::str::FromStr;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use ethers::signers::LocalWallet;
+use fuels::prelude::{Address, ContractId};
+
+use crate::consts::*;
+
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Config file {path}: {message}")]
+    Read { path: String, message: String },
+    #[error("Invalid config: {0}")]
+    Parse(String),
+    #[error("Invalid config, {field}: {message}")]
+    Invalid { field: &'static str, message: String },
+    #[error("Missing secret, set {0}")]
+    MissingSecret(&'static str),
+    #[error("Keystore {path}: {message}")]
+    Keystore { path: String, message: String },
+}
+
+
+/// the defaults a config starts from, before the file and the environment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    /// a local node, with the contracts deployed by the setup.
+    #[default]
+    Local,
+    /// a shared test network, with an already deployed SwapVerifier.
+    Testnet,
+    /// nothing assumed, the network comes from the file or the environment.
+    Custom,
+}
+
+impl FromStr for Profile {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s {
+            "local" => Ok(Profile::Local),
+            "testnet" => Ok(Profile::Testnet),
+            "custom" => Ok(Profile::Custom),
+            other => Err(ConfigError::Invalid { field: "profile", message: format!("unknown profile {}", other) }),
+        }
+    }
+}
+
+/// whether the setups launch their own node, or use a running one, see network::NetworkTarget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    InProcess,
+    External,
+}
+
+impl FromStr for TargetKind {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s {
+            "in_process" => Ok(TargetKind::InProcess),
+            "external" => Ok(TargetKind::External),
+            other => Err(invalid("network.target", &format!("unknown target {}", other))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub provider_url: String,
+    /// the fuel chain id of the node, checked on connect, 0 for any. The EIP-712 domain chain id
+    /// is fixed by the contracts.
+    pub chain_id: u64,
+    pub target: TargetKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifier_contract_id: Option<String>,
+    /// the TokenMinter the setups mint the test assets with on an external node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_minter_contract_id: Option<String>,
+}
+
+/// the forc build outputs of the contracts and predicates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArtifactPaths {
+    pub verifier_binary: String,
+    pub verifier_storage: String,
+    pub owner_predicate_binary: String,
+    pub owner_predicate_abi: String,
+    pub predicate_validator_binary: String,
+    pub dummy_wallet_binary: String,
+    pub token_minter_binary: String,
+    pub token_minter_storage: String,
+}
+
+impl Default for ArtifactPaths {
+    fn default() -> Self {
+        ArtifactPaths {
+            verifier_binary: "./contracts/contract_validator/out/debug/contract_validator.bin".to_string(),
+            verifier_storage: "./contracts/contract_validator/out/debug/contract_validator-storage_slots.json".to_string(),
+            owner_predicate_binary: "./contracts/owner_predicate/out/debug/owner_predicate.bin".to_string(),
+            owner_predicate_abi: "./contracts/owner_predicate/out/debug/owner_predicate-abi.json".to_string(),
+            predicate_validator_binary: "./contracts/predicate_validator/out/debug/predicate_validator.bin".to_string(),
+            dummy_wallet_binary: "./contracts/dummy_predicate_wallet/out/debug/dummy_predicate_wallet.bin".to_string(),
+            token_minter_binary: "./contracts/asset_ops/out/debug/asset_ops.bin".to_string(),
+            token_minter_storage: "./contracts/asset_ops/out/debug/asset_ops-storage_slots.json".to_string(),
+        }
+    }
+}
+
+/// the salts the contracts are deployed with, as 32 byte hex.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentConfig {
+    pub verifier_salt: String,
+    pub token_minter_salt: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProtocolFeeConfig {
+    pub recipient: String,
+    pub bps: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasSponsorConfig {
+    pub gas_amount: u64,
+    pub budget: u64,
+    /// the address whose base asset inputs the SwapVerifier does not count as part of a swap,
+    /// see network::gas_sponsor_address when unset. Required by the testnet profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
+
+/// how wait_for_tx_success waits for a tx on the node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PollingConfig {
+    /// the first poll interval.
+    pub interval_ms: u64,
+    /// the poll interval grows by backoff_factor after each poll, up to max_interval_ms.
+    pub max_interval_ms: u64,
+    pub backoff_factor: u32,
+    pub timeout_secs: u64,
+    /// wait on the node's tx status subscription, polling only when the node does not serve it.
+    pub subscribe: bool,
+}
+
+/// how fill_intent sends a fill again after it was squeezed out or timed out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResubmitConfig {
+    /// the sends of a fill, the first one included.
+    pub max_attempts: u32,
+    /// the tip added on every resend, so it can replace a fill still in the pool.
+    pub tip_increment: u64,
+}
+
+/// the policies of the swap txs, see tx_policy::SwapTxPolicy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxPolicyConfig {
+    pub tip: u64,
+    /// a fixed script gas limit, or none to size it from a dry run plus gas_margin_bps.
+    pub script_gas_limit: Option<u64>,
+    pub gas_margin_bps: u64,
+    /// the blocks from the current height until the tx can be included.
+    pub maturity_blocks: Option<u32>,
+    /// the blocks from the current height the tx may still be sent in, capped at the intent deadline.
+    pub expiration_blocks: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SwapConfig {
+    /// the tolerance signed into new intents, unless given.
+    pub tolerance_bps: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelayConfig {
+    pub addr: String,
+    pub url: String,
+    pub sweep_interval_secs: u64,
+    pub indexer_cursor: String,
+    pub store_path: String,
+}
+
+/// how the tracing events are written, see telemetry::init_tracing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// one readable line per event, with the span fields.
+    Human,
+    /// one JSON object per event, for log collectors.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s {
+            "human" => Ok(LogFormat::Human),
+            "json" => Ok(LogFormat::Json),
+            other => Err(invalid("logging.format", &format!("unknown format {}", other))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// a tracing filter, e.g. info, or intent_swap=debug,fuels=warn.
+    pub level: String,
+    pub format: LogFormat,
+}
+
+/// the keys, only ever read from the environment or a keystore, never from the config file.
+#[derive(Clone, Default)]
+pub struct Secrets {
+    signer_evm_sk: Option<String>,
+    fuel_wallet_sk: Option<String>,
+}
+
+impl fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Secrets")
+            .field("signer_evm_sk", &self.signer_evm_sk.as_ref().map(|_| "<redacted>"))
+            .field("fuel_wallet_sk", &self.fuel_wallet_sk.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Secrets {
+
+    /// INTENT_SIGNER_SK, or the INTENT_SIGNER_KEYSTORE file unlocked with
+    /// INTENT_SIGNER_KEYSTORE_PASSWORD. FUEL_WALLET_SK for the fuel wallet.
+    pub fn from_env() -> StdResult<Self, ConfigError> {
+        let signer_evm_sk = match (std::env::var("INTENT_SIGNER_SK"), std::env::var("INTENT_SIGNER_KEYSTORE")) {
+            (Ok(evm_sk), _) => Some(evm_sk),
+            (Err(_), Ok(path)) => {
+                let password = std::env::var("INTENT_SIGNER_KEYSTORE_PASSWORD")
+                    .map_err(|_| ConfigError::MissingSecret("INTENT_SIGNER_KEYSTORE_PASSWORD"))?;
+                let wallet = LocalWallet::decrypt_keystore(&path, password)
+                    .map_err(|e| ConfigError::Keystore { path, message: e.to_string() })?;
+                Some(hex::encode(wallet.signer().to_bytes()))
+            }
+            _ => None,
+        };
+        Ok(Secrets {
+            signer_evm_sk,
+            fuel_wallet_sk: std::env::var("FUEL_WALLET_SK").ok(),
+        })
+    }
+
+    pub fn signer_evm_sk(&self) -> StdResult<&str, ConfigError> {
+        self.signer_evm_sk
+            .as_deref()
+            .ok_or(ConfigError::MissingSecret("INTENT_SIGNER_SK or INTENT_SIGNER_KEYSTORE"))
+    }
+
+    /// None for a random wallet, which only builds and reads, the gas is paid by a sponsor.
+    pub fn fuel_wallet_sk(&self) -> Option<&str> {
+        self.fuel_wallet_sk.as_deref()
+    }
+}
+
+
+/// the whole configuration, a profile's defaults overlaid by a TOML file and the environment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub profile: Profile,
+    pub network: NetworkConfig,
+    pub artifacts: ArtifactPaths,
+    pub deployment: DeploymentConfig,
+    pub protocol_fee: ProtocolFeeConfig,
+    pub gas_sponsor: GasSponsorConfig,
+    pub polling: PollingConfig,
+    pub resubmit: ResubmitConfig,
+    pub tx_policy: TxPolicyConfig,
+    pub swap: SwapConfig,
+    pub relay: RelayConfig,
+    pub logging: LoggingConfig,
+    #[serde(skip)]
+    pub secrets: Secrets,
+}
+
+impl Config {
+
+    pub fn for_profile(profile: Profile) -> Self {
+        let (provider_url, target, polling) = match profile {
+            Profile::Local => (
+                DEFAULT_PROVIDER_URL,
+                TargetKind::InProcess,
+                PollingConfig { interval_ms: 100, max_interval_ms: 1_000, backoff_factor: 2, timeout_secs: 12, subscribe: true },
+            ),
+            Profile::Custom => (
+                DEFAULT_PROVIDER_URL,
+                TargetKind::External,
+                PollingConfig { interval_ms: 100, max_interval_ms: 1_000, backoff_factor: 2, timeout_secs: 12, subscribe: true },
+            ),
+            Profile::Testnet => (
+                TESTNET_PROVIDER_URL,
+                TargetKind::External,
+                PollingConfig { interval_ms: 1_000, max_interval_ms: 8_000, backoff_factor: 2, timeout_secs: 60, subscribe: true },
+            ),
+        };
+        Config {
+            profile,
+            network: NetworkConfig {
+                provider_url: provider_url.to_string(),
+                chain_id: 0,
+                target,
+                verifier_contract_id: None,
+                token_minter_contract_id: None,
+            },
+            artifacts: ArtifactPaths::default(),
+            deployment: DeploymentConfig {
+                verifier_salt: format!("0x{}", hex::encode([01u8; 32])),
+                token_minter_salt: format!("0x{}", hex::encode([02u8; 32])),
+            },
+            protocol_fee: ProtocolFeeConfig {
+                recipient: DEFAULT_PROTOCOL_FEE_RECIPIENT.to_string(),
+                bps: DEFAULT_PROTOCOL_FEE_BPS,
+            },
+            gas_sponsor: GasSponsorConfig {
+                gas_amount: DEFAULT_GAS_SPONSOR_GAS_AMOUNT,
+                budget: DEFAULT_GAS_SPONSOR_BUDGET,
+                address: None,
+            },
+            polling,
+            resubmit: ResubmitConfig {
+                max_attempts: 3,
+                tip_increment: 1_000,
+            },
+            tx_policy: TxPolicyConfig {
+                tip: 0,
+                script_gas_limit: None,
+                gas_margin_bps: 2_000,
+                maturity_blocks: None,
+                expiration_blocks: None,
+            },
+            swap: SwapConfig { tolerance_bps: 250 },
+            relay: RelayConfig {
+                addr: DEFAULT_RELAY_ADDR.to_string(),
+                url: format!("http://{}", DEFAULT_RELAY_ADDR),
+                sweep_interval_secs: 2,
+                indexer_cursor: "intent_indexer_cursor.json".to_string(),
+                store_path: "intent_store.sled".to_string(),
+            },
+            logging: LoggingConfig {
+                level: "info".to_string(),
+                format: LogFormat::Human,
+            },
+            secrets: Secrets::default(),
+        }
+    }
+
+    /// the local profile with no file, as the scenarios run against the in-process node.
+    pub fn local() -> Self {
+        Config::for_profile(Profile::Local)
+    }
+
+    /// the file at path, or intent-swap.toml when it exists, then the environment.
+    /// The profile is INTENT_SWAP_PROFILE, or the profile in the file, or local.
+    pub fn load(path: Option<&Path>) -> StdResult<Self, ConfigError> {
+        let default_path = Path::new(CONFIG_DEFAULT_PATH);
+        let path = path.or(default_path.exists().then_some(default_path));
+
+        let file_table = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Read {
+                    path: path.display().to_string(),
+                    message: e.to_string(),
+                })?;
+                text.parse::<toml::Table>().map_err(|e| ConfigError::Read {
+                    path: path.display().to_string(),
+                    message: e.to_string(),
+                })?
+            }
+            None => toml::Table::new(),
+        };
+
+        let profile = match std::env::var("INTENT_SWAP_PROFILE") {
+            Ok(profile) => profile.parse()?,
+            Err(_) => match file_table.get("profile") {
+                Some(toml::Value::String(profile)) => profile.parse()?,
+                Some(_) => return Err(ConfigError::Invalid { field: "profile", message: "not a string".to_string() }),
+                None => Profile::Local,
+            },
+        };
+
+        let toml::Value::Table(mut table) = toml::Value::try_from(Config::for_profile(profile))
+            .map_err(|e| ConfigError::Parse(e.to_string()))? else {
+            unreachable!("a config serializes to a table");
+        };
+        merge_tables(&mut table, file_table);
+        let mut config: Config = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.to_string()))?;
+        config.profile = profile;
+
+        config.apply_env()?;
+        config.secrets = Secrets::from_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) -> StdResult<(), ConfigError> {
+        let overrides: [(&str, &mut String); 6] = [
+            ("FUEL_PROVIDER_URL", &mut self.network.provider_url),
+            ("RELAY_ADDR", &mut self.relay.addr),
+            ("RELAY_URL", &mut self.relay.url),
+            ("INDEXER_CURSOR", &mut self.relay.indexer_cursor),
+            ("INTENT_STORE", &mut self.relay.store_path),
+            ("RUST_LOG", &mut self.logging.level),
+        ];
+        for (var, field) in overrides {
+            if let Ok(value) = std::env::var(var) {
+                *field = value;
+            }
+        }
+        if let Ok(verifier_id) = std::env::var("VERIFIER_CONTRACT_ID") {
+            self.network.verifier_contract_id = Some(verifier_id);
+        }
+        if let Ok(token_minter_id) = std::env::var("TOKEN_MINTER_CONTRACT_ID") {
+            self.network.token_minter_contract_id = Some(token_minter_id);
+        }
+        if let Ok(gas_sponsor) = std::env::var("GAS_SPONSOR_ADDRESS") {
+            self.gas_sponsor.address = Some(gas_sponsor);
+        }
+        if let Ok(chain_id) = std::env::var("FUEL_CHAIN_ID") {
+            self.network.chain_id = chain_id
+                .parse()
+                .map_err(|_| invalid("network.chain_id", &format!("FUEL_CHAIN_ID {} is not a number", chain_id)))?;
+        }
+        if let Ok(target) = std::env::var("FUEL_NETWORK_TARGET") {
+            self.network.target = target.parse()?;
+        }
+        if let Ok(format) = std::env::var("LOG_FORMAT") {
+            self.logging.format = format.parse()?;
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> StdResult<(), ConfigError> {
+        if self.network.provider_url.is_empty() {
+            return Err(invalid("network.provider_url", "is empty"));
+        }
+        // the contract ids are only needed where they are used, deploy runs before they exist.
+        self.verifier_contract_id()?;
+        self.token_minter_contract_id()?;
+        self.verifier_salt()?;
+        self.token_minter_salt()?;
+        self.protocol_fee_recipient()?;
+        if self.protocol_fee.bps > 10_000 {
+            return Err(invalid("protocol_fee.bps", "is over 10000"));
+        }
+        // a shared network has a gas sponsor of its own, not whichever key deployed.
+        if self.gas_sponsor_address()?.is_none() && self.profile == Profile::Testnet {
+            return Err(invalid("gas_sponsor.address", "is required by the testnet profile"));
+        }
+        if self.gas_sponsor.gas_amount == 0 || self.gas_sponsor.gas_amount > self.gas_sponsor.budget {
+            return Err(invalid("gas_sponsor.gas_amount", "must be above 0 and at most the budget"));
+        }
+        if self.polling.interval_ms == 0 || self.polling.interval_ms >= self.polling.timeout_secs * 1_000 {
+            return Err(invalid("polling.interval_ms", "must be above 0 and below the timeout"));
+        }
+        if self.polling.max_interval_ms < self.polling.interval_ms {
+            return Err(invalid("polling.max_interval_ms", "is below polling.interval_ms"));
+        }
+        if self.polling.backoff_factor == 0 {
+            return Err(invalid("polling.backoff_factor", "must be above 0"));
+        }
+        if self.resubmit.max_attempts == 0 {
+            return Err(invalid("resubmit.max_attempts", "must be above 0"));
+        }
+        if self.tx_policy.script_gas_limit == Some(0) {
+            return Err(invalid("tx_policy.script_gas_limit", "must be above 0"));
+        }
+        if let (Some(maturity), Some(expiration)) = (self.tx_policy.maturity_blocks, self.tx_policy.expiration_blocks) {
+            if maturity >= expiration {
+                return Err(invalid("tx_policy.maturity_blocks", "must be below tx_policy.expiration_blocks"));
+            }
+        }
+        if self.swap.tolerance_bps > 10_000 {
+            return Err(invalid("swap.tolerance_bps", "is over 10000"));
+        }
+        if self.relay.sweep_interval_secs == 0 {
+            return Err(invalid("relay.sweep_interval_secs", "must be above 0"));
+        }
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(&self.logging.level) {
+            return Err(invalid("logging.level", &e.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn verifier_contract_id(&self) -> StdResult<Option<ContractId>, ConfigError> {
+        self.network.verifier_contract_id
+            .as_deref()
+            .map(|id| ContractId::from_str(id).map_err(|_| invalid("network.verifier_contract_id", "is not a contract id")))
+            .transpose()
+    }
+
+    pub fn token_minter_contract_id(&self) -> StdResult<Option<ContractId>, ConfigError> {
+        self.network.token_minter_contract_id
+            .as_deref()
+            .map(|id| ContractId::from_str(id).map_err(|_| invalid("network.token_minter_contract_id", "is not a contract id")))
+            .transpose()
+    }
+
+    pub fn verifier_salt(&self) -> StdResult<[u8; 32], ConfigError> {
+        parse_bytes32("deployment.verifier_salt", &self.deployment.verifier_salt)
+    }
+
+    pub fn token_minter_salt(&self) -> StdResult<[u8; 32], ConfigError> {
+        parse_bytes32("deployment.token_minter_salt", &self.deployment.token_minter_salt)
+    }
+
+    pub fn protocol_fee_recipient(&self) -> StdResult<Address, ConfigError> {
+        Ok(Address::from(parse_bytes32("protocol_fee.recipient", &self.protocol_fee.recipient)?))
+    }
+
+    pub fn gas_sponsor_address(&self) -> StdResult<Option<Address>, ConfigError> {
+        self.gas_sponsor.address
+            .as_deref()
+            .map(|address| Ok(Address::from(parse_bytes32("gas_sponsor.address", address)?)))
+            .transpose()
+    }
+}
+
+/// the values in overlay replace the ones in base, table by table.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn parse_bytes32(field: &'static str, value: &str) -> StdResult<[u8; 32], ConfigError> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|e| invalid(field, &e.to_string()))?;
+    bytes.try_into().map_err(|_| invalid(field, "is not 32 bytes"))
+}
+
+fn invalid(field: &'static str, message: &str) -> ConfigError {
+    ConfigError::Invalid { field, message: message.to_string() }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_field(config: &Config) -> &'static str {
+        match config.validate() {
+            Err(ConfigError::Invalid { field, .. }) => field,
+            other => panic!("expected an invalid field, got {:?}", other),
+        }
+    }
+
+    fn profile_table(profile: Profile) -> toml::Table {
+        let toml::Value::Table(table) = toml::Value::try_from(Config::for_profile(profile)).unwrap() else {
+            unreachable!("a config serializes to a table");
+        };
+        table
+    }
+
+    #[test]
+    fn profile_defaults_are_valid() {
+        Config::for_profile(Profile::Local).validate().unwrap();
+        Config::for_profile(Profile::Custom).validate().unwrap();
+    }
+
+    #[test]
+    fn testnet_requires_a_gas_sponsor() {
+        let mut config = Config::for_profile(Profile::Testnet);
+        assert_eq!(invalid_field(&config), "gas_sponsor.address");
+
+        config.gas_sponsor.address = Some(format!("0x{}", hex::encode([7u8; 32])));
+        config.validate().unwrap();
+        assert_eq!(config.gas_sponsor_address().unwrap(), Some(Address::new([7u8; 32])));
+    }
+
+    #[test]
+    fn invalid_values_name_their_field() {
+        let mut config = Config::local();
+        config.protocol_fee.bps = 10_001;
+        assert_eq!(invalid_field(&config), "protocol_fee.bps");
+
+        let mut config = Config::local();
+        config.protocol_fee.recipient = "0x1234".to_string();
+        assert_eq!(invalid_field(&config), "protocol_fee.recipient");
+
+        let mut config = Config::local();
+        config.network.verifier_contract_id = Some("not hex".to_string());
+        assert_eq!(invalid_field(&config), "network.verifier_contract_id");
+
+        let mut config = Config::local();
+        config.gas_sponsor.gas_amount = config.gas_sponsor.budget + 1;
+        assert_eq!(invalid_field(&config), "gas_sponsor.gas_amount");
+
+        let mut config = Config::local();
+        config.polling.interval_ms = config.polling.timeout_secs * 1_000;
+        assert_eq!(invalid_field(&config), "polling.interval_ms");
+
+        let mut config = Config::local();
+        config.polling.max_interval_ms = config.polling.interval_ms - 1;
+        assert_eq!(invalid_field(&config), "polling.max_interval_ms");
+
+        let mut config = Config::local();
+        config.tx_policy.script_gas_limit = Some(0);
+        assert_eq!(invalid_field(&config), "tx_policy.script_gas_limit");
+
+        let mut config = Config::local();
+        config.tx_policy.maturity_blocks = Some(10);
+        config.tx_policy.expiration_blocks = Some(10);
+        assert_eq!(invalid_field(&config), "tx_policy.maturity_blocks");
+
+        let mut config = Config::local();
+        config.swap.tolerance_bps = 10_001;
+        assert_eq!(invalid_field(&config), "swap.tolerance_bps");
+    }
+
+    #[test]
+    fn merge_replaces_values_table_by_table() {
+        let mut base: toml::Table = toml::from_str("a = 1\n[t]\nx = 1\ny = 2\n").unwrap();
+        let overlay: toml::Table = toml::from_str("b = 3\n[t]\ny = 4\n").unwrap();
+        merge_tables(&mut base, overlay);
+
+        let expected: toml::Table = toml::from_str("a = 1\nb = 3\n[t]\nx = 1\ny = 4\n").unwrap();
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn file_overlays_the_profile_defaults() {
+        let mut table = profile_table(Profile::Local);
+        let file: toml::Table = toml::from_str("[protocol_fee]\nbps = 50\n[network]\ntarget = \"external\"\n").unwrap();
+        merge_tables(&mut table, file);
+        let config: Config = toml::Value::Table(table).try_into().unwrap();
+
+        let mut expected = Config::local();
+        expected.protocol_fee.bps = 50;
+        expected.network.target = TargetKind::External;
+        assert_eq!(config.protocol_fee, expected.protocol_fee);
+        assert_eq!(config.network, expected.network);
+        assert_eq!(config.polling, expected.polling);
+        assert_eq!(config.relay, expected.relay);
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let mut table = profile_table(Profile::Local);
+        merge_tables(&mut table, toml::from_str("typo = 1\n").unwrap());
+        let config: StdResult<Config, toml::de::Error> = toml::Value::Table(table).try_into();
+        assert!(config.is_err());
+    }
+}
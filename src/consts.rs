@@ -1,12 +1,21 @@
+// the chain id of the EIP-712 domain the intents are signed in, fixed by the contracts.
+pub const CHAIN_ID_FUEL: u64 = 9889;
+// pub const CHAIN_ID_FUEL: u64 = 1;
 
+// the config file read when no other is given.
+pub const CONFIG_DEFAULT_PATH: &str = "intent-swap.toml";
 
+// the config defaults, see config::Config::for_profile.
+pub const DEFAULT_PROVIDER_URL: &str = "127.0.0.1:4000";
+pub const TESTNET_PROVIDER_URL: &str = "https://testnet.fuel.network/v1/graphql";
 
-pub const CHAIN_ID_FUEL: u64 = 9889;
-// pub const CHAIN_ID_FUEL: u64 = 1;
+// protocol fee configured on the SwapVerifier, paid in the intent input asset.
+pub const DEFAULT_PROTOCOL_FEE_RECIPIENT: &str = "0x5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe5afe";
+pub const DEFAULT_PROTOCOL_FEE_BPS: u64 = 30;
 
-// evm address: 0x222227f7e08997ee7457a0a772e417ca5462906d
-// private key: 0b4895d01ae5996f3fafd5e008f7802b3f8ccd3440ec9076d04c3d9b00593b5b
-pub const SENDER_EVM_SK: &str = "0b4895d01ae5996f3fafd5e008f7802b3f8ccd3440ec9076d04c3d9b00593b5b";
+// gas sponsor, the base asset amount to cover the gas of one tx, and the total budget it pays.
+pub const DEFAULT_GAS_SPONSOR_GAS_AMOUNT: u64 = 100_000;
+pub const DEFAULT_GAS_SPONSOR_BUDGET: u64 = 1_000_000;
 
-//
-pub const FUEL_EMPTY_WALLET_SK: &str = "be904f9ecaf07479440e1cbdf6611de88648ef87fe1b53cfe6fe840a5cf8c023";
\ No newline at end of file
+// intent relay, the address it listens on.
+pub const DEFAULT_RELAY_ADDR: &str = "127.0.0.1:8787";
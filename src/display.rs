@@ -1,7 +1,7 @@
 use std::str::FromStr;
 use std::collections::HashMap;
 use serde_json::Value;
-use tracing::{info, error};
+use tracing::{debug, info, error};
 use fuels::{
     accounts::wallet::WalletUnlocked,
     prelude::*,
@@ -59,83 +59,34 @@ impl From<Address> for FuelAddress {
 pub mod display {
     use super::*;
 
+    /// the balances of the address, and (at most 25) coins of each asset, as debug events.
     pub async fn print_utxos_for_address(
         provider: &Provider,
         bech32addr: &Bech32Address
     ) {
-        println!("get utxos for address:");
-
-        let some_address: Address = Address::from(bech32addr);
-
-        println!("\naddress {} UTXOs:", hex::encode(some_address));
+        let address = hex::encode(Address::from(bech32addr));
 
         let balances = provider.get_balances(bech32addr).await.unwrap();
         print_balances(&balances);
 
-        // println!("--------------------------------");
         for (key, value) in balances.iter() {
-            println!("Asset(key): {},\tTotalValue: {}", key, value);
-            // print!("\n");
-            println!("Index:\tUTXO_txid (tx_id):\t\t\t\t\t\t\tAmount:\t\tStatus:");
-
-
+            debug!(address = %address, asset_id = %key, balance = value, "asset utxos");
 
             let ass_id = AssetId::from_str(key).unwrap();
-
-            let x_max_utxos: usize = 25;
-            for i in 0..x_max_utxos {
-                let coins = provider.get_coins(&bech32addr, ass_id).await.unwrap();
-                let c_len = coins.len();
-                // println!("coins length = {}", c_len);
-
-                if i < coins.len() {
-                    //index:
-                    print!("{}", i);
-                    let blah_coin = &coins[i];
-
-                    let c_utxo_id = blah_coin.utxo_id;
-                    // Serialize UtxoId to JSON
-                    let json_str = serde_json::to_string(&c_utxo_id).unwrap();
-                    //println!("Serialized UtxoId: {}", json_str);
-
-                    // Parse JSON string into a serde_json::Value
-                    let json_value: Value = serde_json::from_str(&json_str).unwrap();
-
-                    // Access the tx_id field from the JSON value
-                    let tx_id_from_json: Option<String> = serde_json::from_value(json_value["tx_id"].clone()).unwrap();
-                    //.map(|bytes: Bytes32| hex::encode(&bytes.bytes()));
-
-                    // tx_id from within UtxoId { tx_id: ---, output_index: --- }
-                    match tx_id_from_json {
-                        Some(tx_id) => print!("\t{}", tx_id),
-                        None => println!("Failed to extract tx_id"),
-                    }
-
-                    let c_amount = blah_coin.amount;
-                    print!("\t{}", c_amount);
-
-                    let c_status = blah_coin.status.clone();
-                    match c_status {
-                        CoinStatus::Unspent => print!("\t\tUnspent"),
-                        CoinStatus::Spent => print!("\t\tSpent"),
-                    }
-
-                    // println!("\n---");
-                    // println!("blah_coin[{}]: {:?}", i, blah_coin);
-
-                    print!("\n");
-                } else {
-                    // println!("Index {} is out of bounds for x_max_utxos ({}).", i, x_max_utxos);
-                    print!("\n");
-                    break; // Exit the loop if there are less than x Coins for particular AssetId owned.
-                }
+            let coins = provider.get_coins(bech32addr, ass_id).await.unwrap();
+            for (index, coin) in coins.iter().take(25).enumerate() {
+                debug!(
+                    address = %address,
+                    asset_id = %key,
+                    index,
+                    tx_id = %coin.utxo_id.tx_id(),
+                    output_index = coin.utxo_id.output_index(),
+                    amount = coin.amount,
+                    status = ?coin.status,
+                    "utxo",
+                );
             }
-
         }
-
-        println!("\nAddress balances:");
-        let balances = provider.get_balances(bech32addr).await.unwrap();
-        println!("{:#?}\n", balances);
     }
 
 /*
@@ -362,9 +313,8 @@ pub mod display {
 
 
 pub fn print_balances(balances: &HashMap<String, u64>) {
-    println!("Asset: \t\t\tBalance:");
     for (key, value) in balances.iter() {
-        println!("{} \t{}", print_truncated_32byte_hex(hex::decode(key).unwrap().as_slice()), value);
+        debug!(asset_id = %print_truncated_32byte_hex(hex::decode(key).unwrap().as_slice()), balance = value, "balance");
     }
 }
 
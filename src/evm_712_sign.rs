@@ -1,9 +1,11 @@
 
 use std::str::FromStr;
+use tracing::{debug, instrument};
 use ethers::{
     signers::LocalWallet,
     types::{
-        U256, Signature,
+        U256, Signature, RecoveryMessage,
+        Address as EthAddress,
     },
 };
 use crate::consts::*;
@@ -30,6 +32,7 @@ pub mod build_eip712_genio_ethers {
         outputasset: H256,
         outputamount: U256,
         tolerance: U256,
+        recipient: H256,
     }
 
 
@@ -51,79 +54,149 @@ pub mod build_eip712_genio_ethers {
         result
     }
 
-    pub async fn get_sig_eip712_by_ethers_genio(
+    fn build_genio(
         assets_in: [[u8; 32]; 5],
         utxoids_in:[[u8; 32]; 5],
         amounts_in: [[u8; 32]; 5],
         asset_out: [u8; 32],
         amount_out: [u8; 32],
         tolerance: [u8; 32],
-    )  -> Vec<u8> {
-
-        println!("Input Amounts:");
-        for (i, amount) in amounts_in.iter().enumerate() {
-            let amount_u256 = U256::from_big_endian(amount);
-            println!("  Amount {} : {}", i, amount_u256);
-        }
-        println!("\nOutput Amount:");
-        let out_amount_u256 = U256::from_big_endian(&amount_out);
-        println!("  Amount: {}", out_amount_u256);
-
-
-        let tx = GenIO {
+        recipient: [u8; 32],
+    ) -> GenIO {
+        GenIO {
             inputassets: convert_to_h256_array(assets_in),
             inpututxoids: convert_to_h256_array(utxoids_in),
             inputamounts: convert_to_u256_array(amounts_in),
             outputasset: H256::from_slice(&asset_out),
             outputamount: U256::from_big_endian(&amount_out),
             tolerance: U256::from(tolerance),
-        };
+            recipient: H256::from_slice(&recipient),
+        }
+    }
+
+    /// the EIP-712 digest of the GenIO, the hash the sender signs.
+    pub fn get_eip712_digest_genio(
+        assets_in: [[u8; 32]; 5],
+        utxoids_in:[[u8; 32]; 5],
+        amounts_in: [[u8; 32]; 5],
+        asset_out: [u8; 32],
+        amount_out: [u8; 32],
+        tolerance: [u8; 32],
+        recipient: [u8; 32],
+    ) -> [u8; 32] {
+        build_genio(assets_in, utxoids_in, amounts_in, asset_out, amount_out, tolerance, recipient)
+            .encode_eip712()
+            .unwrap()
+    }
+
+    /// the compact EIP-712 signature of the GenIO. The span never records signer_sk.
+    #[instrument(
+        name = "sign_intent",
+        skip_all,
+        fields(
+            amounts_in = ?amounts_in.iter().map(|amount| U256::from_big_endian(amount)).collect::<Vec<_>>(),
+            asset_out = %hex::encode(asset_out),
+            amount_out = %U256::from_big_endian(&amount_out),
+            signer = tracing::field::Empty,
+        ),
+    )]
+    pub async fn get_sig_eip712_by_ethers_genio(
+        signer_sk: &str,
+        assets_in: [[u8; 32]; 5],
+        utxoids_in:[[u8; 32]; 5],
+        amounts_in: [[u8; 32]; 5],
+        asset_out: [u8; 32],
+        amount_out: [u8; 32],
+        tolerance: [u8; 32],
+        recipient: [u8; 32],
+    )  -> Vec<u8> {
+        let tx = build_genio(assets_in, utxoids_in, amounts_in, asset_out, amount_out, tolerance, recipient);
 
-        let wallet_from_key = LocalWallet::from_str(SENDER_EVM_SK).unwrap();
+        let wallet_from_key = LocalWallet::from_str(signer_sk).unwrap();
         let wallet = wallet_from_key.with_chain_id(CHAIN_ID_FUEL);
 
         // Sign the transaction
         let sig = wallet.sign_typed_data(&tx).await.expect("failed to sign typed data");
         let compact_sig = compact(&sig);
-
-        println!("Signature: {:?}", sig);
-        println!("Compact Sig: {}", hex::encode(compact_sig));
-
-        // Extract r, s, v from the signature
-        let mut r = [0u8; 32];
-        let mut s = [0u8; 32];
-        sig.r.to_big_endian(&mut r);
-        sig.s.to_big_endian(&mut s);
-        let v = sig.v as u8;
+        tracing::Span::current().record("signer", tracing::field::display(hex::encode(wallet.address())));
 
         // Verify the components of the EIP-712 structure
         let domain_separator = tx.domain().unwrap().separator();
         let type_hash = GenIO::type_hash().unwrap();
         let struct_hash = tx.struct_hash().unwrap();
         let encoded = tx.encode_eip712().unwrap();
-
-
-        // Print out the results for verification
-        println!("Wallet Address: {}", hex::encode(wallet.address()));
-        println!(" ");
-        println!("Domain Separator: 0x{}", hex::encode(domain_separator));
-        println!("Type Hash: 0x{}", hex::encode(type_hash));
-        println!("Struct Hash: 0x{}", hex::encode(struct_hash));
-        println!("Encoded EIP-712: 0x{}", hex::encode(encoded));
-        println!(" ");
-        println!("Signature (r): 0x{}", hex::encode(r));
-        println!("Signature (s): 0x{}", hex::encode(s));
-        println!("Signature (v): {}", v);
+        debug!(
+            domain_separator = %hex::encode(domain_separator),
+            type_hash = %hex::encode(type_hash),
+            struct_hash = %hex::encode(struct_hash),
+            digest = %hex::encode(encoded),
+            "eip712 digest",
+        );
 
         // Verify the signature
         let signer = sig.recover(encoded).expect("failed to recover signer");
-        println!("Signer: {}", hex::encode(signer));
+        debug!(signature = %hex::encode(compact_sig), recovered = %hex::encode(signer), "intent signed");
 
         compact_sig.to_vec()
     }
 
 }
 
+/// signs plain transfers from the owner predicate, with the owner's EVM key.
+pub mod personal_sign_ethers {
+
+    use super::*;
+    use ethers_signers::Signer as EthSigner;
+    use fuels::{
+        prelude::Bech32Address,
+        core::traits::Signer,
+        crypto::{Message, Signature as FuelSignature},
+        types::errors::{error, Result as FuelResult},
+    };
+
+    /// a fuels Signer that personal_signs (EIP-191) the tx id with an EVM key, and returns the
+    /// compact signature as the witness. Added to a tx builder, the owner predicate verifies it.
+    #[derive(Clone, Debug)]
+    pub struct EvmOwnerSigner {
+        wallet: LocalWallet,
+        predicate_address: Bech32Address,
+    }
+
+    impl EvmOwnerSigner {
+        pub fn new(evm_sk: &str, predicate_address: Bech32Address) -> Self {
+            let wallet = LocalWallet::from_str(evm_sk).unwrap().with_chain_id(CHAIN_ID_FUEL);
+            EvmOwnerSigner {
+                wallet,
+                predicate_address,
+            }
+        }
+    }
+
+    /// the compact personal_sign (EIP-191) signature of message with an EVM key.
+    #[instrument(name = "sign_transfer", skip_all, fields(message = %hex::encode(message)))]
+    pub async fn personal_sign_compact(evm_sk: &str, message: &[u8]) -> Vec<u8> {
+        let wallet = LocalWallet::from_str(evm_sk).unwrap().with_chain_id(CHAIN_ID_FUEL);
+        let sig = wallet.sign_message(message).await.expect("failed to personal_sign");
+        compact(&sig).to_vec()
+    }
+
+    #[async_trait::async_trait]
+    impl Signer for EvmOwnerSigner {
+        async fn sign(&self, message: Message) -> FuelResult<FuelSignature> {
+            let sig = self.wallet
+                .sign_message(*message)
+                .await
+                .map_err(|e| error!(Other, "failed to personal_sign the tx id: {e}"))?;
+            Ok(FuelSignature::from_bytes(compact(&sig)))
+        }
+
+        fn address(&self) -> &Bech32Address {
+            &self.predicate_address
+        }
+    }
+
+}
+
 // credit: fuel-labs
 fn compact(signature: &Signature) -> [u8; 64] {
     let shifted_parity = U256::from(signature.v - 27) << 255;
@@ -140,4 +213,31 @@ fn compact(signature: &Signature) -> [u8; 64] {
     sig[32..64].copy_from_slice(&s_bytes);
 
     sig
-}
\ No newline at end of file
+}
+
+/// reverses compact, the 64 byte EIP-2098 signature to r, s, v.
+pub fn decompact(compact_sig: &[u8]) -> Option<Signature> {
+    if compact_sig.len() != 64 {
+        return None;
+    }
+    let r = U256::from_big_endian(&compact_sig[..32]);
+    let y_parity_and_s = U256::from_big_endian(&compact_sig[32..64]);
+    let y_parity = y_parity_and_s.bit(255);
+    let s = y_parity_and_s & !(U256::one() << 255);
+
+    Some(Signature {
+        r,
+        s,
+        v: 27 + y_parity as u64,
+    })
+}
+
+/// the EVM address that made the compact signature of message.
+///
+/// a H256 message is a digest (e.g. the EIP-712 digest), bytes are personal_sign (EIP-191) data.
+pub fn recover_compact_signer<M: Into<RecoveryMessage>>(
+    message: M,
+    compact_sig: &[u8],
+) -> Option<EthAddress> {
+    decompact(compact_sig)?.recover(message).ok()
+}
@@ -32,10 +32,40 @@ pub mod conversions {
         b
     }
 
+    // Convert the low 8 bytes of a big-endian Bits256 into a u64
+    pub fn bits256_to_u64(a: Bits256) -> u64 {
+        let mut bytes8arr: [u8; 8] = [0; 8];
+        bytes8arr.copy_from_slice(&a.0[24..32]);
+        u64::from_be_bytes(bytes8arr)
+    }
+
+}
+
+pub mod swap_math {
+
+    /// The protocol fee on a total input amount, rounded down. Mirrors calc_fee_amount()
+    /// in the SwapVerifier so both sides agree on the exact amount.
+    pub fn fee_amount(total_in: u64, fee_bps: u64) -> u64 {
+        (total_in / 10_000) * fee_bps + ((total_in % 10_000) * fee_bps) / 10_000
+    }
+
+    /// The (lower, upper) bounds the SwapVerifier accepts for the output amount,
+    /// given the expected output amount and tolerance in bps.
+    pub fn tolerance_bounds(amount_out: u64, tolerance_bps: u64) -> (u64, u64) {
+        let tolerance_amount = (amount_out * tolerance_bps) / 10_000;
+        (amount_out.saturating_sub(tolerance_amount), amount_out + tolerance_amount)
+    }
+
+    /// The amount of asset_in the solver receives after paying the protocol fee.
+    pub fn solver_net_amount_in(total_in: u64, fee_bps: u64) -> u64 {
+        total_in - fee_amount(total_in, fee_bps)
+    }
 }
 
 pub mod display {
+    use tracing::debug;
 
+    /// a line of dashes ending in the section name, as a debug event.
     pub fn print_separator_line(length: usize, suffix: &str) {
         // Calculate the length of the dashes
         let dash_length = if length > suffix.len() {
@@ -44,6 +74,41 @@ pub mod display {
             0
         };
         let dashes = "-".repeat(dash_length);
-        println!("\n{} {}", dashes, suffix);
+        debug!(section = suffix, "{}", dashes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{conversions::*, swap_math::*};
+
+    // the same amounts as the calc_fee_amount tests of intentswap_712_tools.
+    #[test]
+    fn fee_amount_matches_the_verifier() {
+        assert_eq!(fee_amount(1_520_000_000, 30), 4_560_000);
+        assert_eq!(fee_amount(u64::MAX, 30), 55_340_232_221_128_654);
+        assert_eq!(fee_amount(1_520_000_000, 0), 0);
+        assert_eq!(fee_amount(9_999, 1), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn solver_receives_the_input_less_the_fee() {
+        assert_eq!(solver_net_amount_in(1_520_000_000, 30), 1_515_440_000);
+        assert_eq!(solver_net_amount_in(1_520_000_000, 0), 1_520_000_000);
+        assert_eq!(solver_net_amount_in(1_520_000_000, 10_000), 0);
+    }
+
+    #[test]
+    fn tolerance_bounds_around_the_amount_out() {
+        assert_eq!(tolerance_bounds(2_000_000_000, 250), (1_950_000_000, 2_050_000_000));
+        assert_eq!(tolerance_bounds(2_000_000_000, 0), (2_000_000_000, 2_000_000_000));
+        assert_eq!(tolerance_bounds(2_000_000_000, 10_000), (0, 4_000_000_000));
+    }
+
+    #[test]
+    fn u64_round_trips_through_bits256() {
+        for value in [0, 1, 4_560_000, u64::MAX] {
+            assert_eq!(bits256_to_u64(u64_to_bits256(value)), value);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+use std::time::Duration;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+use fuels::{
+    prelude::*,
+    tx::Receipt,
+    types::{
+        Bytes32,
+        tx_status::TxStatus,
+    },
+};
+
+use crate::{
+    config::{ArtifactPaths, PollingConfig},
+    interfaces::{
+        generalized_swap_verifier_interface::{
+            build_intent_messenger_tx,
+            GenIO,
+            Intent,
+            SwapVerifier,
+        },
+        owner_predicate::OwnerBinding,
+    },
+    receipt_show::receipt_sniffer::{
+        TxStatusResult,
+        wait_for_tx_success,
+    },
+    relay::{
+        intent_book::{IntentBook, SignedIntent},
+        RelayError,
+    },
+    sponsor::{GasSponsor, GasSponsorError},
+};
+
+
+#[derive(Error, Debug)]
+pub enum IndexerError {
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("Failed to decode the intent logs: {0}")]
+    Decode(String),
+    #[error("Indexer cursor {path}: {message}")]
+    Cursor { path: String, message: String },
+    #[error(transparent)]
+    GasSponsor(#[from] GasSponsorError),
+    #[error("Transaction failed: {0}")]
+    TxFailed(String),
+}
+
+
+/// publish a signed intent on chain, through the SwapVerifier intent_messenger, which logs it.
+/// Returns the id of the tx once it succeeded.
+pub async fn publish_intent_onchain(
+    provider: &Provider,
+    polling: &PollingConfig,
+    swapverifier_instance: &SwapVerifier<WalletUnlocked>,
+    gas_sponsor: &mut GasSponsor,
+    sender_evm_address: [u8; 20],
+    gio_data: GenIO,
+    signature_bytes: Vec<u8>,
+) -> StdResult<Bytes32, IndexerError> {
+    let stb = build_intent_messenger_tx(
+        swapverifier_instance.clone(),
+        sender_evm_address,
+        gio_data,
+        signature_bytes,
+    ).await;
+
+    let tx = gas_sponsor.sponsor(provider, stb).await?;
+    let tx_id = provider
+        .send_transaction(tx)
+        .await
+        .map_err(|e| IndexerError::TxFailed(e.to_string()))?;
+
+    match wait_for_tx_success(provider, &tx_id, polling).await {
+        TxStatusResult::Ok { .. } => Ok(tx_id),
+        TxStatusResult::Err { error, .. } => Err(IndexerError::TxFailed(format!("{:?}", error))),
+    }
+}
+
+
+/// the next block height to index, saved as JSON after every block.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexerCursor {
+    pub next_height: u32,
+}
+
+impl IndexerCursor {
+
+    /// the saved cursor, or a cursor at the first block if there is none yet.
+    pub fn load(path: &Path) -> StdResult<Self, IndexerError> {
+        if !path.exists() {
+            return Ok(IndexerCursor::default());
+        }
+        let json = std::fs::read_to_string(path).map_err(|e| cursor_error(path, e))?;
+        serde_json::from_str(&json).map_err(|e| cursor_error(path, e))
+    }
+
+    /// written to a temporary file first, so a restart never reads a partly written cursor.
+    pub fn save(&self, path: &Path) -> StdResult<(), IndexerError> {
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(self).unwrap()).map_err(|e| cursor_error(path, e))?;
+        std::fs::rename(&tmp_path, path).map_err(|e| cursor_error(path, e))
+    }
+}
+
+fn cursor_error(path: &Path, e: impl std::fmt::Display) -> IndexerError {
+    IndexerError::Cursor {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    }
+}
+
+
+/// walks the blocks for the intents logged by the SwapVerifier intent_messenger, and adds
+/// the valid ones to the intent book. Resumes from the saved cursor after a restart.
+pub struct IntentIndexer {
+    provider: Provider,
+    swapverifier_instance: SwapVerifier<WalletUnlocked>,
+    artifacts: ArtifactPaths,
+    binding: OwnerBinding,
+    cursor_path: PathBuf,
+    cursor: IndexerCursor,
+}
+
+impl IntentIndexer {
+
+    pub fn new(
+        provider: Provider,
+        swapverifier_instance: SwapVerifier<WalletUnlocked>,
+        artifacts: ArtifactPaths,
+        binding: OwnerBinding,
+        cursor_path: PathBuf,
+    ) -> StdResult<Self, IndexerError> {
+        let cursor = IndexerCursor::load(&cursor_path)?;
+        Ok(IntentIndexer {
+            provider,
+            swapverifier_instance,
+            artifacts,
+            binding,
+            cursor_path,
+            cursor,
+        })
+    }
+
+    pub fn next_height(&self) -> u32 {
+        self.cursor.next_height
+    }
+
+    /// index the blocks from the cursor up to the latest block. Returns the intents found,
+    /// the ones that pass the relay checks are added to the book.
+    #[instrument(name = "index_blocks", skip_all, fields(from_height = self.cursor.next_height))]
+    pub async fn index_new_blocks(&mut self, book: &IntentBook) -> StdResult<Vec<SignedIntent>, IndexerError> {
+        let latest_height = self.provider
+            .latest_block_height()
+            .await
+            .map_err(|e| IndexerError::Provider(e.to_string()))?;
+
+        let mut found = vec![];
+        while self.cursor.next_height <= latest_height {
+            for intent in self.intents_in_block(self.cursor.next_height).await? {
+                // the same checks as a relay submission, an invalid intent is skipped. validate_solution
+                // logs the intent too, a filled intent is skipped as its inputs are spent. A provider
+                // error stops before the cursor is saved, so the block is indexed again.
+                match book.accept(&self.provider, &self.artifacts, &self.binding, intent.clone()).await {
+                    Ok(_) => {}
+                    Err(RelayError::Provider(e)) => return Err(IndexerError::Provider(e)),
+                    Err(e) => debug!(height = self.cursor.next_height, error = %e, "indexed intent not added"),
+                }
+                found.push(intent);
+            }
+            self.cursor.next_height += 1;
+            self.cursor.save(&self.cursor_path)?;
+        }
+        Ok(found)
+    }
+
+    /// index the new blocks every interval.
+    pub async fn run(mut self, book: IntentBook, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.index_new_blocks(&book).await {
+                warn!(height = self.cursor.next_height, error = %e, "intent indexer failed");
+            }
+        }
+    }
+
+    async fn intents_in_block(&self, height: u32) -> StdResult<Vec<SignedIntent>, IndexerError> {
+        let block = self.provider
+            .block_by_height(height.into())
+            .await
+            .map_err(|e| IndexerError::Provider(e.to_string()))?;
+        let Some(block) = block else {
+            return Ok(vec![]);
+        };
+
+        let contract_id = ContractId::from(self.swapverifier_instance.contract_id());
+        let mut intents = vec![];
+        for tx_id in block.transactions {
+            let status = self.provider
+                .tx_status(&tx_id)
+                .await
+                .map_err(|e| IndexerError::Provider(e.to_string()))?;
+            // a reverted intent_messenger call does not publish the intent.
+            let TxStatus::Success { receipts, .. } = status else {
+                continue;
+            };
+            // any contract can log a value with the Intent log id, only the SwapVerifier logs are read.
+            let verifier_receipts: Vec<Receipt> = receipts
+                .into_iter()
+                .filter(|receipt| matches!(receipt, Receipt::LogData { id, .. } if *id == contract_id))
+                .collect();
+            let logged = self.swapverifier_instance
+                .log_decoder()
+                .decode_logs_with_type::<Intent>(&verifier_receipts)
+                .map_err(|e| IndexerError::Decode(e.to_string()))?;
+            intents.extend(logged.iter().map(SignedIntent::from_intent));
+        }
+        Ok(intents)
+    }
+}
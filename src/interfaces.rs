@@ -1,19 +1,29 @@
 // use rand::prelude::Rng;
 use thiserror::Error;
 use std::result::Result as StdResult;
+use tracing::{info, instrument};
 use fuels::{
     prelude::*,
     prelude::{
         Address, ContractId,
     },
+    client::FuelClient,
+    programs::contract::Regular,
     types::{
         Bits256,
+        Bytes32,
         transaction::TxPolicies,
         input::Input as SdkInput,
         output::Output as SdkOutput,
     },
     accounts::wallet::WalletUnlocked,
 };
+use crate::config::{ArtifactPaths, Config};
+use crate::io::{utxo_input_utils, UTXOToolsError};
+use crate::helpers::{
+    conversions::{bits256_to_u64, bytes32_to_bits256, u64_to_bits256},
+    swap_math,
+};
 
 
 
@@ -22,15 +32,20 @@ use fuels::{
 pub enum GenIOError {
     #[error("No non-zero amount to swap in either input or output")]
     NoAmountToSwap,
+    #[error("No recipient set for the output asset")]
+    NoRecipient,
+    #[error("A GenIO has at most 5 inputs, got {0}")]
+    TooManyInputs(usize),
+    #[error("The input amounts overflow a u64")]
+    AmountOverflow,
+    #[error(transparent)]
+    Input(#[from] UTXOToolsError),
 }
 
 /// interfaces to SwapVerifier verifier Contract
 pub mod generalized_swap_verifier_interface {
     use super::*;
 
-    pub const VERIFIER_CONTRACT_BINARY_PATH: &str = "./contracts/contract_validator/out/debug/contract_validator.bin";
-    pub const VERIFIER_CONTRACT_STORAGE_PATH: &str = "./contracts/contract_validator/out/debug/contract_validator-storage_slots.json";
-
     abigen!(
         Contract(
         name = "SwapVerifier",
@@ -39,47 +54,229 @@ pub mod generalized_swap_verifier_interface {
     );
 
 
-    pub fn get_contract_verifier_path() -> (String, String) {
-        (VERIFIER_CONTRACT_BINARY_PATH.to_string(), VERIFIER_CONTRACT_STORAGE_PATH.to_string())
+    pub fn get_contract_verifier_path(artifacts: &ArtifactPaths) -> (String, String) {
+        (artifacts.verifier_binary.clone(), artifacts.verifier_storage.clone())
     }
 
+    /// The protocol fee the SwapVerifier is deployed with (FEE_RECIPIENT, FEE_BPS configurables).
+    /// The fee is paid in the intent input asset, on the total of the intent input amounts.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ProtocolFee {
+        pub recipient: Address,
+        pub bps: u64,
+    }
 
-    pub async fn contract_verifier_instance(
-        wallet: WalletUnlocked,
-    ) -> (SwapVerifier<WalletUnlocked>, ContractId) {
+    impl ProtocolFee {
 
-        // deploy with salt:
-        // let mut rng = rand::thread_rng();
-        // let salt = rng.gen::<[u8; 32]>();
-        let salt: [u8; 32] = [01u8; 32];
-        println!("SwapVerifier salt: {}", hex::encode(salt));
+        /// no protocol fee, the verifier skips the fee check.
+        pub fn none() -> Self {
+            ProtocolFee {
+                recipient: Address::zeroed(),
+                bps: 0,
+            }
+        }
 
-        let storage_configuration = StorageConfiguration::default()
-            .add_slot_overrides_from_file(VERIFIER_CONTRACT_STORAGE_PATH)
-            .unwrap();
+        /// the fee of the config, validated when it was loaded.
+        pub fn from_config(config: &Config) -> Self {
+            ProtocolFee {
+                recipient: config.protocol_fee_recipient().unwrap(),
+                bps: config.protocol_fee.bps,
+            }
+        }
 
-        let configuration = LoadConfiguration::default()
-            .with_storage_configuration(storage_configuration)
-            .with_salt(salt);
+        /// the fee owed for the intent, on the total of the intent input amounts.
+        ///
+        /// None if the inputs are not all of the first input asset or the total overflows,
+        /// the verifier rejects such an intent when the fee is enabled.
+        pub fn fee_for_intent(&self, gio_data: &GenIO) -> Option<u64> {
+            let fee_asset = gio_data.inputassets[0];
+            let mut total_in: u64 = 0;
+            for (asset, amount) in gio_data.inputassets.iter().zip(gio_data.inputamounts.iter()) {
+                let amount = bits256_to_u64(*amount);
+                if amount == 0 {
+                    continue;
+                }
+                if *asset != fee_asset {
+                    return None;
+                }
+                total_in = total_in.checked_add(amount)?;
+            }
+            Some(swap_math::fee_amount(total_in, self.bps))
+        }
 
-        let contract_id_bech32 = Contract::load_from(
-            VERIFIER_CONTRACT_BINARY_PATH,
-            configuration,
-            )
-            .unwrap()
-            .deploy(&wallet, TxPolicies::default())
-            .await
-            .unwrap();
+        /// the Output coin paying the fee to the fee recipient, None if the fee is disabled
+        /// or not defined for the intent.
+        pub fn fee_output(&self, gio_data: &GenIO) -> Option<SdkOutput> {
+            if self.bps == 0 {
+                return None;
+            }
+            Some(SdkOutput::Coin {
+                to: self.recipient,
+                amount: self.fee_for_intent(gio_data)?,
+                asset_id: AssetId::from(gio_data.inputassets[0].0),
+            })
+        }
+    }
 
-        let cv_instance = SwapVerifier::new(contract_id_bech32.clone(), wallet);
 
-        let cv_contract_id = ContractId::from_bytes_ref(&contract_id_bech32.hash);
+    /// the SwapVerifier configurables for the protocol fee and the gas sponsor, whose
+    /// base asset inputs are not counted as part of the swap. Address::zeroed() for no sponsor.
+    ///
+    /// the owner predicate template binds the intent sender to the owner of the sender inputs.
+    pub fn get_verifier_configurables(
+        artifacts: &ArtifactPaths,
+        protocol_fee: &ProtocolFee,
+        gas_sponsor: &Address,
+    ) -> SwapVerifierConfigurables {
+        let owner_template = crate::interfaces::owner_predicate::get_owner_predicate_template(artifacts);
+        SwapVerifierConfigurables::default()
+            .with_FEE_RECIPIENT(Bits256(*protocol_fee.recipient)).unwrap()
+            .with_FEE_BPS(protocol_fee.bps).unwrap()
+            .with_GAS_SPONSOR(Bits256(**gas_sponsor)).unwrap()
+            .with_OWNER_PREDICATE_TEMPLATE(owner_template.hash).unwrap()
+            .with_OWNER_PREDICATE_OFFSET(owner_template.owner_offset).unwrap()
+            .with_OWNER_PREDICATE_VERIFIER_OFFSET(owner_template.verifier_offset).unwrap()
+            .with_OWNER_PREDICATE_FEE_RECIPIENT_OFFSET(owner_template.fee_recipient_offset).unwrap()
+            .with_OWNER_PREDICATE_FEE_BPS_OFFSET(owner_template.fee_bps_offset).unwrap()
+    }
 
-        println!("SwapVerifier ID bech32 \t: {}", contract_id_bech32.clone().to_string());
-        println!("SwapVerifier ID (hex) \t: {}", cv_contract_id);
+    #[derive(Error, Debug)]
+    pub enum VerifierError {
+        #[error("SwapVerifier artifacts: {0}")]
+        Artifacts(String),
+        #[error("Provider error: {0}")]
+        Provider(String),
+        #[error("No contract deployed at {0}")]
+        NotDeployed(ContractId),
+        #[error("The contract at {contract_id} is not the local contract_validator.bin with these configurables, its bytecode root is {deployed} instead of {expected}")]
+        BytecodeMismatch { contract_id: ContractId, deployed: Bytes32, expected: Bytes32 },
+        #[error("SwapVerifier deploy failed: {0}")]
+        Deploy(String),
+    }
+
+    /// a SwapVerifier deployment: the local build, the configurables and the salt. The contract
+    /// id follows from them, so it is known before the deploy.
+    #[derive(Clone, Debug)]
+    pub struct VerifierDeployment {
+        artifacts: ArtifactPaths,
+        configurables: SwapVerifierConfigurables,
+        salt: [u8; 32],
+    }
+
+    impl VerifierDeployment {
+
+        pub fn new(config: &Config, protocol_fee: &ProtocolFee, gas_sponsor: &Address) -> Self {
+            VerifierDeployment {
+                artifacts: config.artifacts.clone(),
+                configurables: get_verifier_configurables(&config.artifacts, protocol_fee, gas_sponsor),
+                salt: config.verifier_salt().unwrap(),
+            }
+        }
 
+        fn load(&self) -> StdResult<Contract<Regular>, VerifierError> {
+            let storage_configuration = StorageConfiguration::default()
+                .add_slot_overrides_from_file(&self.artifacts.verifier_storage)
+                .map_err(|e| VerifierError::Artifacts(e.to_string()))?;
 
-        (cv_instance, contract_id_bech32.into())
+            let configuration = LoadConfiguration::default()
+                .with_storage_configuration(storage_configuration)
+                .with_configurables(self.configurables.clone())
+                .with_salt(self.salt);
+
+            Contract::load_from(&self.artifacts.verifier_binary, configuration)
+                .map_err(|e| VerifierError::Artifacts(e.to_string()))
+        }
+
+        /// the id the SwapVerifier has once deployed, on any node.
+        pub fn contract_id(&self) -> StdResult<ContractId, VerifierError> {
+            Ok(self.load()?.contract_id())
+        }
+
+        /// the bytecode root of the local build, with the configurables set.
+        pub fn code_root(&self) -> StdResult<Bytes32, VerifierError> {
+            Ok(self.load()?.code_root())
+        }
+
+        /// deploy, unless the contract id is already deployed on the node of the wallet.
+        #[instrument(name = "deploy_verifier", skip_all, fields(salt = %hex::encode(self.salt)))]
+        pub async fn deploy(&self, wallet: &WalletUnlocked) -> StdResult<ContractId, VerifierError> {
+            let contract = self.load()?;
+            let contract_id = contract.contract_id();
+            let provider = wallet
+                .provider()
+                .ok_or(VerifierError::Provider("the wallet has no provider".to_string()))?;
+
+            if deployed_bytecode(provider, &contract_id).await?.is_some() {
+                info!(contract_id = %contract_id, "SwapVerifier already deployed");
+                return Ok(contract_id);
+            }
+            contract
+                .deploy(wallet, TxPolicies::default())
+                .await
+                .map_err(|e| VerifierError::Deploy(e.to_string()))?;
+            info!(contract_id = %contract_id, "SwapVerifier deployed");
+            Ok(contract_id)
+        }
+    }
+
+    /// a SwapVerifier instance, for a contract checked to be the local build.
+    #[derive(Clone, Debug)]
+    pub struct SwapVerifierClient {
+        pub contract_id: ContractId,
+        pub instance: SwapVerifier<WalletUnlocked>,
+    }
+
+    impl SwapVerifierClient {
+
+        /// attach to the SwapVerifier at contract_id, when its bytecode root is the one of the
+        /// deployment, instead of redeploying it.
+        pub async fn connect(
+            contract_id: ContractId,
+            account: WalletUnlocked,
+            deployment: &VerifierDeployment,
+        ) -> StdResult<Self, VerifierError> {
+            let provider = account
+                .provider()
+                .ok_or(VerifierError::Provider("the account has no provider".to_string()))?;
+            let bytecode = deployed_bytecode(provider, &contract_id)
+                .await?
+                .ok_or(VerifierError::NotDeployed(contract_id))?;
+
+            let deployed = fuels::tx::Contract::root_from_code(&bytecode);
+            let expected = deployment.code_root()?;
+            if deployed != expected {
+                return Err(VerifierError::BytecodeMismatch { contract_id, deployed, expected });
+            }
+            Ok(SwapVerifierClient {
+                contract_id,
+                instance: SwapVerifier::new(contract_id, account),
+            })
+        }
+    }
+
+    /// the bytecode of the contract, None if nothing is deployed at contract_id.
+    pub(crate) async fn deployed_bytecode(provider: &Provider, contract_id: &ContractId) -> StdResult<Option<Vec<u8>>, VerifierError> {
+        let client = FuelClient::new(provider.url()).map_err(|e| VerifierError::Provider(e.to_string()))?;
+        let contract = client
+            .contract(contract_id)
+            .await
+            .map_err(|e| VerifierError::Provider(e.to_string()))?;
+        Ok(contract.map(|contract| contract.bytecode))
+    }
+
+    /// deploy the SwapVerifier, or reuse it when the node already has the same deployment.
+    pub async fn contract_verifier_instance(
+        config: &Config,
+        wallet: WalletUnlocked,
+        protocol_fee: &ProtocolFee,
+        gas_sponsor: &Address,
+    ) -> StdResult<(SwapVerifier<WalletUnlocked>, ContractId), VerifierError> {
+
+        let deployment = VerifierDeployment::new(config, protocol_fee, gas_sponsor);
+        let cv_contract_id = deployment.deploy(&wallet).await?;
+        let cv_instance = SwapVerifier::new(cv_contract_id, wallet);
+
+        Ok((cv_instance, cv_contract_id))
     }
 
     /// helper function to simply populate the GenIO tx data struct with the tx params.
@@ -90,10 +287,14 @@ pub mod generalized_swap_verifier_interface {
         asset_out: Bits256,
         amount_out: Bits256,
         tolerance_bps: Bits256,
+        recipient: Bits256,
     ) -> StdResult<GenIO, GenIOError> {
         if !amounts_in.iter().any(|&amount| amount != Bits256::zeroed()) {
             return Err(GenIOError::NoAmountToSwap);
         }
+        if recipient == Bits256::zeroed() {
+            return Err(GenIOError::NoRecipient);
+        }
         let generalio_tx = GenIO {
             inputassets: assets_in,
             inpututxoids: utxoids_in,
@@ -101,10 +302,76 @@ pub mod generalized_swap_verifier_interface {
             outputasset: asset_out,
             outputamount: amount_out,
             tolerance: tolerance_bps,
+            recipient,
         };
         Ok(generalio_tx)
     }
 
+    /// the GenIO selling all of the inputs, each of asset_in, for amount_out of asset_out sent to
+    /// the recipient, and the total amount of the inputs.
+    pub fn genio_for_inputs(
+        inputs: &[SdkInput],
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_out: u64,
+        tolerance_bps: u64,
+        recipient: &Bech32Address,
+    ) -> StdResult<(GenIO, u64), GenIOError> {
+        if inputs.len() > 5 {
+            return Err(GenIOError::TooManyInputs(inputs.len()));
+        }
+        let mut assets_in = [Bits256::zeroed(); 5];
+        let mut utxoids_in = [Bits256::zeroed(); 5];
+        let mut amounts_in = [Bits256::zeroed(); 5];
+        let mut total_amount_in: u64 = 0;
+        for (i, input) in inputs.iter().enumerate() {
+            let (utxo_txid_bytes, _, utxo_amount) = utxo_input_utils::get_input_txid_and_amount(input)?;
+            // a contract input has no amount to sell.
+            let utxo_amount = utxo_amount.ok_or(UTXOToolsError::UnexpectedInputType)?;
+            assets_in[i] = Bits256(*asset_in);
+            utxoids_in[i] = Bits256(utxo_txid_bytes);
+            amounts_in[i] = u64_to_bits256(utxo_amount);
+            total_amount_in = total_amount_in.checked_add(utxo_amount).ok_or(GenIOError::AmountOverflow)?;
+        }
+
+        let gio_data = populate_genio(
+            assets_in,
+            utxoids_in,
+            amounts_in,
+            Bits256(*asset_out),
+            u64_to_bits256(amount_out),
+            u64_to_bits256(tolerance_bps),
+            bytes32_to_bits256(recipient.hash),
+        )?;
+        Ok((gio_data, total_amount_in))
+    }
+
+    /// create the Output coin of the intent output asset, sent to the recipient signed in the GenIO.
+    pub fn build_recipient_output(
+        gio_data: &GenIO,
+        amount: u64,
+    ) -> SdkOutput {
+        SdkOutput::Coin {
+            to: Address::from(gio_data.recipient.0),
+            amount,
+            asset_id: AssetId::from(gio_data.outputasset.0),
+        }
+    }
+
+    /// the reason for a validate_solution revert code, None for a code the verifier does not use.
+    pub fn verifier_revert_reason(revert_id: u64) -> Option<&'static str> {
+        match revert_id {
+            6661 => Some("intent not signed by the owner of the inputs"),
+            6662 => Some("inputs do not match the intent"),
+            6663 => Some("outputs do not match the intent"),
+            6664 => Some("utxos do not match the intent"),
+            6665 => Some("change not returned to the sender"),
+            6667 => Some("output not sent to the recipient"),
+            6668 => Some("protocol fee not paid"),
+            _ => None,
+        }
+    }
+
     pub async fn build_verify_intent_sender_tx(
         contract_instance: SwapVerifier<WalletUnlocked>,
         gio_data: GenIO,
@@ -130,19 +397,50 @@ pub mod generalized_swap_verifier_interface {
         stb
     }
 
+    /// the intent_messenger call, which logs the intent on chain for the indexers to pick up.
+    /// The gas is added by the gas sponsor.
+    pub async fn build_intent_messenger_tx(
+        contract_instance: SwapVerifier<WalletUnlocked>,
+        sender_evm_address: [u8; 20],
+        gio_data: GenIO,
+        signature_bytes: Vec<u8>,
+    ) -> ScriptTransactionBuilder {
+        let intent = Intent {
+            sender: crate::interfaces::predicate_validator::evm_address_to_bits256(sender_evm_address),
+            compsig: Bytes(signature_bytes),
+            io: gio_data,
+        };
+        contract_instance
+            .methods()
+            .intent_messenger(intent)
+            .with_tx_policies(TxPolicies::default())
+            .transaction_builder().await.unwrap()
+    }
+
+    /// the intent sender is the EVM address that signed the intent, which owns the sender inputs.
+    /// The tx_policies are usually a tx_policy::SwapTxPolicy, whose apply sizes the gas limit.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(
+        name = "build_fill",
+        skip_all,
+        fields(sender = %hex::encode(sender_evm_address), inputs = inputs.len(), outputs = outputs.len()),
+    )]
     pub async fn call_validate_solution(
         contract_instance: SwapVerifier<WalletUnlocked>,
-        tx_sender: Bits256,
+        sender_evm_address: [u8; 20],
         gio_data: GenIO,
         signature_bytes: Vec<u8>,
         inputs: Vec<SdkInput>,
         outputs: Vec<SdkOutput>,
+        protocol_fee: &ProtocolFee,
+        tx_policies: TxPolicies,
     ) -> ScriptTransactionBuilder {
 
-        // .with_script_gas_limit(840000)
+        // the solver pays the protocol fee out of the input asset it receives.
+        let fee_output = protocol_fee.fee_output(&gio_data);
 
         let intent = Intent {
-            sender: tx_sender,
+            sender: crate::interfaces::predicate_validator::evm_address_to_bits256(sender_evm_address),
             compsig: Bytes(signature_bytes),
             io: gio_data,
         };
@@ -151,7 +449,7 @@ pub mod generalized_swap_verifier_interface {
             .validate_solution(
                 intent,
             )
-            .with_tx_policies(TxPolicies::default())
+            .with_tx_policies(tx_policies)
             .transaction_builder().await.unwrap();
 
         for some_input in inputs {
@@ -160,6 +458,9 @@ pub mod generalized_swap_verifier_interface {
         for some_output in outputs {
             stb.outputs_mut().push(some_output);
         }
+        if let Some(fee_output) = fee_output {
+            stb.outputs_mut().push(fee_output);
+        }
 
         stb
     }
@@ -173,11 +474,11 @@ pub mod generalized_swap_verifier_interface {
 
 
 
-/*
+/// interfaces to the intent predicate (predicate_validator), which spends the owner's inputs
+/// for a tx that fills an intent signed by the owner, without a contract call.
 pub mod predicate_validator {
     use super::*;
-
-    pub const PREDICATE_VALIDATOR_BINARY_PATH: &str = "./contracts/predicate_validator/out/debug/predicate_validator.bin";
+    use crate::interfaces::generalized_swap_verifier_interface::{GenIO as VerifierGenIO, ProtocolFee};
 
     abigen!(
         Predicate(
@@ -186,37 +487,179 @@ pub mod predicate_validator {
         ),
     );
 
+    /// the EVM address as the b256 the predicate recovers, left padded with zeros.
+    pub fn evm_address_to_bits256(owner_evm_address: [u8; 20]) -> Bits256 {
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&owner_evm_address);
+        Bits256(padded)
+    }
+
+    pub fn get_predicate_validator_configurables(
+        protocol_fee: &ProtocolFee,
+        owner_evm_address: [u8; 20],
+    ) -> SimpleSwapPredicateValidatorConfigurables {
+        SimpleSwapPredicateValidatorConfigurables::default()
+            .with_OWNER(evm_address_to_bits256(owner_evm_address)).unwrap()
+            .with_FEE_RECIPIENT(Bits256(*protocol_fee.recipient)).unwrap()
+            .with_FEE_BPS(protocol_fee.bps).unwrap()
+    }
+
+    /// the ABI encoded Intent, used as the predicate data of every owner input.
     pub fn get_predicate_validator_data(
         sender: Bits256,
         signature_bytes: Vec<u8>,
-        asset_in: Bits256,
-        amount_in: Bits256,
-        utxoid_in: Bits256,
-        asset_out: Bits256,
-        amount_out: Bits256,
+        gio_data: &VerifierGenIO,
     ) -> Vec<u8> {
+        let intent = Intent {
+            sender,
+            compsig: Bytes(signature_bytes),
+            io: GenIO {
+                inputassets: gio_data.inputassets,
+                inpututxoids: gio_data.inpututxoids,
+                inputamounts: gio_data.inputamounts,
+                outputasset: gio_data.outputasset,
+                outputamount: gio_data.outputamount,
+                tolerance: gio_data.tolerance,
+                recipient: gio_data.recipient,
+            },
+        };
+        SimpleSwapPredicateValidatorEncoder::default()
+            .encode_data(intent)
+            .unwrap()
+    }
+
+    /// the predicate bytecode and address for the owner's EVM address, with the protocol fee
+    /// its fills pay.
+    pub fn get_predicate_validator_info(
+        artifacts: &ArtifactPaths,
+        protocol_fee: &ProtocolFee,
+        owner_evm_address: [u8; 20],
+    ) -> (Vec<u8>, Bech32Address, Address) {
+
+        let predicate_validator_bytecode = std::fs::read(&artifacts.predicate_validator_binary).unwrap();
+        let configurables = get_predicate_validator_configurables(
+            protocol_fee,
+            owner_evm_address,
+        );
+        let predicate = Predicate::from_code(predicate_validator_bytecode.clone())
+            .with_configurables(configurables);
+
+        let predicate_b32addr = predicate.address().clone();
+        let predicate_address: Address = predicate.address().into();
+        let new_bytecode = predicate.code().to_vec();
+
+        (new_bytecode, predicate_b32addr, predicate_address)
+    }
+
+    /// a swap tx without a contract call. The owner inputs are spent through the predicate, with
+    /// the Intent as predicate data. The gas is added by the gas sponsor.
+    pub fn build_predicate_swap(
+        owner_inputs: Vec<SdkInput>,
+        predicate_data: Vec<u8>,
+        other_inputs: Vec<SdkInput>,
+        outputs: Vec<SdkOutput>,
+    ) -> ScriptTransactionBuilder {
+        let mut inputs = utxo_input_utils::with_predicate_data(owner_inputs, predicate_data);
+        inputs.extend(other_inputs);
+
+        ScriptTransactionBuilder::prepare_transfer(
+            inputs,
+            outputs,
+            TxPolicies::default(),
+        )
+    }
+
+}
+
+/// interfaces to the owner predicate, the user's wallet controlled by their EVM address.
+pub mod owner_predicate {
+    use super::*;
+    use crate::interfaces::generalized_swap_verifier_interface::{GenIO as VerifierGenIO, ProtocolFee};
+    use crate::interfaces::predicate_validator::evm_address_to_bits256;
+    use crate::evm_712_sign::personal_sign_ethers::EvmOwnerSigner;
+    use sha2::Digest;
 
-        let mut encoded_bytes: Vec<u8> = Vec::new();
-        encoded_bytes.push(0x00);
-        let predicate_data = SimpleSwapPredicateValidatorEncoder::default().encode_data(
-            Bytes(encoded_bytes),
+    abigen!(
+        Predicate(
+            name = "OwnerPredicate",
+            abi = "./contracts/owner_predicate/out/debug/owner_predicate-abi.json"
+        ),
+    );
+
+    /// the SwapVerifier an owner predicate is bound to, and its protocol fee. An intent spend of
+    /// the predicate must have this verifier as a contract input and pay this fee.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct OwnerBinding {
+        pub verifier: ContractId,
+        pub protocol_fee: ProtocolFee,
+    }
+
+    /// the sha256 of the owner predicate bytecode with zero configurables, and the offsets of
+    /// the configurables. The SwapVerifier reads the owner and the verifier of the sender
+    /// inputs from the predicate bytecode with these.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct OwnerPredicateTemplate {
+        pub hash: Bits256,
+        pub owner_offset: u64,
+        pub verifier_offset: u64,
+        pub fee_recipient_offset: u64,
+        pub fee_bps_offset: u64,
+    }
+
+    /// the template of the compiled owner predicate, its configurables are the defaults (zero)
+    /// so it is the template as is.
+    pub fn get_owner_predicate_template(artifacts: &ArtifactPaths) -> OwnerPredicateTemplate {
+        let owner_predicate_bytecode = std::fs::read(&artifacts.owner_predicate_binary).unwrap();
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(&owner_predicate_bytecode);
+        let template_hash: [u8; 32] = hasher.finalize().into();
+
+        let abi: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(&artifacts.owner_predicate_abi).unwrap()
         ).unwrap();
-        predicate_data
+        let configurables = abi["configurables"].as_array().unwrap();
+        let offset = |name: &str| {
+            configurables
+                .iter()
+                .find(|c| c["name"] == name)
+                .and_then(|c| c["offset"].as_u64())
+                .unwrap_or_else(|| panic!("{name} configurable not in the owner predicate ABI"))
+        };
+
+        OwnerPredicateTemplate {
+            hash: Bits256(template_hash),
+            owner_offset: offset("OWNER_EVM_ADDRESS"),
+            verifier_offset: offset("SWAP_VERIFIER"),
+            fee_recipient_offset: offset("FEE_RECIPIENT"),
+            fee_bps_offset: offset("FEE_BPS"),
+        }
     }
 
+    pub fn get_owner_predicate_configurables(
+        binding: &OwnerBinding,
+        owner_evm_address: [u8; 20],
+    ) -> OwnerPredicateConfigurables {
+        OwnerPredicateConfigurables::default()
+            .with_OWNER_EVM_ADDRESS(evm_address_to_bits256(owner_evm_address)).unwrap()
+            .with_SWAP_VERIFIER(Bits256(*binding.verifier)).unwrap()
+            .with_FEE_RECIPIENT(Bits256(*binding.protocol_fee.recipient)).unwrap()
+            .with_FEE_BPS(binding.protocol_fee.bps).unwrap()
+    }
 
-    pub fn get_predicate_validator_info(
-        // configurable_byte: u8,
+    /// the owner predicate bytecode and address for the owner's EVM address, bound to a verifier.
+    pub fn get_owner_predicate_info(
+        artifacts: &ArtifactPaths,
+        binding: &OwnerBinding,
+        owner_evm_address: [u8; 20],
     ) -> (Vec<u8>, Bech32Address, Address) {
 
-        let predicate_validator_bytecode = std::fs::read(PREDICATE_VALIDATOR_BINARY_PATH).unwrap();
-        let predicate = Predicate::from_code(predicate_validator_bytecode.clone());
-
-        // let configurables = get_predicate_validator_configurables(
-        //     configurable_byte,
-        // );
-        // let predicate = Predicate::from_code(predicate_validator_bytecode.clone())
-        //     .with_configurables(configurables);
+        let owner_predicate_bytecode = std::fs::read(&artifacts.owner_predicate_binary).unwrap();
+        let configurables = get_owner_predicate_configurables(
+            binding,
+            owner_evm_address,
+        );
+        let predicate = Predicate::from_code(owner_predicate_bytecode.clone())
+            .with_configurables(configurables);
 
         let predicate_b32addr = predicate.address().clone();
         let predicate_address: Address = predicate.address().into();
@@ -225,8 +668,61 @@ pub mod predicate_validator {
         (new_bytecode, predicate_b32addr, predicate_address)
     }
 
+    /// predicate data to spend the owner inputs listed in the signed intent.
+    pub fn get_owner_predicate_intent_data(
+        sender: Bits256,
+        signature_bytes: Vec<u8>,
+        gio_data: &VerifierGenIO,
+    ) -> Vec<u8> {
+        let intent = Intent {
+            sender,
+            compsig: Bytes(signature_bytes),
+            io: GenIO {
+                inputassets: gio_data.inputassets,
+                inpututxoids: gio_data.inpututxoids,
+                inputamounts: gio_data.inputamounts,
+                outputasset: gio_data.outputasset,
+                outputamount: gio_data.outputamount,
+                tolerance: gio_data.tolerance,
+                recipient: gio_data.recipient,
+            },
+        };
+        OwnerPredicateEncoder::default()
+            .encode_data(OwnerSpend::Intent(intent))
+            .unwrap()
+    }
+
+    /// predicate data for a plain transfer, the owner's signature is the witness at witness_index.
+    pub fn get_owner_predicate_transfer_data(
+        witness_index: u64,
+    ) -> Vec<u8> {
+        OwnerPredicateEncoder::default()
+            .encode_data(OwnerSpend::Transfer(witness_index))
+            .unwrap()
+    }
+
+    /// a plain transfer from the owner predicate. The owner's signer is the first signer of
+    /// the tx, so its signature is the witness at index 0. The gas is added by the gas sponsor.
+    pub fn build_owner_transfer(
+        owner_inputs: Vec<SdkInput>,
+        outputs: Vec<SdkOutput>,
+        owner_signer: EvmOwnerSigner,
+    ) -> ScriptTransactionBuilder {
+        let inputs = utxo_input_utils::with_predicate_data(
+            owner_inputs,
+            get_owner_predicate_transfer_data(0),
+        );
+
+        let mut stb = ScriptTransactionBuilder::prepare_transfer(
+            inputs,
+            outputs,
+            TxPolicies::default(),
+        );
+        stb.add_signer(owner_signer).unwrap();
+        stb
+    }
+
 }
-*/
 
 // some basic utils for a dummy predicate wallet that always returns true.
 pub mod dummy_pwallet {
@@ -235,7 +731,6 @@ pub mod dummy_pwallet {
 
     use super::*;
 
-    pub const PREDICATE_DUMMYPWALLET_BINARY_PATH: &str = "./contracts/dummy_predicate_wallet/out/debug/dummy_predicate_wallet.bin";
 
     abigen!(
         Predicate(
@@ -254,10 +749,11 @@ pub mod dummy_pwallet {
     }
 
     pub fn get_dummypwallet_info(
+        artifacts: &ArtifactPaths,
         configurable_byte: u8,
     ) -> (Vec<u8>, Bech32Address, Address) {
 
-        let dummypwallet_bytecode = std::fs::read(PREDICATE_DUMMYPWALLET_BINARY_PATH).unwrap();
+        let dummypwallet_bytecode = std::fs::read(&artifacts.dummy_wallet_binary).unwrap();
         let configurables = get_dummypwallet_configurables(
             configurable_byte,
         );
@@ -279,10 +775,20 @@ pub mod dummy_pwallet {
 pub mod tokenminter {
     use super::*;
     use fuels::types::Bits256;
-
-    pub const CONTRACT_TOKENMINTER_BINARY_PATH: &str = "./contracts/asset_ops/out/debug/asset_ops.bin";
-    pub const CONTRACT_TOKENMINTER_STORAGEJSON_PATH: &str = "./contracts/asset_ops/out/debug/asset_ops-storage_slots.json";
-
+    use crate::config::ConfigError;
+    use crate::interfaces::generalized_swap_verifier_interface::deployed_bytecode;
+
+    #[derive(Error, Debug)]
+    pub enum TokenMinterError {
+        #[error(transparent)]
+        Config(#[from] ConfigError),
+        #[error("TokenMinter artifacts: {0}")]
+        Artifacts(String),
+        #[error("Provider error: {0}")]
+        Provider(String),
+        #[error("TokenMinter deploy failed: {0}")]
+        Deploy(String),
+    }
 
     abigen!(
         Contract(
@@ -291,35 +797,47 @@ pub mod tokenminter {
         ),
     );
 
+    #[instrument(name = "deploy_token_minter", skip_all)]
     pub async fn deploy_tokenminter(
+        config: &Config,
         wallet_with_gas: &WalletUnlocked,
-    ) -> ContractId {
+    ) -> StdResult<ContractId, TokenMinterError> {
 
         // deploy with salt:
         // let mut rng = rand::thread_rng();
         // let salt = rng.gen::<[u8; 32]>();
-        let salt: [u8; 32] = [02u8; 32];
-        println!("TokenMinter salt: {}", hex::encode(salt));
+        let salt: [u8; 32] = config.token_minter_salt()?;
 
         let storage_configuration = StorageConfiguration::default()
-            .add_slot_overrides_from_file(CONTRACT_TOKENMINTER_STORAGEJSON_PATH)
-            .unwrap();
+            .add_slot_overrides_from_file(&config.artifacts.token_minter_storage)
+            .map_err(|e| TokenMinterError::Artifacts(e.to_string()))?;
 
         let configuration = LoadConfiguration::default()
             .with_storage_configuration(storage_configuration)
             .with_salt(salt);
 
-        let tokenminter_b32cid = Contract::load_from(
-            CONTRACT_TOKENMINTER_BINARY_PATH,
+        let contract = Contract::load_from(
+            &config.artifacts.token_minter_binary,
             configuration,
         )
-        .unwrap()
-        .deploy(wallet_with_gas, TxPolicies::default())
-        .await
-        .unwrap();
-
-        let tokenminter_cid = ContractId::from_bytes_ref(&tokenminter_b32cid.hash);
-        *tokenminter_cid
+        .map_err(|e| TokenMinterError::Artifacts(e.to_string()))?;
+
+        // the salt makes the id predictable, an already deployed TokenMinter is reused.
+        let tokenminter_cid = contract.contract_id();
+        let provider = wallet_with_gas
+            .provider()
+            .ok_or(TokenMinterError::Provider("the wallet has no provider".to_string()))?;
+        let deployed = deployed_bytecode(provider, &tokenminter_cid)
+            .await
+            .map_err(|e| TokenMinterError::Provider(e.to_string()))?;
+        if deployed.is_none() {
+            contract
+                .deploy(wallet_with_gas, TxPolicies::default())
+                .await
+                .map_err(|e| TokenMinterError::Deploy(e.to_string()))?;
+        }
+        info!(contract_id = %tokenminter_cid, salt = %hex::encode(salt), "TokenMinter ready");
+        Ok(tokenminter_cid)
     }
 
     pub async fn mint_to(
@@ -344,3 +862,109 @@ pub mod tokenminter {
 
 }
 
+
+
+#[cfg(test)]
+mod tests {
+    use super::generalized_swap_verifier_interface::*;
+    use super::*;
+    use fuels::{
+        tx::UtxoId,
+        types::{coin::Coin, coin_type::CoinType},
+    };
+    use crate::helpers::conversions::u64_to_bits256;
+
+    fn genio(assets: [[u8; 32]; 5], amounts: [u64; 5]) -> GenIO {
+        populate_genio(
+            assets.map(Bits256),
+            [Bits256([1u8; 32]); 5],
+            amounts.map(u64_to_bits256),
+            Bits256([9u8; 32]),
+            u64_to_bits256(2_000_000_000),
+            u64_to_bits256(250),
+            Bits256([7u8; 32]),
+        ).unwrap()
+    }
+
+    fn fee(bps: u64) -> ProtocolFee {
+        ProtocolFee { recipient: Address::new([5u8; 32]), bps }
+    }
+
+    #[test]
+    fn fee_on_the_total_of_the_inputs() {
+        let gio_data = genio([[3u8; 32]; 5], [1_000_000_000, 520_000_000, 0, 0, 0]);
+        assert_eq!(fee(30).fee_for_intent(&gio_data), Some(4_560_000));
+        assert_eq!(
+            fee(30).fee_output(&gio_data),
+            Some(SdkOutput::Coin { to: Address::new([5u8; 32]), amount: 4_560_000, asset_id: AssetId::new([3u8; 32]) }),
+        );
+        assert_eq!(fee(0).fee_output(&gio_data), None);
+    }
+
+    #[test]
+    fn no_fee_for_mixed_input_assets() {
+        let mut assets = [[3u8; 32]; 5];
+        assets[1] = [4u8; 32];
+        let gio_data = genio(assets, [1_000_000_000, 520_000_000, 0, 0, 0]);
+        assert_eq!(fee(30).fee_for_intent(&gio_data), None);
+        assert_eq!(fee(30).fee_output(&gio_data), None);
+
+        // an empty slot of another asset is not an input.
+        let gio_data = genio(assets, [1_000_000_000, 0, 0, 0, 0]);
+        assert_eq!(fee(30).fee_for_intent(&gio_data), Some(3_000_000));
+    }
+
+    #[test]
+    fn no_fee_when_the_total_overflows() {
+        let gio_data = genio([[3u8; 32]; 5], [u64::MAX, 1, 0, 0, 0]);
+        assert_eq!(fee(30).fee_for_intent(&gio_data), None);
+    }
+
+    fn coin_input(tx_id: u8, amount: u64) -> SdkInput {
+        SdkInput::resource_signed(CoinType::Coin(Coin {
+            amount,
+            asset_id: AssetId::new([3u8; 32]),
+            utxo_id: UtxoId::new(Bytes32::new([tx_id; 32]), 0),
+            ..Default::default()
+        }))
+    }
+
+    fn genio_of(inputs: &[SdkInput]) -> StdResult<(GenIO, u64), GenIOError> {
+        genio_for_inputs(
+            inputs,
+            AssetId::new([3u8; 32]),
+            AssetId::new([9u8; 32]),
+            2_000_000_000,
+            250,
+            &Bech32Address::from(Address::new([7u8; 32])),
+        )
+    }
+
+    #[test]
+    fn genio_of_the_inputs_and_their_total() {
+        let (gio_data, total_amount_in) = genio_of(&[coin_input(1, 1_000_000_000), coin_input(2, 520_000_000)]).unwrap();
+
+        assert_eq!(total_amount_in, 1_520_000_000);
+        let zero = Bits256::zeroed();
+        assert_eq!(gio_data.inputassets, [Bits256([3u8; 32]), Bits256([3u8; 32]), zero, zero, zero]);
+        assert_eq!(gio_data.inpututxoids, [Bits256([1u8; 32]), Bits256([2u8; 32]), zero, zero, zero]);
+        assert_eq!(
+            gio_data.inputamounts,
+            [u64_to_bits256(1_000_000_000), u64_to_bits256(520_000_000), zero, zero, zero],
+        );
+        assert_eq!(gio_data.outputasset, Bits256([9u8; 32]));
+        assert_eq!(gio_data.recipient, Bits256([7u8; 32]));
+    }
+
+    #[test]
+    fn genio_of_more_than_5_inputs() {
+        let inputs: Vec<SdkInput> = (1..=6).map(|tx_id| coin_input(tx_id, 1_000)).collect();
+        assert!(matches!(genio_of(&inputs), Err(GenIOError::TooManyInputs(6))));
+    }
+
+    #[test]
+    fn genio_of_overflowing_inputs() {
+        let inputs = [coin_input(1, u64::MAX), coin_input(2, 1)];
+        assert!(matches!(genio_of(&inputs), Err(GenIOError::AmountOverflow)));
+    }
+}
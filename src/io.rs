@@ -1,5 +1,6 @@
 use std::result::Result as StdResult;
 use std::fmt;
+use tracing::{debug, instrument, warn};
 use fuels::{
     prelude::*,
     accounts::{
@@ -18,6 +19,7 @@ use fuels::{
 pub enum UTXOToolsError {
     NoUTXOFound,
     UnexpectedInputType,
+    Provider(String),
 }
 
 impl fmt::Display for UTXOToolsError {
@@ -25,6 +27,7 @@ impl fmt::Display for UTXOToolsError {
         match self {
             UTXOToolsError::NoUTXOFound => write!(f, "No UTXO found in the input"),
             UTXOToolsError::UnexpectedInputType => write!(f, "Unexpected input type"),
+            UTXOToolsError::Provider(e) => write!(f, "Provider error: {}", e),
         }
     }
 }
@@ -36,6 +39,7 @@ pub mod utxo_input_utils {
     use super::*;
     use fuels::types::coin_type::CoinType;
 
+    #[instrument(name = "utxo_lookup", skip_all, fields(asset_id = %hex::encode(asset_id), target_amount = target_amount))]
     pub async fn get_predi_input(
         provider: &Provider,
         predi_bytecode: &Vec<u8>,
@@ -51,11 +55,6 @@ pub mod utxo_input_utils {
             predicate = predicate.with_data(data);
         }
 
-        println!("get_predi_input:");
-        println!("\tasset_id       : {}", hex::encode(asset_id));
-        println!("\ttarget_amount  : {}", target_amount);
-        println!(" ");
-
         let predicate_b32addr = predicate.address().clone();
         let predicate_address: Address = predicate.address().into();
         let mut predicate_inputs: Vec<SdkInput> = Vec::new();      // target asset inputs
@@ -69,10 +68,12 @@ pub mod utxo_input_utils {
                 .await
                 .unwrap(),
         );
+        debug!(predicate = %predicate_b32addr, inputs = predicate_inputs.len(), "predicate inputs selected");
 
         Ok((predicate_inputs, predicate_b32addr, predicate_address))
     }
 
+    #[instrument(name = "utxo_lookup", skip_all, fields(asset_id = %hex::encode(asset_id), target_amount = target_amount))]
     pub async fn find_inputs_by_resource_filter(
         provider: &Provider,
         predi_bytecode: &Vec<u8>,
@@ -149,6 +150,7 @@ pub mod utxo_input_utils {
     /// predicate only.
     /// - This function prioritizes older coins when multiple matches are found.
     ///
+    #[instrument(name = "utxo_lookup", skip_all, fields(asset_id = %hex::encode(asset_id), target_amount = target_amount))]
     pub async fn find_single_bytecode_predicate_input_for_amount(
         provider: &Provider,
         predi_bytecode: &Vec<u8>,
@@ -238,10 +240,6 @@ pub mod utxo_input_utils {
         bech32addr: &Bech32Address,
         target_assetid: AssetId,
     ) -> u64 {
-        println!("get balance:");
-        let some_address: Address = Address::from(bech32addr);
-        println!("owner: {} UTXOs:", hex::encode(some_address));
-
         let balances = provider.get_balances(bech32addr).await.unwrap();
         // print_balances(&balances);
 
@@ -254,14 +252,18 @@ pub mod utxo_input_utils {
                 target_asset_balance = *balance;
             },
             None => {
-                println!("No balance found for {}", target_key);
+                debug!(owner = %bech32addr, asset_id = %target_key, "no balance found");
             },
         }
         target_asset_balance
     }
 
-    /// extract the utxo txid and index for the Input::Coin
+    /// extract the utxo txid and index for the Input::Coin,
+    /// or the nonce (with index 0) for a message input, which is its unique id in the GenIO.
     pub fn get_input_txid(some_input: &SdkInput) -> StdResult<([u8; 32], u16), UTXOToolsError> {
+        if let Some((nonce_bytes, _)) = get_input_message_nonce_and_amount(some_input) {
+            return Ok((nonce_bytes, 0));
+        }
         let utxo_id = match some_input {
             SdkInput::ResourcePredicate { resource, .. } | SdkInput::ResourceSigned { resource } => {
                 match resource {
@@ -288,7 +290,11 @@ pub mod utxo_input_utils {
     }
 
 
+    /// as get_input_txid, with the amount of the coin or message.
     pub fn get_input_txid_and_amount(some_input: &SdkInput) -> StdResult<([u8; 32], u16, Option<u64>), UTXOToolsError> {
+        if let Some((nonce_bytes, amount)) = get_input_message_nonce_and_amount(some_input) {
+            return Ok((nonce_bytes, 0, Some(amount)));
+        }
         let (utxo_id, amount) = match some_input {
             SdkInput::ResourcePredicate { resource, .. } | SdkInput::ResourceSigned { resource } => {
                 match resource {
@@ -317,7 +323,118 @@ pub mod utxo_input_utils {
         }
     }
 
+    /// the predicate inputs for the utxos of a GenIO, each an (asset, utxo id, amount) where the id
+    /// is the tx id of a coin or the nonce of a message. Errors if any of them is not an unspent
+    /// resource of the predicate with that amount.
+    #[instrument(name = "utxo_lookup", skip_all, fields(utxos = utxos.len()))]
+    pub async fn find_inputs_by_utxo_ids(
+        provider: &Provider,
+        predi_bytecode: &Vec<u8>,
+        predi_data: Option<Vec<u8>>,
+        utxos: &[(AssetId, [u8; 32], u64)],
+    ) -> StdResult<Vec<SdkInput>, UTXOToolsError> {
+
+        let predicate = Predicate::from_code(predi_bytecode.clone());
+        let predicate_b32addr = predicate.address().clone();
+        let pdata = predi_data.unwrap_or_default();
+
+        let messages = provider
+            .get_messages(&predicate_b32addr)
+            .await
+            .map_err(|e| UTXOToolsError::Provider(e.to_string()))?;
+
+        let mut inputs = vec![];
+        for (asset_id, utxo_id, amount) in utxos {
+            let coins = provider
+                .get_coins(&predicate_b32addr, *asset_id)
+                .await
+                .map_err(|e| UTXOToolsError::Provider(e.to_string()))?;
+
+            let resource = if let Some(coin) = coins
+                .into_iter()
+                .find(|coin| **coin.utxo_id.tx_id() == *utxo_id && coin.amount == *amount)
+            {
+                CoinType::Coin(coin)
+            } else if let Some(message) = messages
+                .iter()
+                .find(|message| *asset_id == AssetId::BASE && *message.nonce == *utxo_id && message.amount == *amount)
+            {
+                CoinType::Message(message.clone())
+            } else {
+                warn!(utxo_id = %hex::encode(utxo_id), asset_id = %hex::encode(asset_id), amount, "intent utxo not found");
+                return Err(UTXOToolsError::NoUTXOFound);
+            };
 
+            inputs.push(SdkInput::resource_predicate(
+                resource,
+                predi_bytecode.clone(),
+                pdata.clone(),
+            ));
+        }
+
+        Ok(inputs)
+    }
+
+    /// the utxo ids that are no longer unspent coins (by tx id) or messages (by nonce) of the owner.
+    #[instrument(name = "utxo_lookup", skip_all, fields(owner = %owner_b32addr, utxos = utxos.len()))]
+    pub async fn find_spent_utxo_ids(
+        provider: &Provider,
+        owner_b32addr: &Bech32Address,
+        utxos: &[(AssetId, [u8; 32], u64)],
+    ) -> StdResult<Vec<[u8; 32]>, UTXOToolsError> {
+        let messages = provider
+            .get_messages(owner_b32addr)
+            .await
+            .map_err(|e| UTXOToolsError::Provider(e.to_string()))?;
+
+        let mut spent = vec![];
+        for (asset_id, utxo_id, amount) in utxos {
+            let coins = provider
+                .get_coins(owner_b32addr, *asset_id)
+                .await
+                .map_err(|e| UTXOToolsError::Provider(e.to_string()))?;
+
+            let unspent = coins
+                .iter()
+                .any(|coin| **coin.utxo_id.tx_id() == *utxo_id && coin.amount == *amount)
+                || messages
+                    .iter()
+                    .any(|message| *asset_id == AssetId::BASE && *message.nonce == *utxo_id && message.amount == *amount);
+            if !unspent {
+                spent.push(*utxo_id);
+            }
+        }
+        Ok(spent)
+    }
+
+    /// rebuild predicate inputs with the predicate data, e.g. once the intent over their utxo ids is signed.
+    pub fn with_predicate_data(
+        inputs: Vec<SdkInput>,
+        predi_data: Vec<u8>,
+    ) -> Vec<SdkInput> {
+        inputs
+            .into_iter()
+            .map(|input| match input {
+                SdkInput::ResourcePredicate { resource, code, .. } => {
+                    SdkInput::resource_predicate(resource, code, predi_data.clone())
+                },
+                other => other,
+            })
+            .collect()
+    }
+
+    /// extract the nonce and amount of a message input, None for any other input.
+    pub fn get_input_message_nonce_and_amount(some_input: &SdkInput) -> Option<([u8; 32], u64)> {
+        match some_input {
+            SdkInput::ResourcePredicate { resource: CoinType::Message(message), .. }
+            | SdkInput::ResourceSigned { resource: CoinType::Message(message) } => {
+                let mut nonce_bytes: [u8; 32] = [0x00; 32];
+                nonce_bytes.copy_from_slice(message.nonce.as_ref());
+                Some((nonce_bytes, message.amount))
+            },
+            _ => None,
+        }
+    }
 
 
 
@@ -339,7 +456,7 @@ pub mod tools {
                 target_asset_balance = *balance;
             },
             None => {
-                println!("No balance found for {}", target_key);
+                debug!(owner = %bech32addr, asset_id = %target_key, "no balance found");
             },
         }
         target_asset_balance
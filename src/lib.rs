@@ -0,0 +1,23 @@
+pub mod receipt_show;
+pub mod helpers;
+pub mod setup_01;
+pub mod setup_02;
+pub mod io;
+pub mod interfaces;
+pub mod wallets;
+pub mod evm_712_sign;
+pub mod consts;
+pub mod config;
+pub mod telemetry;
+pub mod network;
+pub mod sponsor;
+pub mod relay;
+pub mod solver;
+pub mod indexer;
+pub mod store;
+pub mod watcher;
+pub mod cli;
+pub mod trace;
+pub mod settlement;
+pub mod tx_policy;
+pub mod scenario;
@@ -5,55 +5,129 @@ use fuels::{
         Bits256,
         input::Input as SdkInput,
         output::Output as SdkOutput,
+        transaction::TxPolicies,
+        transaction_builders::ScriptTransactionBuilder,
     },
 };
 
-pub mod receipt_show;
-use receipt_show::log_shower;
+use clap::Parser;
+use tracing::{debug, info, instrument, warn};
+
+use intent_swap::{
+    cli::{self, Cli, Command},
+    config::Config,
+    network::{gas_sponsor_address, verifier_deployment, NetworkTarget},
+    evm_712_sign,
+    helpers,
+    io,
+    scenario::{ScenarioBuilder, WalletKind},
+    setup_01,
+    setup_02,
+    settlement::Settlement,
+    telemetry,
+    trace::VerifierTrace,
+    tx_policy::SwapTxPolicy,
+};
 
-pub mod helpers;
-pub mod setup_01;
+use intent_swap::setup_02::setup_case_02::SenderFunding;
 
-pub mod io;
-use io::{
+use intent_swap::io::{
     utxo_input_utils,
     tools::get_tx_id_from_script,
 };
 
-pub mod interfaces;
-use interfaces::generalized_swap_verifier_interface::*;
-use crate::interfaces::generalized_swap_verifier_interface;
+use intent_swap::interfaces::generalized_swap_verifier_interface::*;
+use intent_swap::interfaces::owner_predicate;
+use intent_swap::interfaces::predicate_validator::evm_address_to_bits256;
+use intent_swap::interfaces::generalized_swap_verifier_interface;
 
-pub mod wallets;
-use wallets::*;
+use intent_swap::wallets::*;
 
-pub mod evm_712_sign;
-use crate::receipt_show::receipt_sniffer::{
+use intent_swap::receipt_show::receipt_sniffer::{
     TxStatusResult, TxPollError,
     wait_for_tx_success,
 };
 
-pub mod consts;
+use intent_swap::sponsor::{GasSponsor, GasSponsorError, SponsorAccount};
 
 //--------------------------------------------------------------------------------------------------------------------------
 
 
 #[tokio::main]
 async fn main() {
-    println!("Run swap with contract validator.");
+    dotenv::dotenv().ok();
+    let mut cli = Cli::parse();
+
+    let config = cli.load_config().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if let Err(e) = telemetry::init_tracing(&config.logging) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    let result = match cli.command.take() {
+        None | Some(Command::Scenarios) => {
+            run_scenarios(&config).await;
+            Ok(())
+        }
+        Some(Command::Node) => cli::run_node(&config, cli.json).await,
+        Some(command) => cli::run_command(command, &config)
+            .await
+            .map(|output| cli::print_output(&output, cli.json)),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+/// the swap scenarios, on the network target of the config: each against its own in-process
+/// node, or all against one external node with the contracts deployed.
+async fn run_scenarios(config: &Config) {
+    let target = NetworkTarget::from_config(config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    info!("Run swap with contract validator");
+
+    let _f = run_contract_validated_intent_swap(config).await;
+
+    if target.is_in_process() {
+        info!("Reject fills that do not pay the protocol fee");
+
+        let _f = run_unpaid_fee_rejection(config).await;
+    }
+
+    info!("Attach to the deployed SwapVerifier");
+
+    let _f = run_verifier_client(config).await;
+
+    info!("Run base asset swaps with contract validator");
+
+    let _f = run_base_asset_intent_swaps(config, SenderFunding::Coins).await;
 
-    let _f = run_contract_validated_intent_swap().await;
+    if target.is_in_process() {
+        info!("Run base asset swaps from message inputs with contract validator");
+
+        let _f = run_base_asset_intent_swaps(config, SenderFunding::Messages).await;
+    } else {
+        info!("Skip the base asset swaps from message inputs, they need the in-process node");
+    }
 
 }
 
 //--------------------------------------------------------------------------------------------------------------------------
 
-async fn run_contract_validated_intent_swap() {
-    println!("run_contract_validated_intent_swap");
-
+#[instrument(skip_all)]
+async fn run_contract_validated_intent_swap(config: &Config) {
     // pick a known EVM wallet used for testing
-    let eth_address = get_evm_addr();
-    println!("EVM SENDER ADDRESS: 0x{}", hex::encode(eth_address));
+    let eth_address = get_evm_addr(test_sender_evm_sk());
+    info!(sender = %hex::encode(eth_address), "intent sender");
+
+    // the protocol fee the SwapVerifier is deployed with.
+    let protocol_fee = ProtocolFee::from_config(config);
 
     // setup assets from blank slate.
     let (
@@ -63,12 +137,13 @@ async fn run_contract_validated_intent_swap() {
         predicate_owner_b32addr,
         predicate_solver_bytecode,
         predicate_solver_b32addr,
+        sponsor_account,
         asset_in_id,
         asset_out_id
-    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider().await.unwrap();
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(config, &protocol_fee, None).await.unwrap();
 
     // get an empty fuel wallet to use in the contract instance.
-    let empty_wallet = get_fuel_wallet(&provider);
+    let empty_wallet = get_fuel_wallet(&provider, config);
 
     let swapverifier_instance = SwapVerifier::new(
         giov_cid.clone(),
@@ -89,9 +164,16 @@ async fn run_contract_validated_intent_swap() {
     // let amount_out_decimal: u64 = 1_940_000_000; // 2x, make the price 0.76 ETH per
 
     let amount_to_sender = amount_out_decimal;
-    let amount_to_solver = amount_in_1_decimal + amount_in_2_decimal;
+    let total_amount_in_decimal = amount_in_1_decimal + amount_in_2_decimal;
 
-    let tolerance_bps: u64 = 250;
+    // the solver pays the protocol fee from the asset_in it receives.
+    let amount_to_solver = helpers::swap_math::solver_net_amount_in(total_amount_in_decimal, protocol_fee.bps);
+
+    // the address that receives the output asset, signed into the intent.
+    // (defaults to the owner, but can be any third-party address)
+    let recipient_b32addr = predicate_owner_b32addr.clone();
+
+    let tolerance_bps = config.swap.tolerance_bps;
 
 
     helpers::display::print_separator_line(170, "(INPUTS):"); //-------------------------------------------------------------------------
@@ -102,118 +184,64 @@ async fn run_contract_validated_intent_swap() {
         &predicate_owner_b32addr,
         *AssetId::from_bytes_ref(&asset_in_id),
     ).await;
-    println!("DummyPWallet asset_in balance = {}", dwal_asset_in_bal);
+    info!(balance = dwal_asset_in_bal, "owner predicate asset_in balance");
 
-    /*
-    println!("get DummyPWallet asset_in 1st input");
-    let utxo_collector = UTXOllector::new();
-
-    let (dwal_asset_in_1_input, _, _) = utxo_collector.find_single_bytecode_predicate_input_for_amount(
-        &provider,
-        &predicate_owner_bytecode.clone(),
-        None,
-        *AssetId::from_bytes_ref(&asset_in_id),
-        amount_in_1_decimal,
-    ).await.unwrap();
-
-    let (asset_in_1_utxo_txid_bytes, utxo_1_txid_idx) = get_input_txid(&dwal_asset_in_1_input.clone()).unwrap();
-    println!("Asset In 1 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_1_utxo_txid_bytes));
-    println!("index     : {}", utxo_1_txid_idx);
-
-    // get the asset_in 2nd Input and UTXOid, (which will go to the Solver).
-    println!("get DummyPWallet asset_in 2nd input");
-    let utxo_collector = UTXOllector::new();
-
-    let (dwal_asset_in_2_input, _, _) = utxo_collector.find_single_bytecode_predicate_input_for_amount(
-        &provider,
-        &predicate_owner_bytecode.clone(),
-        None,
-        *AssetId::from_bytes_ref(&asset_in_id),
-        amount_in_2_decimal,
-    ).await.unwrap();
-
-    let (asset_in_2_utxo_txid_bytes, utxo_2_txid_idx) = get_input_txid(&dwal_asset_in_2_input.clone()).unwrap();
-    println!("Asset In 2 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_2_utxo_txid_bytes));
-    println!("index     : {}", utxo_2_txid_idx);
-    */
-
-    println!("get DummyPWallet asset_in 1st, 2nd inputs:");
 
     let (dwal_asset_in_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
         &provider,
         &predicate_owner_bytecode.clone(),
         None,
         *AssetId::from_bytes_ref(&asset_in_id),
-        amount_to_solver,
+        total_amount_in_decimal,
     ).await.unwrap();
     let dwal_asset_in_1_input = dwal_asset_in_inputs.get(0).unwrap().to_owned();
     let dwal_asset_in_2_input = dwal_asset_in_inputs.get(1).unwrap().to_owned();
 
-    /*
-    let (asset_in_1_utxo_txid_bytes, utxo_1_txid_idx) = utxo_input_utils::get_input_txid(&dwal_asset_in_1_input.clone()).unwrap();
-    println!("Asset In 1 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_1_utxo_txid_bytes));
-    println!("index     : {}", utxo_1_txid_idx);
-
-    let (asset_in_2_utxo_txid_bytes, utxo_2_txid_idx) = utxo_input_utils::get_input_txid(&dwal_asset_in_2_input.clone()).unwrap();
-    println!("Asset In 2 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_2_utxo_txid_bytes));
-    println!("index     : {}", utxo_2_txid_idx);
-    */
 
     let (
         asset_in_1_utxo_txid_bytes,
         utxo_1_txid_idx,
         utxo_1_amount_idx
     ) = utxo_input_utils::get_input_txid_and_amount(&dwal_asset_in_1_input.clone()).unwrap();
-    println!("Asset In 1 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_1_utxo_txid_bytes));
-    println!("amount    : {}", utxo_1_amount_idx.unwrap());
-    println!("index     : {}", utxo_1_txid_idx);
+    info!(
+        utxo_id = %hex::encode(asset_in_1_utxo_txid_bytes),
+        index = utxo_1_txid_idx,
+        amount = utxo_1_amount_idx.unwrap(),
+        "asset_in input 1",
+    );
 
     let (
         asset_in_2_utxo_txid_bytes,
         utxo_2_txid_idx,
         utxo_2_amount_idx,
     ) = utxo_input_utils::get_input_txid_and_amount(&dwal_asset_in_2_input.clone()).unwrap();
-    println!("Asset In 2 UTXO ID:");
-    println!("utxo txid : {}", hex::encode(asset_in_2_utxo_txid_bytes));
-    println!("amount    : {}", utxo_2_amount_idx.unwrap());
-    println!("index     : {}", utxo_2_txid_idx);
-
-
-
-
-
-
+    info!(
+        utxo_id = %hex::encode(asset_in_2_utxo_txid_bytes),
+        index = utxo_2_txid_idx,
+        amount = utxo_2_amount_idx.unwrap(),
+        "asset_in input 2",
+    );
 
-    // get the gas input for the solver, who pays the gas.
-    println!("get DummyPSolver gas input:");
-    let dsolver_baseasset_bal = io::tools::get_asset_balance_for_address(
+    // the gas is paid by a separate gas sponsor, not the solver.
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+    let dsponsor_baseasset_bal = io::tools::get_asset_balance_for_address(
         &provider,
-        &predicate_solver_b32addr,
+        &Bech32Address::from(gas_sponsor.address()),
         AssetId::default(),
     ).await;
-    println!("DummyPSolver base asset balance = {}", dsolver_baseasset_bal);
-
-    let (dsolver_gas_input, _, _) = utxo_input_utils::find_single_bytecode_predicate_input_for_amount(
-        &provider,
-        &predicate_solver_bytecode.clone(),
-        None,
-        AssetId::default(),
-        100000u64,
-    ).await.unwrap();
+    info!(balance = dsponsor_baseasset_bal, "gas sponsor base asset balance");
 
     // get the asset_out asset id, from the the solver (which will do to the swapper pwallet)
-    println!("get DummyPSolver asset_out input:");
     let dsolver_asset_out_bal = io::tools::get_asset_balance_for_address(
         &provider,
         &predicate_solver_b32addr,
         *AssetId::from_bytes_ref(&asset_out_id),
     ).await;
-    println!("DummyPSolver asset_in balance = {}", dsolver_asset_out_bal);
+    info!(balance = dsolver_asset_out_bal, "solver asset_out balance");
 
     let (dsolver_asset_out_input, _, _) = utxo_input_utils::find_single_bytecode_predicate_input_for_amount(
         &provider,
@@ -227,32 +255,16 @@ async fn run_contract_validated_intent_swap() {
     helpers::display::print_separator_line(170, "(OUTPUTS):");    //-------------------------------------------------------------------------
 
 
-    // create an Output of asset id asset_out to send to the Owner (i.e., the sender receives the desired asset).
-    println!("create an Output of asset id asset_out to send to DummyPWallet (the swapped asset received by sender)");
-    let dwallet_swap_output = SdkOutput::Coin {
-        to: Address::from(&predicate_owner_b32addr),
-        amount: amount_to_sender,
-        asset_id: *AssetId::from_bytes_ref(&asset_out_id),
-    };
+    // the Output of asset id asset_out to the recipient is created from the signed GenIO below.
 
     // create an Output of asset id asset_in to send to the Solver (i.e., the solver receives the assets from the owner).
-    println!("create an Output of asset id asset_in to send to DummyPSolver (the swapped asset received by solver)");
     let dsolver_swap_output = SdkOutput::Coin {
         to: Address::from(&predicate_solver_b32addr),
         amount: amount_to_solver,
         asset_id: *AssetId::from_bytes_ref(&asset_in_id),
     };
 
-    // create an output for the change and return it to the solver.
-    println!("gas change back to DummyPSolver");
-    let dsolver_gas_change = SdkOutput::change(
-        Address::from(&predicate_solver_b32addr),
-        0,
-        AssetId::default()
-    );
-
     // create an output for the asset_in change and return it to the sender.
-    println!("asset_in change back to DummyPSender");
     let dwallet_assetin_change = SdkOutput::change(
         Address::from(&predicate_owner_b32addr),
         0,
@@ -261,23 +273,18 @@ async fn run_contract_validated_intent_swap() {
 
 
     // collect all inputs and outputs
-    let tx_inputs: Vec<SdkInput> = vec![
+    // the owner inputs get the predicate data (the signed intent) once the intent is signed below.
+    let owner_inputs: Vec<SdkInput> = vec![
         dwal_asset_in_1_input,
         dwal_asset_in_2_input,
-        dsolver_asset_out_input,
-        dsolver_gas_input,
     ];
-    let tx_outputs: Vec<SdkOutput> = vec![
-        dwallet_swap_output,
+    let mut tx_outputs: Vec<SdkOutput> = vec![
         dsolver_swap_output,
-        dsolver_gas_change,
         dwallet_assetin_change,
     ];
 
 
-    // println!("INPUTS:\n{:#?}", tx_inputs);
-    // println!(" ");
-    // println!("OUTPUTS:\n{:#?}", tx_outputs);
+    debug!(inputs = ?owner_inputs, outputs = ?tx_outputs, "swap inputs and outputs");
 
 
     helpers::display::print_separator_line(170, "(TX SWAP DETAILS):"); //----------------------------------------------------------------
@@ -289,23 +296,32 @@ async fn run_contract_validated_intent_swap() {
     let total_amount_out: u64 = 2_000_000_000;    // create a value that is not the same as the utxo (for testing)
 
     let amount_in_hex_string = u64_to_bits256_hex(total_amount_in);
-    println!("total_amount_in :");
-    println!(" (decimal) : {}", total_amount_in);
-    println!(" (hex)     : {}", amount_in_hex_string);
-
     let amount_out_hex_string = u64_to_bits256_hex(total_amount_out);
-    println!("total_amount_out:");
-    println!(" (decimal) : {}", total_amount_out);
-    println!(" (hex)     : {}", amount_out_hex_string);
-
-    println!(" ");
-    println!("asset_in   : {}", hex::encode(asset_in_id));
-    println!("utxo_in 1  : {}", hex::encode(asset_in_1_utxo_txid_bytes));
-    println!("utxo_in 2  : {}", hex::encode(asset_in_2_utxo_txid_bytes));
-    println!("asset_out  : {}", hex::encode(asset_out_id));
-
-    println!(" ");
-    println!("tolerance_bps: {}", tolerance_bps);
+    info!(
+        asset_in = %hex::encode(asset_in_id),
+        amount_in = total_amount_in,
+        utxo_in_1 = %hex::encode(asset_in_1_utxo_txid_bytes),
+        utxo_in_2 = %hex::encode(asset_in_2_utxo_txid_bytes),
+        asset_out = %hex::encode(asset_out_id),
+        amount_out = total_amount_out,
+        recipient = %hex::encode(recipient_b32addr.hash),
+        tolerance_bps,
+        "intent",
+    );
+    debug!(amount_in = %amount_in_hex_string, amount_out = %amount_out_hex_string, "intent amounts as b256");
+
+    // the solver must deliver an amount within the tolerance of the signed amount_out, while it
+    // only receives the asset_in net of the protocol fee.
+    let (amount_out_lower, amount_out_upper) = helpers::swap_math::tolerance_bounds(total_amount_out, tolerance_bps);
+    info!(
+        amount_out_lower,
+        amount_out_upper,
+        fee_bps = protocol_fee.bps,
+        fee_amount = total_amount_in - amount_to_solver,
+        solver_amount_in = amount_to_solver,
+        "fill amounts",
+    );
+    assert!(amount_to_sender >= amount_out_lower && amount_to_sender <= amount_out_upper);
 
 
 
@@ -332,60 +348,100 @@ async fn run_contract_validated_intent_swap() {
         asset_out,
         amount_out,
         helpers::conversions::u64_to_bits256(tolerance_bps),
+        helpers::conversions::bytes32_to_bits256(recipient_b32addr.hash),
     ).unwrap();
 
     // sign the GenIO tx data struct with ethers to obtain a compact signature.
     let compact_sig = evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+        test_sender_evm_sk(),
         convert_to_u8_32_array(gio_tx.inputassets),
         convert_to_u8_32_array(gio_tx.inpututxoids),
         convert_to_u8_32_array(gio_tx.inputamounts),
         gio_tx.outputasset.0,
         gio_tx.outputamount.0,
         helpers::conversions::u64_to_bits256(tolerance_bps).0,
+        gio_tx.recipient.0,
     ).await;
 
     assert_eq!(compact_sig.len(), 64);
 
+    // the owner predicate spends the owner inputs listed in the signed intent.
+    let owner_predicate_data = owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(eth_address.0),
+        compact_sig.clone(),
+        &gio_tx,
+    );
+    let mut tx_inputs = utxo_input_utils::with_predicate_data(owner_inputs, owner_predicate_data);
+    tx_inputs.push(dsolver_asset_out_input);
+
+    // create an Output of asset id asset_out to send to the signed recipient (i.e., the recipient receives the desired asset).
+    let recipient_swap_output = build_recipient_output(&gio_tx, amount_to_sender);
+    tx_outputs.insert(0, recipient_swap_output);
+
+    helpers::display::print_separator_line(170, "(REJECT FILL WITHOUT THE VERIFIER):");
+
+    // the same inputs and outputs without the validate_solution call: the owner predicate
+    // only lets the intent inputs go in a tx with the SwapVerifier as a contract input.
+    let stb = ScriptTransactionBuilder::prepare_transfer(tx_inputs.clone(), tx_outputs.clone(), TxPolicies::default());
+    match gas_sponsor.sponsor(&provider, stb).await {
+        Err(e @ (GasSponsorError::Build(_) | GasSponsorError::PreflightFailed(_))) => {
+            info!(error = %e, "fill without the verifier rejected");
+        }
+        other => panic!("expected the owner predicate to reject the fill, got {:?}", other.map(|_| ())),
+    }
+
     helpers::display::print_separator_line(170, "(BUILD validate_solution call tx):");
 
+    // the balances every party should see change, from the signed intent.
+    let settlement = Settlement::new(
+        &gio_tx,
+        &protocol_fee,
+        Address::from(&predicate_owner_b32addr),
+        Address::from(&predicate_solver_b32addr),
+        gas_sponsor.address(),
+    ).unwrap();
+
+    // the tx policies of the config, the script gas limit sized from a dry run unless set.
+    let tx_policy = SwapTxPolicy::for_intent(&provider, &config.tx_policy, None).await.unwrap();
+
     let stb = call_validate_solution(
         swapverifier_instance.clone(),
-        helpers::conversions::bytes32_to_bits256(predicate_owner_b32addr.hash),
+        eth_address.0,
         gio_tx,
         compact_sig,
         tx_inputs,
         tx_outputs,
+        &protocol_fee,
+        tx_policy.tx_policies(),
     ).await;
-    let tx = stb.build(&provider).await.unwrap();
+    let stb = tx_policy.apply(&provider, stb, config.tx_policy.gas_margin_bps).await.unwrap();
+
+    // the gas sponsor adds its gas input and change output, once the tx passes the pre-flight.
+    let tx = gas_sponsor.sponsor(&provider, stb).await.unwrap();
+    info!(spent = gas_sponsor.spent, budget = gas_sponsor.budget, "gas sponsor");
 
     helpers::display::print_separator_line(170, "(TX):");
 
     let chainid = provider.chain_id();
     let tx_id = get_tx_id_from_script(&tx, chainid);
 
-    println!("tx_id: {}", hex::encode(tx_id));
-    println!(" ");
-    println!("tx:\n{:#?}", tx);
+    debug!(tx = ?tx, "fill tx");
 
-    println!("send the transaction...\n");
+    let balances_before = settlement.snapshot(&provider).await.unwrap();
     let tx_id = provider.send_transaction(tx).await.unwrap();
-    // println!("tx_id: {}", hex::encode(tx_id));
+    info!(tx_id = %tx_id, "fill submitted");
 
     helpers::display::print_separator_line(170, "(SHOW RECEIPTS):");
 
-    // get the receipts from the call:
-    // let receipts = provider
-    //     .tx_status(&tx_id)
-    //     .await
-    //     .unwrap().take_receipts();
-
-    match wait_for_tx_success(&provider, &tx_id, 100, 12).await {
-        TxStatusResult::Ok { receipts, elapsed_time } => {
-            println!("Transaction successful after {:.3}s", elapsed_time.as_secs_f64());
-            println!("Process receipts...");
-
-            // println!("receipts:\n{:?}", receipts);
-            // println!("");
+    match wait_for_tx_success(&provider, &tx_id, &config.polling).await {
+        TxStatusResult::Ok { receipts, block, elapsed_time } => {
+            info!(
+                tx_id = %tx_id,
+                block_height = block.height,
+                timestamp = block.timestamp,
+                elapsed_ms = elapsed_time.as_millis() as u64,
+                "fill settled",
+            );
 
             let dummy_empty_gio = GenIO {
                 inputassets: [Bits256::zeroed(); 5],
@@ -394,12 +450,14 @@ async fn run_contract_validated_intent_swap() {
                 outputasset: Bits256::zeroed(),
                 outputamount: Bits256::zeroed(),
                 tolerance: Bits256::zeroed(),
+                recipient: Bits256::zeroed(),
             };
             let dummy_swap_intent = generalized_swap_verifier_interface::Intent {
                 sender: Bits256::zeroed(),
                 compsig: Bytes([0x00; 64].to_vec()),
                 io: dummy_empty_gio,
             };
+            let trace = VerifierTrace::decode(&swapverifier_instance, &receipts).unwrap();
             let fch = swapverifier_instance
                 .methods()
                 .validate_solution(
@@ -407,28 +465,408 @@ async fn run_contract_validated_intent_swap() {
                 );
             let fcr = fch.get_response(receipts).unwrap();
 
-            println!("CallResponse (bool), was the transaction valid solution?: {:?}", fcr.value);
+            info!(valid_solution = ?fcr.value, "validate_solution returned");
+
+            let report = trace.report();
+            println!("{}", report);
+            assert!(report.passed());
 
-            log_shower(fcr);
+            helpers::display::print_separator_line(170, "(SETTLEMENT):");
+
+            let balances_after = settlement.snapshot(&provider).await.unwrap();
+            let settlement_report = settlement.report(&balances_before, &balances_after);
+            println!("{}", settlement_report);
+            assert!(settlement_report.passed());
         }
-        TxStatusResult::Err { error, status, elapsed_time } => {
+        TxStatusResult::Err { error, status, elapsed_time, .. } => {
             match error {
                 TxPollError::Timeout { tx_id, duration } => {
-                    println!("Transaction {:?} timed out after {:?}", tx_id, duration);
+                    warn!(tx_id = %tx_id, duration = ?duration, "fill timed out");
                 }
                 TxPollError::TransactionError(e) => {
-                    println!(
-                        "Transaction failed after {:?}! Error: {:?}, Last status: {:?}",
-                        elapsed_time, e, status
-                    );
+                    warn!(elapsed = ?elapsed_time, error = ?e, status = ?status, "fill failed");
                 }
             }
         }
     }
 
+    helpers::display::print_separator_line(170, "(OWNER PREDICATE TRANSFER):");
+
+    // the owner moves some of the swapped asset out of the owner predicate, with a plain
+    // transfer signed by the owner's EVM key.
+    let transfer_amount: u64 = 500_000_000;
+    let transfer_ok = run_owner_predicate_transfer(
+        &provider,
+        config,
+        &mut gas_sponsor,
+        (&predicate_owner_bytecode, &predicate_owner_b32addr),
+        *AssetId::from_bytes_ref(&asset_out_id),
+        transfer_amount,
+        empty_wallet.address(),
+    ).await;
+    assert!(transfer_ok);
+
+    let empty_wallet_asset_out_bal = io::tools::get_asset_balance_for_address(
+        &provider,
+        empty_wallet.address(),
+        *AssetId::from_bytes_ref(&asset_out_id),
+    ).await;
+    assert_eq!(empty_wallet_asset_out_bal, transfer_amount);
+}
+
+/// a plain transfer from the owner predicate, the owner personal_signs the tx id.
+#[instrument(skip_all, fields(asset_id = %asset_id, amount = amount, to = %to))]
+async fn run_owner_predicate_transfer(
+    provider: &Provider,
+    config: &Config,
+    gas_sponsor: &mut GasSponsor,
+    owner: (&Vec<u8>, &Bech32Address),
+    asset_id: AssetId,
+    amount: u64,
+    to: &Bech32Address,
+) -> bool {
+    let (owner_bytecode, owner_b32addr) = owner;
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        provider,
+        owner_bytecode,
+        None,
+        asset_id,
+        amount,
+    ).await.unwrap();
 
+    let outputs: Vec<SdkOutput> = vec![
+        SdkOutput::Coin {
+            to: Address::from(to),
+            amount,
+            asset_id,
+        },
+        SdkOutput::change(Address::from(owner_b32addr), 0, asset_id),
+    ];
 
+    let owner_signer = evm_712_sign::personal_sign_ethers::EvmOwnerSigner::new(
+        test_sender_evm_sk(),
+        owner_b32addr.clone(),
+    );
+    let stb = owner_predicate::build_owner_transfer(owner_inputs, outputs, owner_signer);
 
+    let tx = match gas_sponsor.sponsor(provider, stb).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            warn!(error = %e, "gas sponsor refused the transfer");
+            return false;
+        }
+    };
+    let tx_id = provider.send_transaction(tx).await.unwrap();
+    info!(tx_id = %tx_id, "transfer submitted");
+
+    match wait_for_tx_success(provider, &tx_id, &config.polling).await {
+        TxStatusResult::Ok { .. } => true,
+        TxStatusResult::Err { error, .. } => {
+            warn!(error = ?error, "transfer failed");
+            false
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------------------------------
+
+/// swap the base asset for a minted token, and back. The gas inputs are kept apart from the swap:
+/// the first swap is paid by the gas sponsor, the second by the solver, which delivers the base asset.
+/// With SenderFunding::Messages the sender's base asset inputs are message coins, signed by nonce.
+#[instrument(skip(config))]
+async fn run_base_asset_intent_swaps(config: &Config, sender_funding: SenderFunding) {
+    let protocol_fee = ProtocolFee::from_config(config);
+
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        token_id,
+    ) = setup_02::setup_case_02::setup_base_asset_predicates_sdk_provider(
+        config,
+        &protocol_fee,
+        sender_funding,
+        get_evm_addr(test_sender_2_evm_sk()).0,
+    ).await.unwrap();
+
+    let swapverifier_instance = SwapVerifier::new(
+        giov_cid.clone(),
+        get_fuel_wallet(&provider, config),
+    );
+    let token_id = *AssetId::from_bytes_ref(&token_id);
+    let tolerance_bps = config.swap.tolerance_bps;
+
+    helpers::display::print_separator_line(170, "(SWAP BASE ASSET FOR TOKEN):");
+
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let base_amount_in = setup_02::setup_case_02::SENDER_BASE_AMOUNT_1 + setup_02::setup_case_02::SENDER_BASE_AMOUNT_2;
+    let token_amount_out: u64 = 3_000_000_000;
+
+    // the sender inputs are held by the owner predicate of the second key, an intent signed
+    // by the first key (naming itself as the sender) does not spend them.
+    let wrong_signer_ok = run_intent_swap_leg(
+        &provider,
+        config,
+        &swapverifier_instance,
+        &protocol_fee,
+        &mut gas_sponsor,
+        (&predicate_owner_bytecode, &predicate_owner_b32addr),
+        test_sender_evm_sk(),
+        (&predicate_solver_bytecode, &predicate_solver_b32addr),
+        (AssetId::BASE, base_amount_in),
+        (token_id, token_amount_out),
+        tolerance_bps,
+    ).await;
+    assert!(!wrong_signer_ok);
+
+    let base_for_token_ok = run_intent_swap_leg(
+        &provider,
+        config,
+        &swapverifier_instance,
+        &protocol_fee,
+        &mut gas_sponsor,
+        (&predicate_owner_bytecode, &predicate_owner_b32addr),
+        test_sender_2_evm_sk(),
+        (&predicate_solver_bytecode, &predicate_solver_b32addr),
+        (AssetId::BASE, base_amount_in),
+        (token_id, token_amount_out),
+        tolerance_bps,
+    ).await;
+    assert!(base_for_token_ok);
+
+    let sender_token_bal = io::tools::get_asset_balance_for_address(
+        &provider,
+        &predicate_owner_b32addr,
+        token_id,
+    ).await;
+    assert_eq!(sender_token_bal, token_amount_out);
+
+    helpers::display::print_separator_line(170, "(SWAP TOKEN FOR BASE ASSET):");
+
+    // the solver delivers the base asset, so it also pays the gas and receives the base asset change.
+    let mut solver_gas_payer = GasSponsor::new(
+        SponsorAccount::Predicate(
+            Predicate::from_code(predicate_solver_bytecode.clone())
+                .with_provider(provider.clone())
+        ),
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let base_amount_out: u64 = 1_450_000_000;
+    // an external node keeps the base asset of earlier runs in the owner predicate.
+    let sender_base_before = io::tools::get_asset_balance_for_address(
+        &provider,
+        &predicate_owner_b32addr,
+        AssetId::BASE,
+    ).await;
+
+    let token_for_base_ok = run_intent_swap_leg(
+        &provider,
+        config,
+        &swapverifier_instance,
+        &protocol_fee,
+        &mut solver_gas_payer,
+        (&predicate_owner_bytecode, &predicate_owner_b32addr),
+        test_sender_2_evm_sk(),
+        (&predicate_solver_bytecode, &predicate_solver_b32addr),
+        (token_id, token_amount_out),
+        (AssetId::BASE, base_amount_out),
+        tolerance_bps,
+    ).await;
+    assert!(token_for_base_ok);
+
+    let sender_base_bal = io::tools::get_asset_balance_for_address(
+        &provider,
+        &predicate_owner_b32addr,
+        AssetId::BASE,
+    ).await;
+    assert_eq!(sender_base_bal, sender_base_before + base_amount_out);
+}
+
+/// one intent swap, the sender sells all of its asset_in inputs for the asset_out of the solver.
+/// The gas is paid by the gas_sponsor, with its own inputs and base asset change output.
+///
+/// A tx has a single base asset change output, which belongs to the gas payer. So when the sender
+/// sells the base asset, its inputs are spent in full (no change to the sender), and when the solver
+/// delivers the base asset, the solver must be the gas payer to receive its base asset change.
+///
+/// the intent is signed with signer_sk, and names that EVM address as the sender.
+#[allow(clippy::too_many_arguments)]
+#[instrument(
+    skip_all,
+    fields(
+        asset_in = %asset_in.0,
+        amount_in = asset_in.1,
+        asset_out = %asset_out.0,
+        amount_out = asset_out.1,
+        tolerance_bps = tolerance_bps,
+    ),
+)]
+async fn run_intent_swap_leg(
+    provider: &Provider,
+    config: &Config,
+    swapverifier_instance: &SwapVerifier<WalletUnlocked>,
+    protocol_fee: &ProtocolFee,
+    gas_sponsor: &mut GasSponsor,
+    sender: (&Vec<u8>, &Bech32Address),
+    signer_sk: &str,
+    solver: (&Vec<u8>, &Bech32Address),
+    asset_in: (AssetId, u64),
+    asset_out: (AssetId, u64),
+    tolerance_bps: u64,
+) -> bool {
+    let (sender_bytecode, sender_b32addr) = sender;
+    let (solver_bytecode, solver_b32addr) = solver;
+    let (asset_in_id, amount_in) = asset_in;
+    let (asset_out_id, amount_out) = asset_out;
+    let signer_evm_address = get_evm_addr(signer_sk);
+
+    if asset_out_id == AssetId::BASE {
+        assert_eq!(gas_sponsor.address(), Address::from(solver_b32addr));
+    }
+
+    // the sender's inputs, the utxo amounts are what the sender signs.
+    let (sender_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        provider,
+        sender_bytecode,
+        None,
+        asset_in_id,
+        amount_in,
+    ).await.unwrap();
+
+    let (gio_tx, total_amount_in) = genio_for_inputs(
+        &sender_inputs,
+        asset_in_id,
+        asset_out_id,
+        amount_out,
+        tolerance_bps,
+        sender_b32addr,
+    ).unwrap();
+
+    let (solver_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        provider,
+        solver_bytecode,
+        None,
+        asset_out_id,
+        amount_out,
+    ).await.unwrap();
+
+    let compact_sig = evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+        signer_sk,
+        convert_to_u8_32_array(gio_tx.inputassets),
+        convert_to_u8_32_array(gio_tx.inpututxoids),
+        convert_to_u8_32_array(gio_tx.inputamounts),
+        gio_tx.outputasset.0,
+        gio_tx.outputamount.0,
+        gio_tx.tolerance.0,
+        gio_tx.recipient.0,
+    ).await;
+
+    // the solver receives the asset_in net of the protocol fee, the fee output is added with the call.
+    let amount_to_solver = helpers::swap_math::solver_net_amount_in(total_amount_in, protocol_fee.bps);
+    info!(
+        amount_in = total_amount_in,
+        fee_amount = total_amount_in - amount_to_solver,
+        solver_amount_in = amount_to_solver,
+        "fill amounts",
+    );
+
+    let mut tx_outputs: Vec<SdkOutput> = vec![
+        build_recipient_output(&gio_tx, amount_out),
+        SdkOutput::Coin {
+            to: Address::from(solver_b32addr),
+            amount: amount_to_solver,
+            asset_id: asset_in_id,
+        },
+    ];
+    // the base asset change output is added by the gas sponsor.
+    if asset_in_id != AssetId::BASE {
+        tx_outputs.push(SdkOutput::change(Address::from(sender_b32addr), 0, asset_in_id));
+    }
+    if asset_out_id != AssetId::BASE {
+        tx_outputs.push(SdkOutput::change(Address::from(solver_b32addr), 0, asset_out_id));
+    }
+
+    // the owner predicate spends the sender inputs listed in the signed intent.
+    let owner_predicate_data = owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(signer_evm_address.0),
+        compact_sig.clone(),
+        &gio_tx,
+    );
+    let tx_inputs: Vec<SdkInput> = utxo_input_utils::with_predicate_data(sender_inputs, owner_predicate_data)
+        .into_iter()
+        .chain(solver_inputs)
+        .collect();
+
+    let settlement = match Settlement::new(
+        &gio_tx,
+        protocol_fee,
+        Address::from(sender_b32addr),
+        Address::from(solver_b32addr),
+        gas_sponsor.address(),
+    ) {
+        Ok(settlement) => settlement,
+        Err(e) => {
+            warn!(error = %e, "no settlement for the intent");
+            return false;
+        }
+    };
+
+    let tx_policy = SwapTxPolicy::for_intent(provider, &config.tx_policy, None).await.unwrap();
+    let stb = call_validate_solution(
+        swapverifier_instance.clone(),
+        signer_evm_address.0,
+        gio_tx,
+        compact_sig,
+        tx_inputs,
+        tx_outputs,
+        protocol_fee,
+        tx_policy.tx_policies(),
+    ).await;
+    let stb = match tx_policy.apply(provider, stb, config.tx_policy.gas_margin_bps).await {
+        Ok(stb) => stb,
+        Err(e) => {
+            warn!(error = %e, "no script gas limit for the fill");
+            return false;
+        }
+    };
+
+    let tx = match gas_sponsor.sponsor(provider, stb).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            warn!(error = %e, "gas sponsor refused the fill");
+            return false;
+        }
+    };
+    info!(spent = gas_sponsor.spent, budget = gas_sponsor.budget, "gas payer");
+
+    let balances_before = settlement.snapshot(provider).await.unwrap();
+    let tx_id = provider.send_transaction(tx).await.unwrap();
+    info!(tx_id = %tx_id, "fill submitted");
+
+    match wait_for_tx_success(provider, &tx_id, &config.polling).await {
+        TxStatusResult::Ok { .. } => {
+            let balances_after = settlement.snapshot(provider).await.unwrap();
+            let settlement_report = settlement.report(&balances_before, &balances_after);
+            println!("{}", settlement_report);
+            settlement_report.passed()
+        }
+        TxStatusResult::Err { error, .. } => {
+            warn!(error = ?error, "fill failed");
+            false
+        }
+    }
 }
 
 
@@ -447,4 +885,177 @@ fn convert_to_u8_32_array(input: [Bits256; 5]) -> [[u8; 32]; 5] {
 }
 
 
+//--------------------------------------------------------------------------------------------------------------------------
+
+/// fills that do not pay the protocol fee, without the fee output and with one short of the fee:
+/// the SwapVerifier reverts them with 6668. The owner predicate is bound with no fee, so the
+/// intent inputs only get to the verifier check.
+#[instrument(skip_all)]
+async fn run_unpaid_fee_rejection(config: &Config) {
+    let protocol_fee = ProtocolFee {
+        recipient: ProtocolFee::from_config(config).recipient,
+        bps: 30,
+    };
+    let eth_address = get_evm_addr(test_sender_evm_sk());
+
+    // the owner predicate of the verifier deployed below, with the fee check left to it. The
+    // verifier id follows from the gas sponsor, whose key is chosen first.
+    let sponsor_wallet = WalletUnlocked::new_random(None);
+    let verifier_id = VerifierDeployment::new(config, &protocol_fee, &Address::from(sponsor_wallet.address()))
+        .contract_id()
+        .unwrap();
+    let binding = owner_predicate::OwnerBinding { verifier: verifier_id, protocol_fee: ProtocolFee::none() };
+    let owner_bytecode = owner_predicate::get_owner_predicate_info(&config.artifacts, &binding, eth_address.0).0;
+
+    let scenario = ScenarioBuilder::new(config)
+        .protocol_fee(&protocol_fee)
+        .wallet("owner", WalletKind::Predicate(owner_bytecode))
+        .dummy_predicate("solver", 1u8)
+        .wallet("sponsor", WalletKind::Wallet(sponsor_wallet))
+        .gas_sponsor("sponsor")
+        .base_coins("solver", &[5000001])
+        .base_coins("sponsor", &[5000001])
+        .coins("asset_in", "owner", &[1_000_000_000, 520_000_000])
+        .coins("asset_out", "solver", &[2_000_000_000])
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(scenario.verifier_id, Some(verifier_id));
+
+    let provider = scenario.provider.clone();
+    let (predicate_owner_bytecode, predicate_owner_b32addr) = scenario.predicate("owner").unwrap();
+    let (predicate_solver_bytecode, predicate_solver_b32addr) = scenario.predicate("solver").unwrap();
+    let swapverifier_instance = SwapVerifier::new(verifier_id, get_fuel_wallet(&provider, config));
+    let mut gas_sponsor = GasSponsor::new(
+        scenario.wallet("sponsor").unwrap().sponsor_account(),
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+    let asset_in_id = scenario.asset_id("asset_in").unwrap();
+    let asset_out_id = scenario.asset_id("asset_out").unwrap();
+    let amount_out: u64 = 2_000_000_000;
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+    let (gio_tx, total_amount_in) = genio_for_inputs(
+        &owner_inputs,
+        asset_in_id,
+        asset_out_id,
+        amount_out,
+        config.swap.tolerance_bps,
+        &predicate_owner_b32addr,
+    ).unwrap();
+    let (solver_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_solver_bytecode,
+        None,
+        asset_out_id,
+        amount_out,
+    ).await.unwrap();
+
+    let compact_sig = evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+        test_sender_evm_sk(),
+        convert_to_u8_32_array(gio_tx.inputassets),
+        convert_to_u8_32_array(gio_tx.inpututxoids),
+        convert_to_u8_32_array(gio_tx.inputamounts),
+        gio_tx.outputasset.0,
+        gio_tx.outputamount.0,
+        gio_tx.tolerance.0,
+        gio_tx.recipient.0,
+    ).await;
+    let owner_predicate_data = owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(eth_address.0),
+        compact_sig.clone(),
+        &gio_tx,
+    );
+    let tx_inputs: Vec<SdkInput> = utxo_input_utils::with_predicate_data(owner_inputs, owner_predicate_data)
+        .into_iter()
+        .chain(solver_inputs)
+        .collect();
+
+    // the solver takes the inputs net of the fee, the fee is left in the owner change.
+    let fee_amount = protocol_fee.fee_for_intent(&gio_tx).unwrap();
+    let tx_outputs: Vec<SdkOutput> = vec![
+        build_recipient_output(&gio_tx, amount_out),
+        SdkOutput::Coin {
+            to: Address::from(&predicate_solver_b32addr),
+            amount: total_amount_in - fee_amount,
+            asset_id: asset_in_id,
+        },
+        SdkOutput::change(Address::from(&predicate_owner_b32addr), 0, asset_in_id),
+        SdkOutput::change(Address::from(&predicate_solver_b32addr), 0, asset_out_id),
+    ];
+    let underpaid_fee_output = SdkOutput::Coin {
+        to: protocol_fee.recipient,
+        amount: fee_amount - 1,
+        asset_id: asset_in_id,
+    };
+
+    for (case, fee_output) in [("no fee output", None), ("underpaid fee output", Some(underpaid_fee_output))] {
+        let mut outputs = tx_outputs.clone();
+        outputs.extend(fee_output);
+
+        // ProtocolFee::none() so that call_validate_solution adds no fee output of its own.
+        let stb = call_validate_solution(
+            swapverifier_instance.clone(),
+            eth_address.0,
+            gio_tx.clone(),
+            compact_sig.clone(),
+            tx_inputs.clone(),
+            outputs,
+            &ProtocolFee::none(),
+            TxPolicies::default(),
+        ).await;
+        match gas_sponsor.sponsor(&provider, stb).await {
+            Err(GasSponsorError::PreflightFailed(reason)) if reason.contains("6668") => {
+                info!(case, reason = verifier_revert_reason(6668).unwrap(), "fill rejected");
+            }
+            other => panic!("expected the verifier to revert the {} fill with 6668, got {:?}", case, other.map(|_| ())),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------------------------------
+
+/// deploy the SwapVerifier twice and attach to it. The second deploy returns the same id, and
+/// a client only attaches when the deployed bytecode is the local build with the same configurables.
+#[instrument(skip_all)]
+async fn run_verifier_client(config: &Config) {
+    // no verifier is deployed with the scenario, the funder deploys it below.
+    let wallet = ScenarioBuilder::new(config)
+        .without_verifier()
+        .build()
+        .await
+        .unwrap()
+        .funder;
+
+    let deployment = verifier_deployment(config).unwrap();
+    let expected_id = deployment.contract_id().unwrap();
+    let verifier_id = deployment.deploy(&wallet).await.unwrap();
+    assert_eq!(verifier_id, expected_id);
+    assert_eq!(deployment.deploy(&wallet).await.unwrap(), verifier_id);
+    info!(verifier_id = %verifier_id, "SwapVerifier deployed once");
+
+    let client = SwapVerifierClient::connect(verifier_id, wallet.clone(), &deployment).await.unwrap();
+    assert_eq!(client.contract_id, verifier_id);
+
+    // the same bytecode with another protocol fee is another contract.
+    let other_fee = ProtocolFee { recipient: ProtocolFee::from_config(config).recipient, bps: config.protocol_fee.bps + 1 };
+    let other_deployment = VerifierDeployment::new(config, &other_fee, &gas_sponsor_address(config).unwrap());
+    match SwapVerifierClient::connect(verifier_id, wallet.clone(), &other_deployment).await {
+        Err(VerifierError::BytecodeMismatch { contract_id, .. }) => assert_eq!(contract_id, verifier_id),
+        other => panic!("expected a bytecode mismatch, got {:?}", other.map(|client| client.contract_id)),
+    }
 
+    // the id of the other configurables was never deployed.
+    let missing_id = other_deployment.contract_id().unwrap();
+    match SwapVerifierClient::connect(missing_id, wallet, &deployment).await {
+        Err(VerifierError::NotDeployed(contract_id)) => assert_eq!(contract_id, missing_id),
+        other => panic!("expected no contract, got {:?}", other.map(|client| client.contract_id)),
+    }
+}
@@ -0,0 +1,201 @@
+use std::result::Result as StdResult;
+use std::str::FromStr;
+use thiserror::Error;
+use fuel_crypto::SecretKey;
+use fuels::{
+    prelude::*,
+    types::Bits256,
+};
+
+use crate::{
+    config::{Config, ConfigError, TargetKind},
+    interfaces::{
+        generalized_swap_verifier_interface::{
+            contract_verifier_instance,
+            ProtocolFee,
+            SwapVerifierClient,
+            VerifierDeployment,
+            VerifierError,
+        },
+        owner_predicate::OwnerBinding,
+        tokenminter::{self, TokenMinterError},
+    },
+    wallets::get_fuel_wallet,
+};
+
+
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Verifier(#[from] VerifierError),
+    #[error(transparent)]
+    TokenMinter(#[from] TokenMinterError),
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("The setup on {0} needs a funded wallet, set FUEL_WALLET_SK")]
+    NoFundedWallet(String),
+    #[error("{0} needs the in-process node")]
+    InProcessOnly(&'static str),
+    #[error("The SwapVerifier gas sponsor is {expected}, the setup pays the gas from {actual}")]
+    GasSponsorMismatch { expected: Address, actual: Address },
+    #[error("The node has chain id {actual}, the config expects {expected}")]
+    ChainIdMismatch { expected: u64, actual: u64 },
+}
+
+
+/// where the setups run the flows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetworkTarget {
+    /// a new in-process node for every setup, the setup deploys the SwapVerifier and TokenMinter.
+    InProcess,
+    /// a running node, e.g. a separately started fuel-core, with the contracts already deployed.
+    External(ExternalNetwork),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExternalNetwork {
+    pub provider_url: String,
+    /// deployed with the protocol fee, gas sponsor and artifacts of the config.
+    pub verifier_contract_id: ContractId,
+    pub token_minter_contract_id: ContractId,
+}
+
+impl NetworkTarget {
+
+    /// the target of network.target, an external node needs both contract ids.
+    pub fn from_config(config: &Config) -> StdResult<Self, ConfigError> {
+        match config.network.target {
+            TargetKind::InProcess => Ok(NetworkTarget::InProcess),
+            TargetKind::External => Ok(NetworkTarget::External(ExternalNetwork {
+                provider_url: config.network.provider_url.clone(),
+                verifier_contract_id: config.verifier_contract_id()?.ok_or(ConfigError::Invalid {
+                    field: "network.verifier_contract_id",
+                    message: "is needed for an external node".to_string(),
+                })?,
+                token_minter_contract_id: config.token_minter_contract_id()?.ok_or(ConfigError::Invalid {
+                    field: "network.token_minter_contract_id",
+                    message: "is needed for an external node".to_string(),
+                })?,
+            })),
+        }
+    }
+
+    pub fn is_in_process(&self) -> bool {
+        matches!(self, NetworkTarget::InProcess)
+    }
+}
+
+impl ExternalNetwork {
+
+    /// the node, and the FUEL_WALLET_SK wallet that funds the setup on it. The SwapVerifier
+    /// there must be the deployment of deploy_contracts.
+    pub async fn connect(&self, config: &Config) -> StdResult<(Provider, WalletUnlocked), NetworkError> {
+        let provider = connect_provider(&self.provider_url, config).await?;
+        if config.secrets.fuel_wallet_sk().is_none() {
+            return Err(NetworkError::NoFundedWallet(self.provider_url.clone()));
+        }
+        let wallet = get_fuel_wallet(&provider, config);
+        SwapVerifierClient::connect(self.verifier_contract_id, wallet.clone(), &verifier_deployment(config)?).await?;
+        Ok((provider, wallet))
+    }
+
+    /// a new sub id for each test asset minted on the node. The node keeps its state between
+    /// runs, so the flows only see the balances of assets minted for this run.
+    pub fn fresh_sub_id() -> Bits256 {
+        Bits256(rand::random())
+    }
+}
+
+/// the provider at provider_url, whose chain id must be network.chain_id unless that is 0.
+pub async fn connect_provider(provider_url: &str, config: &Config) -> StdResult<Provider, NetworkError> {
+    let provider = Provider::connect(provider_url)
+        .await
+        .map_err(|e| NetworkError::Provider(e.to_string()))?;
+    let expected = config.network.chain_id;
+    let actual = *provider.chain_id();
+    if expected != 0 && actual != expected {
+        return Err(NetworkError::ChainIdMismatch { expected, actual });
+    }
+    Ok(provider)
+}
+
+/// the SwapVerifier deployment of an external node: the protocol fee and the gas sponsor of
+/// the config.
+pub fn verifier_deployment(config: &Config) -> StdResult<VerifierDeployment, ConfigError> {
+    Ok(VerifierDeployment::new(config, &ProtocolFee::from_config(config), &gas_sponsor_address(config)?))
+}
+
+/// gas_sponsor.address, validated when the config was loaded, else the FUEL_WALLET_SK wallet that
+/// deploys and funds the setups, else no gas sponsor.
+pub fn gas_sponsor_address(config: &Config) -> StdResult<Address, ConfigError> {
+    if let Some(address) = config.gas_sponsor_address()? {
+        return Ok(address);
+    }
+    match config.secrets.fuel_wallet_sk() {
+        Some(fuel_sk) => {
+            let secret_key = SecretKey::from_str(fuel_sk).map_err(|_| ConfigError::Invalid {
+                field: "FUEL_WALLET_SK",
+                message: "is not a secret key".to_string(),
+            })?;
+            let wallet = WalletUnlocked::new_from_private_key(secret_key, None);
+            Ok(Address::from(wallet.address()))
+        }
+        None => Ok(Address::zeroed()),
+    }
+}
+
+/// the gas sponsor of a setup on an external node is the one the SwapVerifier was deployed with,
+/// else its base asset inputs would count as part of the swap.
+pub fn check_gas_sponsor(config: &Config, gas_sponsor: Address) -> StdResult<(), NetworkError> {
+    let expected = gas_sponsor_address(config)?;
+    if gas_sponsor != expected {
+        return Err(NetworkError::GasSponsorMismatch { expected, actual: gas_sponsor });
+    }
+    Ok(())
+}
+
+/// the SwapVerifier the owner predicates of the config are bound to: network.verifier_contract_id
+/// when set, else the id of verifier_deployment.
+pub fn owner_binding(config: &Config) -> StdResult<OwnerBinding, NetworkError> {
+    let verifier = match config.verifier_contract_id()? {
+        Some(contract_id) => contract_id,
+        None => verifier_deployment(config)?.contract_id()?,
+    };
+    Ok(OwnerBinding {
+        verifier,
+        protocol_fee: ProtocolFee::from_config(config),
+    })
+}
+
+/// the binding of the owner predicates of a setup to its verifier: in-process the setup deploys
+/// the verifier with protocol_fee, an external node has the one of the config.
+pub fn setup_owner_binding(
+    config: &Config,
+    verifier: ContractId,
+    protocol_fee: &ProtocolFee,
+) -> StdResult<OwnerBinding, ConfigError> {
+    let protocol_fee = match NetworkTarget::from_config(config)? {
+        NetworkTarget::InProcess => protocol_fee.clone(),
+        NetworkTarget::External(_) => ProtocolFee::from_config(config),
+    };
+    Ok(OwnerBinding { verifier, protocol_fee })
+}
+
+/// deploy the SwapVerifier of verifier_deployment and the TokenMinter on the node of the wallet,
+/// for an external target. Both ids follow from the salts, so deploying again returns the
+/// same ids without a new deploy.
+pub async fn deploy_contracts(
+    config: &Config,
+    wallet: &WalletUnlocked,
+) -> StdResult<(ContractId, ContractId), NetworkError> {
+    let (_swapverifier_instance, swapverifier_cid) = contract_verifier_instance(
+        config,
+        wallet.clone(),
+        &ProtocolFee::from_config(config),
+        &gas_sponsor_address(config)?,
+    ).await?;
+    let tokenminter_cid = tokenminter::deploy_tokenminter(config, wallet).await?;
+    Ok((swapverifier_cid, tokenminter_cid))
+}
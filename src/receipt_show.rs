@@ -4,59 +4,30 @@ use fuel_vm::prelude::*;
 use fuels::types::{
     Bits256, B512, EvmAddress, Bytes,
 };
-use fuels::programs::responses::CallResponse;
+use tracing::{debug, info, instrument, warn};
 
 
 
-/// just an ugly response decoder and dumper.
-pub fn log_shower<T>(fr: CallResponse<T>)
-where
-    T: std::fmt::Debug,
-{
-
-    let logs_res = fr.decode_logs();
-    // println!("logs_res:\n{:?}", logs_res);
-
-    let log1_string = fr.decode_logs_with_type::<String>();
-
-    let mut values_string_arr: Vec<String> = Vec::new();
-    match log1_string {
-        Ok(values) => {
-            let g = values.len();
-            println!("number of String's = {}", g);
-            for i in 0..g{
-                values_string_arr.push(values[i].clone());
-            }
-
-
-        }
-        Error => {
-
-        }
-    };
-
-    for (i, v) in values_string_arr.iter().enumerate() {
-        match i {
-            0 => println!("log: String[{:02}] --> .. : {}", i, *v),
-            _ => println!("log: String[{:02}] --> .. : {}", i, *v),
-        }
-    }
-
-}
-
 pub fn hex_print_bytes(bytes: &Vec<u8>) -> String{
     hex::encode(bytes)
 }
 
 
-/*
 pub mod receipt_sniffer {
     use super::*;
+    use std::io;
+    use fuel_core_client::client::{
+        types::TransactionStatus as ClientTxStatus,
+        FuelClient,
+    };
+    use futures::{future::join_all, StreamExt};
     use fuels::prelude::*;
     use fuels::types::Bytes32;
     use fuels::types::errors::transaction::Reason;
     use fuels::types::tx_status::TxStatus;
-    use tokio::time::{sleep, Duration};
+    use tokio::time::{sleep, timeout_at, Duration, Instant};
+
+    use crate::config::PollingConfig;
 
     #[derive(Debug)]
     pub enum TxPollError {
@@ -67,212 +38,198 @@ pub mod receipt_sniffer {
         TransactionError(Error),
     }
 
+    /// the block a tx was included in, the timestamp in unix seconds.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct TxBlock {
+        pub height: u32,
+        pub timestamp: i64,
+    }
+
     #[derive(Debug)]
     pub enum TxStatusResult {
         Ok {
-            receipts: Vec<Receipt>
+            receipts: Vec<Receipt>,
+            block: TxBlock,
+            elapsed_time: Duration
         },
         Err {
             error: TxPollError,
             status: TxStatus,
+            /// the block of a reverted tx, None when the tx never made it into one.
+            block: Option<TxBlock>,
             elapsed_time: Duration
         }
     }
 
-    /// Polls transaction status until it succeeds or fails
-    /// Returns TxStatusResult which contains either receipts on success
-    /// or detailed error information on failure
+    /// Waits until the transaction succeeds or fails, through the node's transaction status
+    /// subscription when polling.subscribe is set and the node serves it, else by polling with
+    /// exponential backoff. Returns TxStatusResult with the receipts and block on success,
+    /// or detailed error information on failure, along with timing information
+    #[instrument(name = "wait_tx", skip_all, fields(tx_id = %tx_id, timeout_secs = polling.timeout_secs))]
     pub async fn wait_for_tx_success(
         provider: &Provider,
         tx_id: &Bytes32,
-        poll_interval_ms: u64,
-        timeout_secs: u64,
+        polling: &PollingConfig,
+    ) -> TxStatusResult {
+        let start_time = Instant::now();
+        match FuelClient::new(provider.url()) {
+            Ok(client) => wait_with_client(&client, tx_id, polling, start_time).await,
+            Err(e) => provider_error(e.to_string(), start_time),
+        }
+    }
+
+    /// wait for a batch of transactions at once, the results are in the order of tx_ids.
+    #[instrument(name = "wait_txs", skip_all, fields(count = tx_ids.len()))]
+    pub async fn wait_for_txs(
+        provider: &Provider,
+        tx_ids: &[Bytes32],
+        polling: &PollingConfig,
+    ) -> Vec<TxStatusResult> {
+        let start_time = Instant::now();
+        let client = match FuelClient::new(provider.url()) {
+            Ok(client) => client,
+            Err(e) => return tx_ids.iter().map(|_| provider_error(e.to_string(), start_time)).collect(),
+        };
+        join_all(tx_ids.iter().map(|tx_id| wait_with_client(&client, tx_id, polling, start_time))).await
+    }
+
+    async fn wait_with_client(
+        client: &FuelClient,
+        tx_id: &Bytes32,
+        polling: &PollingConfig,
+        start_time: Instant,
     ) -> TxStatusResult {
-        let start_time = std::time::Instant::now();
-        let timeout = Duration::from_secs(timeout_secs);
-        let poll_interval = Duration::from_millis(poll_interval_ms);
+        let deadline = start_time + Duration::from_secs(polling.timeout_secs);
 
+        if polling.subscribe {
+            match subscribe_final_status(client, tx_id, deadline).await {
+                Ok(Some(status)) => {
+                    if let Some(result) = tx_outcome(status, start_time) {
+                        return result;
+                    }
+                }
+                // the deadline passed, or the node closed the subscription early.
+                Ok(None) => {}
+                Err(e) => debug!(error = %e, "tx status subscription unavailable, polling"),
+            }
+        }
+
+        let mut poll_interval = Duration::from_millis(polling.interval_ms);
+        let max_interval = Duration::from_millis(polling.max_interval_ms);
         loop {
-            let elapsed = start_time.elapsed();
-            if elapsed > timeout {
+            let now = Instant::now();
+            if now >= deadline {
+                warn!(elapsed_ms = start_time.elapsed().as_millis() as u64, "tx still pending at the timeout");
                 return TxStatusResult::Err {
                     error: TxPollError::Timeout {
-                        tx_id: tx_id.clone(),
-                        duration: elapsed,
+                        tx_id: *tx_id,
+                        duration: start_time.elapsed(),
                     },
                     status: TxStatus::Submitted,
-                    elapsed_time: elapsed,
+                    block: None,
+                    elapsed_time: start_time.elapsed(),
                 };
             }
 
-            match provider.tx_status(&tx_id).await {
-                Ok(status) => match status {
-                    TxStatus::Success { receipts } => {
-                        return TxStatusResult::Ok { receipts }
-                    }
-                    TxStatus::Revert { reason, revert_id, receipts } => {
-                        return TxStatusResult::Err {
-                            error: TxPollError::TransactionError(Error::Transaction(
-                                Reason::Reverted {
-                                    reason: reason.clone(),
-                                    revert_id,
-                                    receipts: receipts.clone(),
-                                }
-                            )),
-                            status: TxStatus::Revert {
-                                reason,
-                                revert_id,
-                                receipts,
-                            },
-                            elapsed_time: elapsed,
-                        };
-                    }
-                    TxStatus::SqueezedOut { reason } => {
-                        return TxStatusResult::Err {
-                            error: TxPollError::TransactionError(Error::Transaction(
-                                Reason::SqueezedOut(reason.clone())
-                            )),
-                            status: TxStatus::SqueezedOut { reason },
-                            elapsed_time: elapsed,
-                        };
+            // None until the node knows the tx, Submitted while it is in the pool.
+            match client.transaction(tx_id).await {
+                Ok(Some(response)) => {
+                    if let Some(result) = tx_outcome(response.status, start_time) {
+                        return result;
                     }
-                    TxStatus::Submitted => {
-                        sleep(poll_interval).await;
-                        continue;
-                    }
-                },
-                Err(e) => {
-                    // Check if the error is "status not found"
-                    if e.to_string().contains("status not found for transaction") {
-                        // This is expected while waiting for the transaction to be processed
-                        sleep(poll_interval).await;
-                        continue;
-                    }
-
-                    // For other errors, return the error
-                    return TxStatusResult::Err {
-                        error: TxPollError::TransactionError(e),
-                        status: TxStatus::Submitted,
-                        elapsed_time: elapsed,
-                    };
                 }
+                Ok(None) => {}
+                // a node that drops a request is asked again, only the deadline ends the wait.
+                Err(e) => warn!(error = %e, "tx status unavailable, polling again"),
             }
-        }
-    }
-}
-*/
 
-pub mod receipt_sniffer {
-    use super::*;
-    use fuels::prelude::*;
-    use fuels::types::Bytes32;
-    use fuels::types::errors::transaction::Reason;
-    use fuels::types::tx_status::TxStatus;
-    use tokio::time::{sleep, Duration};
-
-    #[derive(Debug)]
-    pub enum TxPollError {
-        Timeout {
-            tx_id: Bytes32,
-            duration: Duration,
-        },
-        TransactionError(Error),
-    }
-
-    #[derive(Debug)]
-    pub enum TxStatusResult {
-        Ok {
-            receipts: Vec<Receipt>,
-            elapsed_time: Duration
-        },
-        Err {
-            error: TxPollError,
-            status: TxStatus,
-            elapsed_time: Duration
+            sleep(poll_interval.min(deadline - now)).await;
+            poll_interval = (poll_interval * polling.backoff_factor).min(max_interval);
         }
     }
 
-    /// Polls transaction status until it succeeds or fails
-    /// Returns TxStatusResult which contains either receipts on success
-    /// or detailed error information on failure, along with timing information
-    pub async fn wait_for_tx_success(
-        provider: &Provider,
+    /// the first status past Submitted, None when the deadline passes or the stream ends first.
+    async fn subscribe_final_status(
+        client: &FuelClient,
         tx_id: &Bytes32,
-        poll_interval_ms: u64,
-        timeout_secs: u64,
-    ) -> TxStatusResult {
-        let start_time = std::time::Instant::now();
-        let timeout = Duration::from_secs(timeout_secs);
-        let poll_interval = Duration::from_millis(poll_interval_ms);
-
+        deadline: Instant,
+    ) -> io::Result<Option<ClientTxStatus>> {
+        let mut statuses = Box::pin(client.subscribe_transaction_status(tx_id).await?);
         loop {
-            let elapsed = start_time.elapsed();
-            if elapsed > timeout {
-                return TxStatusResult::Err {
-                    error: TxPollError::Timeout {
-                        tx_id: tx_id.clone(),
-                        duration: elapsed,
-                    },
-                    status: TxStatus::Submitted,
-                    elapsed_time: elapsed,
-                };
+            match timeout_at(deadline, statuses.next()).await {
+                Ok(Some(status)) => match status? {
+                    ClientTxStatus::Submitted { .. } => continue,
+                    status => return Ok(Some(status)),
+                },
+                Ok(None) | Err(_) => return Ok(None),
             }
+        }
+    }
 
-            match provider.tx_status(&tx_id).await {
-                Ok(status) => match status {
-                    TxStatus::Success { receipts } => {
-                        return TxStatusResult::Ok {
-                            receipts,
-                            elapsed_time: start_time.elapsed()
+    /// the result of a final status, None while the tx is only submitted.
+    fn tx_outcome(status: ClientTxStatus, start_time: Instant) -> Option<TxStatusResult> {
+        let elapsed_time = start_time.elapsed();
+        let block = match &status {
+            ClientTxStatus::Submitted { .. } => return None,
+            ClientTxStatus::SqueezedOut { .. } => None,
+            ClientTxStatus::Success { block_height, time, .. }
+            | ClientTxStatus::Failure { block_height, time, .. } => Some(TxBlock {
+                height: u32::from(*block_height),
+                timestamp: time.to_unix(),
+            }),
+        };
+
+        // the revert id and reason as the sdk derives them from the receipts.
+        Some(match (TxStatus::from(status), block) {
+            (TxStatus::Success { receipts }, Some(block)) => {
+                info!(elapsed_ms = elapsed_time.as_millis() as u64, block_height = block.height, "tx succeeded");
+                TxStatusResult::Ok {
+                    receipts,
+                    block,
+                    elapsed_time,
+                }
+            }
+            (TxStatus::Revert { reason, revert_id, receipts }, block) => {
+                warn!(revert_id, reason = %reason, "tx reverted");
+                TxStatusResult::Err {
+                    error: TxPollError::TransactionError(Error::Transaction(
+                        Reason::Reverted {
+                            reason: reason.clone(),
+                            revert_id,
+                            receipts: receipts.clone(),
                         }
-                    }
-                    TxStatus::Revert { reason, revert_id, receipts } => {
-                        return TxStatusResult::Err {
-                            error: TxPollError::TransactionError(Error::Transaction(
-                                Reason::Reverted {
-                                    reason: reason.clone(),
-                                    revert_id,
-                                    receipts: receipts.clone(),
-                                }
-                            )),
-                            status: TxStatus::Revert {
-                                reason,
-                                revert_id,
-                                receipts,
-                            },
-                            elapsed_time: elapsed,
-                        };
-                    }
-                    TxStatus::SqueezedOut { reason } => {
-                        return TxStatusResult::Err {
-                            error: TxPollError::TransactionError(Error::Transaction(
-                                Reason::SqueezedOut(reason.clone())
-                            )),
-                            status: TxStatus::SqueezedOut { reason },
-                            elapsed_time: elapsed,
-                        };
-                    }
-                    TxStatus::Submitted => {
-                        sleep(poll_interval).await;
-                        continue;
-                    }
-                },
-                Err(e) => {
-                    // Check if the error is "status not found"
-                    if e.to_string().contains("status not found for transaction") {
-                        // This is expected while waiting for the transaction to be processed
-                        sleep(poll_interval).await;
-                        continue;
-                    }
-
-                    // For other errors, return the error
-                    return TxStatusResult::Err {
-                        error: TxPollError::TransactionError(e),
-                        status: TxStatus::Submitted,
-                        elapsed_time: elapsed,
-                    };
+                    )),
+                    status: TxStatus::Revert {
+                        reason,
+                        revert_id,
+                        receipts,
+                    },
+                    block,
+                    elapsed_time,
+                }
+            }
+            (TxStatus::SqueezedOut { reason }, _) => {
+                warn!(reason = %reason, "tx squeezed out");
+                TxStatusResult::Err {
+                    error: TxPollError::TransactionError(Error::Transaction(
+                        Reason::SqueezedOut(reason.clone())
+                    )),
+                    status: TxStatus::SqueezedOut { reason },
+                    block: None,
+                    elapsed_time,
                 }
             }
+            (status, _) => provider_error(format!("unexpected tx status {:?}", status), start_time),
+        })
+    }
+
+    fn provider_error(message: String, start_time: Instant) -> TxStatusResult {
+        warn!(error = %message, "tx status unavailable");
+        TxStatusResult::Err {
+            error: TxPollError::TransactionError(Error::Provider(message)),
+            status: TxStatus::Submitted,
+            block: None,
+            elapsed_time: start_time.elapsed(),
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,765 @@
+use std::collections::HashMap;
+use std::result::Result as StdResult;
+use std::sync::Arc;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{info, instrument, warn, Span};
+use fuels::{
+    prelude::*,
+    types::Bits256,
+};
+use ethers::types::H256;
+
+use crate::config::{ArtifactPaths, Config};
+use crate::evm_712_sign::{
+    build_eip712_genio_ethers::get_eip712_digest_genio,
+    recover_compact_signer,
+};
+use crate::interfaces::{
+    GenIOError,
+    generalized_swap_verifier_interface::{GenIO, Intent, populate_genio},
+    owner_predicate::{get_owner_predicate_info, OwnerBinding},
+};
+use crate::io::{utxo_input_utils::find_inputs_by_utxo_ids, UTXOToolsError};
+
+
+#[derive(Error, Debug)]
+pub enum RelayError {
+    #[error("Invalid hex in field {0}")]
+    InvalidHex(String),
+    #[error("The signature is not by the intent sender")]
+    BadSignature,
+    #[error("Intent input {0} is not an unspent input of the sender")]
+    InputNotOwned(String),
+    #[error("Intent not found: {0}")]
+    NotFound(String),
+    #[error("Intent is not open: {0}")]
+    NotOpen(String),
+    #[error("Intent already submitted: {0}")]
+    Duplicate(String),
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("Relay rejected the request ({status}): {message}")]
+    Rejected { status: u16, message: String },
+    #[error("Relay request failed: {0}")]
+    Http(String),
+    #[error(transparent)]
+    GenIO(#[from] GenIOError),
+}
+
+
+/// the intents as they are sent to and served by the relay.
+pub mod intent_book {
+    use super::*;
+
+    /// GenIO with hex encoded ids and plain amounts, the JSON form of the signed struct.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct GenIOJson {
+        pub inputassets: [String; 5],
+        pub inpututxoids: [String; 5],
+        pub inputamounts: [u64; 5],
+        pub outputasset: String,
+        pub outputamount: u64,
+        pub tolerance: u64,
+        pub recipient: String,
+    }
+
+    impl GenIOJson {
+
+        pub fn genio(&self) -> StdResult<GenIO, RelayError> {
+            let mut assets_in = [Bits256::zeroed(); 5];
+            let mut utxoids_in = [Bits256::zeroed(); 5];
+            let mut amounts_in = [Bits256::zeroed(); 5];
+            for i in 0..5 {
+                assets_in[i] = Bits256(parse_hex("inputassets", &self.inputassets[i])?);
+                utxoids_in[i] = Bits256(parse_hex("inpututxoids", &self.inpututxoids[i])?);
+                amounts_in[i] = u64_to_bits256(self.inputamounts[i]);
+            }
+            Ok(populate_genio(
+                assets_in,
+                utxoids_in,
+                amounts_in,
+                Bits256(parse_hex("outputasset", &self.outputasset)?),
+                u64_to_bits256(self.outputamount),
+                u64_to_bits256(self.tolerance),
+                Bits256(parse_hex("recipient", &self.recipient)?),
+            )?)
+        }
+    }
+
+    /// an intent signed by the sender, as submitted to the relay.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct SignedIntent {
+        /// the EVM address of the sender, the owner of the owner predicate holding the inputs.
+        pub sender: String,
+        /// the compact (EIP-2098) signature of the EIP-712 GenIO digest.
+        pub signature: String,
+        pub io: GenIOJson,
+        /// the block height the relay drops the intent at, if any. Advisory only: it is not part
+        /// of the signed GenIO, so whoever relays the intent can change it, and it is checked
+        /// off-chain, by the relay and the solver before a send, never by the contracts.
+        #[serde(default)]
+        pub expires_at: Option<u32>,
+    }
+
+    impl SignedIntent {
+
+        pub fn new(sender_evm_address: [u8; 20], signature: &[u8], gio_data: &GenIO) -> Self {
+            SignedIntent {
+                sender: to_hex(&sender_evm_address),
+                signature: to_hex(signature),
+                io: GenIOJson {
+                    inputassets: gio_data.inputassets.map(|b| to_hex(&b.0)),
+                    inpututxoids: gio_data.inpututxoids.map(|b| to_hex(&b.0)),
+                    inputamounts: gio_data.inputamounts.map(bits256_to_u64),
+                    outputasset: to_hex(&gio_data.outputasset.0),
+                    outputamount: bits256_to_u64(gio_data.outputamount),
+                    tolerance: bits256_to_u64(gio_data.tolerance),
+                    recipient: to_hex(&gio_data.recipient.0),
+                },
+                expires_at: None,
+            }
+        }
+
+        /// the SignedIntent of an Intent as the SwapVerifier logs it, the sender is the
+        /// EVM address left padded to 32 bytes.
+        pub fn from_intent(intent: &Intent) -> Self {
+            let mut sender_evm_address = [0u8; 20];
+            sender_evm_address.copy_from_slice(&intent.sender.0[12..]);
+            SignedIntent::new(sender_evm_address, &intent.compsig.0, &intent.io)
+        }
+
+        pub fn with_expiry(mut self, block_height: u32) -> Self {
+            self.expires_at = Some(block_height);
+            self
+        }
+
+        pub fn sender_address(&self) -> StdResult<[u8; 20], RelayError> {
+            parse_hex("sender", &self.sender)
+        }
+
+        pub fn signature_bytes(&self) -> StdResult<Vec<u8>, RelayError> {
+            let bytes: [u8; 64] = parse_hex("signature", &self.signature)?;
+            Ok(bytes.to_vec())
+        }
+
+        pub fn genio(&self) -> StdResult<GenIO, RelayError> {
+            self.io.genio()
+        }
+
+        /// the intent id is the EIP-712 digest of the GenIO, the hash the sender signed.
+        pub fn intent_id(&self) -> StdResult<[u8; 32], RelayError> {
+            let gio_data = self.genio()?;
+            Ok(get_eip712_digest_genio(
+                gio_data.inputassets.map(|b| b.0),
+                gio_data.inpututxoids.map(|b| b.0),
+                gio_data.inputamounts.map(|b| b.0),
+                gio_data.outputasset.0,
+                gio_data.outputamount.0,
+                gio_data.tolerance.0,
+                gio_data.recipient.0,
+            ))
+        }
+
+        /// the asset the sender sells, the asset of the first input.
+        pub fn input_asset(&self) -> StdResult<[u8; 32], RelayError> {
+            parse_hex("inputassets", &self.io.inputassets[0])
+        }
+
+        pub fn output_asset(&self) -> StdResult<[u8; 32], RelayError> {
+            parse_hex("outputasset", &self.io.outputasset)
+        }
+
+        /// recover the signer of the intent id, which must be the sender.
+        pub fn verify_signature(&self) -> StdResult<[u8; 32], RelayError> {
+            let intent_id = self.intent_id()?;
+            let signer = recover_compact_signer(H256::from(intent_id), &self.signature_bytes()?)
+                .ok_or(RelayError::BadSignature)?;
+            if signer.0 != self.sender_address()? {
+                return Err(RelayError::BadSignature);
+            }
+            Ok(intent_id)
+        }
+
+        /// the (asset, utxo id, amount) of the intent inputs in use.
+        pub fn utxos(&self) -> StdResult<Vec<(AssetId, [u8; 32], u64)>, RelayError> {
+            let mut utxos = vec![];
+            for i in 0..5 {
+                if self.io.inputamounts[i] == 0 {
+                    continue;
+                }
+                utxos.push((
+                    AssetId::from(parse_hex::<32>("inputassets", &self.io.inputassets[i])?),
+                    parse_hex("inpututxoids", &self.io.inpututxoids[i])?,
+                    self.io.inputamounts[i],
+                ));
+            }
+            Ok(utxos)
+        }
+
+        /// every intent input is an unspent coin (by tx id) or message (by nonce) of the
+        /// sender's owner predicate, with the signed amount.
+        pub async fn verify_inputs(
+            &self,
+            provider: &Provider,
+            artifacts: &ArtifactPaths,
+            binding: &OwnerBinding,
+        ) -> StdResult<(), RelayError> {
+            let (owner_bytecode, _, _) = get_owner_predicate_info(artifacts, binding, self.sender_address()?);
+            match find_inputs_by_utxo_ids(provider, &owner_bytecode, None, &self.utxos()?).await {
+                Ok(_) => Ok(()),
+                // the node could not be asked, the inputs may well be unspent.
+                Err(UTXOToolsError::Provider(e)) => Err(RelayError::Provider(e)),
+                Err(_) => Err(RelayError::InputNotOwned(self.io.inpututxoids[0].clone())),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum IntentStatus {
+        Open,
+        /// the intent inputs were spent, by a fill of the intent or otherwise.
+        Filled,
+        Cancelled,
+        /// the block height passed expires_at before the intent was filled.
+        Expired,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct IntentEntry {
+        pub intent_id: String,
+        pub status: IntentStatus,
+        pub intent: SignedIntent,
+    }
+
+    /// a change to the book, as pushed to the subscribed solvers.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "event", content = "entry", rename_all = "snake_case")]
+    pub enum IntentEvent {
+        Accepted(IntentEntry),
+        Filled(IntentEntry),
+        Cancelled(IntentEntry),
+        Expired(IntentEntry),
+    }
+
+    impl IntentEvent {
+
+        /// the event for the entry's current status.
+        pub fn from_entry(entry: IntentEntry) -> Self {
+            match entry.status {
+                IntentStatus::Open => IntentEvent::Accepted(entry),
+                IntentStatus::Filled => IntentEvent::Filled(entry),
+                IntentStatus::Cancelled => IntentEvent::Cancelled(entry),
+                IntentStatus::Expired => IntentEvent::Expired(entry),
+            }
+        }
+
+        pub fn entry(&self) -> &IntentEntry {
+            match self {
+                IntentEvent::Accepted(entry)
+                | IntentEvent::Filled(entry)
+                | IntentEvent::Cancelled(entry)
+                | IntentEvent::Expired(entry) => entry,
+            }
+        }
+    }
+
+    /// the events buffered per subscriber, a subscriber that falls further behind skips events.
+    pub const INTENT_EVENTS_CAPACITY: usize = 256;
+
+    /// the in memory book of the intents the relay accepted, by intent id.
+    #[derive(Clone, Debug)]
+    pub struct IntentBook {
+        entries: Arc<RwLock<HashMap<[u8; 32], IntentEntry>>>,
+        events: broadcast::Sender<IntentEvent>,
+    }
+
+    impl Default for IntentBook {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl IntentBook {
+
+        pub fn new() -> Self {
+            let (events, _) = broadcast::channel(INTENT_EVENTS_CAPACITY);
+            IntentBook {
+                entries: Arc::new(RwLock::new(HashMap::new())),
+                events,
+            }
+        }
+
+        pub fn subscribe(&self) -> broadcast::Receiver<IntentEvent> {
+            self.events.subscribe()
+        }
+
+        pub async fn insert(
+            &self,
+            intent_id: [u8; 32],
+            intent: SignedIntent,
+        ) -> StdResult<IntentEntry, RelayError> {
+            let mut entries = self.entries.write().await;
+            if entries.contains_key(&intent_id) {
+                return Err(RelayError::Duplicate(to_hex(&intent_id)));
+            }
+            let entry = IntentEntry {
+                intent_id: to_hex(&intent_id),
+                status: IntentStatus::Open,
+                intent,
+            };
+            entries.insert(intent_id, entry.clone());
+            // no subscribers is not an error.
+            let _ = self.events.send(IntentEvent::Accepted(entry.clone()));
+            Ok(entry)
+        }
+
+        /// the intent is accepted when it is signed by the sender, and its inputs are unspent
+        /// inputs of the sender's owner predicate.
+        #[instrument(name = "accept_intent", skip_all, fields(intent_id = tracing::field::Empty))]
+        pub async fn accept(
+            &self,
+            provider: &Provider,
+            artifacts: &ArtifactPaths,
+            binding: &OwnerBinding,
+            intent: SignedIntent,
+        ) -> StdResult<IntentEntry, RelayError> {
+            let intent_id = intent.verify_signature()?;
+            Span::current().record("intent_id", tracing::field::display(to_hex(&intent_id)));
+            if let Err(e) = intent.verify_inputs(provider, artifacts, binding).await {
+                warn!(error = %e, "intent rejected");
+                return Err(e);
+            }
+            let entry = self.insert(intent_id, intent).await?;
+            info!("intent accepted");
+            Ok(entry)
+        }
+
+        pub async fn get(&self, intent_id: &[u8; 32]) -> Option<IntentEntry> {
+            self.entries.read().await.get(intent_id).cloned()
+        }
+
+        /// the open intents, optionally only those selling input_asset or buying output_asset.
+        pub async fn list_open(
+            &self,
+            input_asset: Option<[u8; 32]>,
+            output_asset: Option<[u8; 32]>,
+        ) -> Vec<IntentEntry> {
+            self.entries
+                .read()
+                .await
+                .values()
+                .filter(|entry| entry.status == IntentStatus::Open)
+                .filter(|entry| matches_filter(entry, input_asset, output_asset))
+                .cloned()
+                .collect()
+        }
+
+        pub async fn set_status(
+            &self,
+            intent_id: &[u8; 32],
+            status: IntentStatus,
+        ) -> StdResult<IntentEntry, RelayError> {
+            let mut entries = self.entries.write().await;
+            let entry = entries
+                .get_mut(intent_id)
+                .ok_or_else(|| RelayError::NotFound(to_hex(intent_id)))?;
+            if entry.status != IntentStatus::Open {
+                return Err(RelayError::NotOpen(to_hex(intent_id)));
+            }
+            entry.status = status;
+            let _ = self.events.send(IntentEvent::from_entry(entry.clone()));
+            Ok(entry.clone())
+        }
+
+        /// expire the open intents past their expires_at, and mark those whose inputs are
+        /// no longer unspent as filled. A provider error ends the round with no intent marked,
+        /// the next round checks them again.
+        pub async fn sweep(
+            &self,
+            provider: &Provider,
+            artifacts: &ArtifactPaths,
+            binding: &OwnerBinding,
+        ) -> StdResult<(), RelayError> {
+            let block_height = provider
+                .latest_block_height()
+                .await
+                .map_err(|e| RelayError::Provider(e.to_string()))?;
+
+            for entry in self.list_open(None, None).await {
+                let intent_id: [u8; 32] = parse_hex("intent_id", &entry.intent_id)?;
+                let expired = entry.intent.expires_at.is_some_and(|height| block_height >= height);
+                if expired {
+                    self.set_status(&intent_id, IntentStatus::Expired).await.ok();
+                } else {
+                    match entry.intent.verify_inputs(provider, artifacts, binding).await {
+                        Err(RelayError::InputNotOwned(_)) => {
+                            self.set_status(&intent_id, IntentStatus::Filled).await.ok();
+                        }
+                        Err(e @ RelayError::Provider(_)) => return Err(e),
+                        _ => {}
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// whether the intent sells input_asset and buys output_asset, None matches any asset.
+    pub fn matches_filter(
+        entry: &IntentEntry,
+        input_asset: Option<[u8; 32]>,
+        output_asset: Option<[u8; 32]>,
+    ) -> bool {
+        input_asset.map_or(true, |asset| entry.intent.input_asset().ok() == Some(asset))
+            && output_asset.map_or(true, |asset| entry.intent.output_asset().ok() == Some(asset))
+    }
+
+    /// the sender cancels an intent with a personal_sign (EIP-191) of the intent id.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CancelIntent {
+        pub signature: String,
+    }
+
+    impl CancelIntent {
+        pub fn verify(&self, entry: &IntentEntry) -> StdResult<(), RelayError> {
+            let intent_id: [u8; 32] = parse_hex("intent_id", &entry.intent_id)?;
+            let signature: [u8; 64] = parse_hex("signature", &self.signature)?;
+            let signer = recover_compact_signer(intent_id.to_vec(), &signature)
+                .ok_or(RelayError::BadSignature)?;
+            if signer.0 != entry.intent.sender_address()? {
+                return Err(RelayError::BadSignature);
+            }
+            Ok(())
+        }
+    }
+
+    pub fn to_hex(bytes: &[u8]) -> String {
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    pub fn parse_hex<const N: usize>(field: &str, value: &str) -> StdResult<[u8; N], RelayError> {
+        let bytes = hex::decode(value.trim_start_matches("0x"))
+            .map_err(|_| RelayError::InvalidHex(field.to_string()))?;
+        bytes.try_into().map_err(|_| RelayError::InvalidHex(field.to_string()))
+    }
+
+    fn u64_to_bits256(value: u64) -> Bits256 {
+        crate::helpers::conversions::u64_to_bits256(value)
+    }
+
+    fn bits256_to_u64(value: Bits256) -> u64 {
+        crate::helpers::conversions::bits256_to_u64(value)
+    }
+}
+
+
+/// the HTTP API of the relay.
+///
+/// POST /intents                   submit a SignedIntent, returns the IntentEntry
+/// GET  /intents                   the open intents, ?input_asset=0x..&output_asset=0x..
+/// GET  /intents/:intent_id        one intent
+/// POST /intents/:intent_id/cancel cancel with the sender's CancelIntent
+/// GET  /ws                        WebSocket of IntentEvents, ?input_asset=0x..&output_asset=0x..
+pub mod server {
+    use super::*;
+    use super::intent_book::*;
+    use std::time::Duration;
+    use axum::{
+        extract::{
+            ws::{Message, WebSocket, WebSocketUpgrade},
+            Path, Query, State,
+        },
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::{get, post},
+        Json, Router,
+    };
+
+    #[derive(Clone)]
+    pub struct RelayState {
+        pub book: IntentBook,
+        pub provider: Provider,
+        pub config: Arc<Config>,
+        /// the SwapVerifier the owner predicates of the accepted intents are bound to.
+        pub binding: OwnerBinding,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    pub struct IntentFilter {
+        pub input_asset: Option<String>,
+        pub output_asset: Option<String>,
+    }
+
+    impl IntoResponse for RelayError {
+        fn into_response(self) -> Response {
+            let status = match &self {
+                RelayError::NotFound(_) => StatusCode::NOT_FOUND,
+                RelayError::NotOpen(_) | RelayError::Duplicate(_) => StatusCode::CONFLICT,
+                RelayError::Provider(_) => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            (status, self.to_string()).into_response()
+        }
+    }
+
+    pub fn router(state: RelayState) -> Router {
+        Router::new()
+            .route("/intents", post(submit_intent).get(list_intents))
+            .route("/intents/:intent_id", get(get_intent))
+            .route("/intents/:intent_id/cancel", post(cancel_intent))
+            .route("/ws", get(subscribe_intents))
+            .with_state(state)
+    }
+
+    /// serve the API, and sweep the book for filled and expired intents every
+    /// relay.sweep_interval_secs of the config.
+    pub async fn serve(
+        listener: tokio::net::TcpListener,
+        state: RelayState,
+    ) -> std::io::Result<()> {
+        let sweeper = state.clone();
+        tokio::spawn(async move {
+            let sweep_interval = Duration::from_secs(sweeper.config.relay.sweep_interval_secs);
+            let mut interval = tokio::time::interval(sweep_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = sweeper.book.sweep(&sweeper.provider, &sweeper.config.artifacts, &sweeper.binding).await {
+                    warn!(error = %e, "intent relay sweep failed");
+                }
+            }
+        });
+        axum::serve(listener, router(state)).await
+    }
+
+    async fn submit_intent(
+        State(state): State<RelayState>,
+        Json(intent): Json<SignedIntent>,
+    ) -> StdResult<(StatusCode, Json<IntentEntry>), RelayError> {
+        let entry = state.book.accept(&state.provider, &state.config.artifacts, &state.binding, intent).await?;
+        Ok((StatusCode::CREATED, Json(entry)))
+    }
+
+    async fn list_intents(
+        State(state): State<RelayState>,
+        Query(filter): Query<IntentFilter>,
+    ) -> StdResult<Json<Vec<IntentEntry>>, RelayError> {
+        let input_asset = filter.input_asset.map(|a| parse_hex("input_asset", &a)).transpose()?;
+        let output_asset = filter.output_asset.map(|a| parse_hex("output_asset", &a)).transpose()?;
+        Ok(Json(state.book.list_open(input_asset, output_asset).await))
+    }
+
+    async fn get_intent(
+        State(state): State<RelayState>,
+        Path(intent_id): Path<String>,
+    ) -> StdResult<Json<IntentEntry>, RelayError> {
+        let id: [u8; 32] = parse_hex("intent_id", &intent_id)?;
+        state.book
+            .get(&id)
+            .await
+            .map(Json)
+            .ok_or(RelayError::NotFound(intent_id))
+    }
+
+    async fn subscribe_intents(
+        ws: WebSocketUpgrade,
+        State(state): State<RelayState>,
+        Query(filter): Query<IntentFilter>,
+    ) -> StdResult<Response, RelayError> {
+        let input_asset = filter.input_asset.map(|a| parse_hex("input_asset", &a)).transpose()?;
+        let output_asset = filter.output_asset.map(|a| parse_hex("output_asset", &a)).transpose()?;
+        Ok(ws.on_upgrade(move |socket| push_intent_events(socket, state.book, input_asset, output_asset)))
+    }
+
+    /// the open intents are sent first as Accepted, then every event matching the filter.
+    async fn push_intent_events(
+        mut socket: WebSocket,
+        book: IntentBook,
+        input_asset: Option<[u8; 32]>,
+        output_asset: Option<[u8; 32]>,
+    ) {
+        // subscribe before the snapshot, so no event falls in between.
+        let mut events = book.subscribe();
+
+        for entry in book.list_open(input_asset, output_asset).await {
+            if send_event(&mut socket, &IntentEvent::Accepted(entry)).await.is_err() {
+                return;
+            }
+        }
+
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if !matches_filter(event.entry(), input_asset, output_asset) {
+                        continue;
+                    }
+                    if send_event(&mut socket, &event).await.is_err() {
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "intent relay subscriber lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    async fn send_event(socket: &mut WebSocket, event: &IntentEvent) -> StdResult<(), axum::Error> {
+        socket.send(Message::Text(serde_json::to_string(event).unwrap())).await
+    }
+
+    async fn cancel_intent(
+        State(state): State<RelayState>,
+        Path(intent_id): Path<String>,
+        Json(cancel): Json<CancelIntent>,
+    ) -> StdResult<Json<IntentEntry>, RelayError> {
+        let id: [u8; 32] = parse_hex("intent_id", &intent_id)?;
+        let entry = state.book
+            .get(&id)
+            .await
+            .ok_or(RelayError::NotFound(intent_id))?;
+        cancel.verify(&entry)?;
+        Ok(Json(state.book.set_status(&id, IntentStatus::Cancelled).await?))
+    }
+}
+
+
+/// a client of the relay HTTP API, for senders and solvers.
+pub mod client {
+    use super::*;
+    use super::intent_book::*;
+    use futures::{Stream, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// sign the GenIO with the sender's EVM key, as a SignedIntent to submit.
+    pub async fn sign_intent(evm_sk: &str, gio_data: &GenIO) -> SignedIntent {
+        let signature = crate::evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+            evm_sk,
+            gio_data.inputassets.map(|b| b.0),
+            gio_data.inpututxoids.map(|b| b.0),
+            gio_data.inputamounts.map(|b| b.0),
+            gio_data.outputasset.0,
+            gio_data.outputamount.0,
+            gio_data.tolerance.0,
+            gio_data.recipient.0,
+        ).await;
+        SignedIntent::new(crate::wallets::get_evm_addr(evm_sk).0, &signature, gio_data)
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct RelayClient {
+        base_url: String,
+        http: reqwest::Client,
+    }
+
+    impl RelayClient {
+
+        /// base_url, e.g. "http://127.0.0.1:8787"
+        pub fn new(base_url: &str) -> Self {
+            RelayClient {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                http: reqwest::Client::new(),
+            }
+        }
+
+        pub async fn submit(&self, intent: &SignedIntent) -> StdResult<IntentEntry, RelayError> {
+            let response = self.http
+                .post(format!("{}/intents", self.base_url))
+                .json(intent)
+                .send()
+                .await;
+            read_json(response).await
+        }
+
+        pub async fn list(
+            &self,
+            input_asset: Option<AssetId>,
+            output_asset: Option<AssetId>,
+        ) -> StdResult<Vec<IntentEntry>, RelayError> {
+            let query = asset_query(input_asset, output_asset);
+            let response = self.http
+                .get(format!("{}/intents", self.base_url))
+                .query(&query)
+                .send()
+                .await;
+            read_json(response).await
+        }
+
+        pub async fn get(&self, intent_id: &str) -> StdResult<IntentEntry, RelayError> {
+            let response = self.http
+                .get(format!("{}/intents/{}", self.base_url, intent_id))
+                .send()
+                .await;
+            read_json(response).await
+        }
+
+        /// the stream of IntentEvents for the intents selling input_asset and buying output_asset,
+        /// starting with the open intents. The stream ends when the relay closes the connection.
+        pub async fn subscribe(
+            &self,
+            input_asset: Option<AssetId>,
+            output_asset: Option<AssetId>,
+        ) -> StdResult<impl Stream<Item = IntentEvent>, RelayError> {
+            let query = asset_query(input_asset, output_asset);
+            let ws_base = self.base_url.replacen("http", "ws", 1);
+            let url = reqwest::Url::parse_with_params(&format!("{}/ws", ws_base), &query)
+                .map_err(|e| RelayError::Http(e.to_string()))?;
+
+            let (ws_stream, _) = tokio_tungstenite::connect_async(url.as_str())
+                .await
+                .map_err(|e| RelayError::Http(e.to_string()))?;
+
+            Ok(ws_stream.filter_map(|message| async move {
+                match message {
+                    Ok(Message::Text(text)) => serde_json::from_str::<IntentEvent>(&text).ok(),
+                    _ => None,
+                }
+            }))
+        }
+
+        /// cancel_signature: the sender's compact personal_sign of the intent id bytes.
+        pub async fn cancel(
+            &self,
+            intent_id: &str,
+            cancel_signature: &[u8],
+        ) -> StdResult<IntentEntry, RelayError> {
+            let response = self.http
+                .post(format!("{}/intents/{}/cancel", self.base_url, intent_id))
+                .json(&CancelIntent { signature: to_hex(cancel_signature) })
+                .send()
+                .await;
+            read_json(response).await
+        }
+    }
+
+    fn asset_query(
+        input_asset: Option<AssetId>,
+        output_asset: Option<AssetId>,
+    ) -> Vec<(&'static str, String)> {
+        let mut query = vec![];
+        if let Some(asset) = input_asset {
+            query.push(("input_asset", to_hex(&*asset)));
+        }
+        if let Some(asset) = output_asset {
+            query.push(("output_asset", to_hex(&*asset)));
+        }
+        query
+    }
+
+    async fn read_json<T: serde::de::DeserializeOwned>(
+        response: reqwest::Result<reqwest::Response>,
+    ) -> StdResult<T, RelayError> {
+        let response = response.map_err(|e| RelayError::Http(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RelayError::Rejected {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            });
+        }
+        response.json::<T>().await.map_err(|e| RelayError::Http(e.to_string()))
+    }
+}
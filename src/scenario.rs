@@ -0,0 +1,523 @@
+use std::collections::BTreeMap;
+use std::result::Result as StdResult;
+use thiserror::Error;
+use tracing::{info, instrument};
+use fuels::{
+    prelude::*,
+    types::{
+        Bits256, Bytes32, Nonce,
+        message::Message,
+        transaction::TxPolicies,
+    },
+    accounts::wallet::WalletUnlocked,
+    test_helpers::{setup_single_asset_coins, setup_single_message},
+};
+
+use crate::{
+    config::{Config, ConfigError},
+    interfaces::{
+        dummy_pwallet::get_dummypwallet_info,
+        owner_predicate::{get_owner_predicate_info, OwnerBinding},
+        generalized_swap_verifier_interface::{
+            contract_verifier_instance,
+            ProtocolFee,
+            VerifierDeployment,
+        },
+        tokenminter,
+    },
+    network::{check_gas_sponsor, ExternalNetwork, NetworkError, NetworkTarget},
+    setup_01::get_assetid_for_subid_and_cid,
+    sponsor::SponsorAccount,
+    wallets::get_evm_addr,
+};
+
+
+#[derive(Error, Debug)]
+pub enum ScenarioError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("{0} is declared twice in the scenario")]
+    DuplicateName(String),
+    #[error("No wallet {0} in the scenario")]
+    UnknownWallet(String),
+    #[error("No asset {0} in the scenario")]
+    UnknownAsset(String),
+    #[error("{0} is not a predicate wallet")]
+    NotAPredicate(String),
+    #[error("{0} is an owner predicate, it can not be the gas sponsor")]
+    OwnerPredicateSponsor(String),
+}
+
+
+/// what a wallet of a scenario is.
+#[derive(Clone, Debug)]
+pub enum WalletKind {
+    /// the owner predicate of the EVM address.
+    OwnerPredicate { evm_address: [u8; 20] },
+    /// the test wallet predicate with the configurable byte, 1 is the solver.
+    DummyPredicate(u8),
+    /// any predicate, with its configurables already in the bytecode.
+    Predicate(Vec<u8>),
+    /// a new random key.
+    Eoa,
+    /// a wallet whose key is chosen before the build, e.g. to know its address beforehand.
+    Wallet(WalletUnlocked),
+    /// the wallet that funds the setup, the in-process node wallet or FUEL_WALLET_SK.
+    Funder,
+}
+
+#[derive(Clone, Debug)]
+struct WalletSpec {
+    name: String,
+    kind: WalletKind,
+}
+
+#[derive(Clone, Debug)]
+struct AssetSpec {
+    name: String,
+    /// None for the next sub id on the in-process node, a fresh one on an external node.
+    sub_id: Option<Bits256>,
+    /// one mint for each (wallet, amount), so each is a coin of its own.
+    coins: Vec<(String, u64)>,
+}
+
+
+/// the address the scenario message coins are sent from, stands in for the bridge.
+pub const MESSAGE_SENDER: [u8; 32] = [0xb1; 32];
+
+
+/// declares the wallets, assets and coins of a scenario, and sets them up on the network
+/// target of the config with build.
+///
+/// the base asset coins are transferred from the funded wallet, the message coins are in the
+/// genesis state of the in-process node, the other assets are minted by the TokenMinter. On the
+/// in-process node the SwapVerifier and TokenMinter are deployed, an external node uses the
+/// deployed ones.
+#[derive(Clone, Debug)]
+pub struct ScenarioBuilder<'a> {
+    config: &'a Config,
+    protocol_fee: ProtocolFee,
+    chain_config: Option<ChainConfig>,
+    funder_coins: (u64, u64),
+    wallets: Vec<WalletSpec>,
+    /// one transfer for each (wallet, amount).
+    base_coins: Vec<(String, u64)>,
+    /// one base asset message coin for each (wallet, amount).
+    messages: Vec<(String, u64)>,
+    assets: Vec<AssetSpec>,
+    gas_sponsor: Option<String>,
+    deploy_verifier: bool,
+}
+
+impl<'a> ScenarioBuilder<'a> {
+
+    /// no wallets or assets, the protocol fee of the config and one funder coin of 10^12.
+    pub fn new(config: &'a Config) -> Self {
+        ScenarioBuilder {
+            config,
+            protocol_fee: ProtocolFee::from_config(config),
+            chain_config: None,
+            funder_coins: (1, 1_000_000_000_000),
+            wallets: vec![],
+            base_coins: vec![],
+            messages: vec![],
+            assets: vec![],
+            gas_sponsor: None,
+            deploy_verifier: true,
+        }
+    }
+
+    /// the protocol fee of a SwapVerifier deployed here.
+    pub fn protocol_fee(mut self, protocol_fee: &ProtocolFee) -> Self {
+        self.protocol_fee = protocol_fee.clone();
+        self
+    }
+
+    /// the chain config of the in-process node, e.g. loaded with ChainConfig::load.
+    pub fn chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = Some(chain_config);
+        self
+    }
+
+    /// the base asset coins of the wallet that funds the in-process node setup.
+    pub fn funder_coins(mut self, num_coins: u64, amount_per_coin: u64) -> Self {
+        self.funder_coins = (num_coins, amount_per_coin);
+        self
+    }
+
+    pub fn wallet(mut self, name: &str, kind: WalletKind) -> Self {
+        self.wallets.push(WalletSpec { name: name.to_string(), kind });
+        self
+    }
+
+    pub fn owner_predicate(self, name: &str, evm_sk: &str) -> Self {
+        self.owner_predicate_of(name, get_evm_addr(evm_sk).0)
+    }
+
+    pub fn owner_predicate_of(self, name: &str, evm_address: [u8; 20]) -> Self {
+        self.wallet(name, WalletKind::OwnerPredicate { evm_address })
+    }
+
+    pub fn dummy_predicate(self, name: &str, configurable_byte: u8) -> Self {
+        self.wallet(name, WalletKind::DummyPredicate(configurable_byte))
+    }
+
+    pub fn eoa(self, name: &str) -> Self {
+        self.wallet(name, WalletKind::Eoa)
+    }
+
+    pub fn funder(self, name: &str) -> Self {
+        self.wallet(name, WalletKind::Funder)
+    }
+
+    /// the base asset coins of the wallet, one transfer for each amount.
+    pub fn base_coins(mut self, wallet: &str, amounts: &[u64]) -> Self {
+        self.base_coins.extend(amounts.iter().map(|amount| (wallet.to_string(), *amount)));
+        self
+    }
+
+    /// base asset message coins of the wallet (as if bridged in), one for each amount. On the
+    /// in-process node only, they are created in its genesis state.
+    pub fn messages(mut self, wallet: &str, amounts: &[u64]) -> Self {
+        self.messages.extend(amounts.iter().map(|amount| (wallet.to_string(), *amount)));
+        self
+    }
+
+    /// a TokenMinter asset, with the next sub id.
+    pub fn asset(mut self, name: &str) -> Self {
+        self.assets.push(AssetSpec { name: name.to_string(), sub_id: None, coins: vec![] });
+        self
+    }
+
+    /// a TokenMinter asset with the sub id, on the in-process node only, an external node
+    /// always mints under a fresh sub id.
+    pub fn asset_with_sub_id(mut self, name: &str, sub_id: Bits256) -> Self {
+        self.assets.push(AssetSpec { name: name.to_string(), sub_id: Some(sub_id), coins: vec![] });
+        self
+    }
+
+    /// coins of the asset to the wallet, one mint for each amount.
+    pub fn coins(mut self, asset: &str, wallet: &str, amounts: &[u64]) -> Self {
+        let coins = amounts.iter().map(|amount| (wallet.to_string(), *amount));
+        match self.assets.iter_mut().find(|spec| spec.name == asset) {
+            Some(spec) => spec.coins.extend(coins),
+            None => self.assets.push(AssetSpec {
+                name: asset.to_string(),
+                sub_id: None,
+                coins: coins.collect(),
+            }),
+        }
+        self
+    }
+
+    /// the wallet whose base asset inputs the SwapVerifier deployed here ignores. On an external
+    /// node it must be the gas sponsor the SwapVerifier was deployed with, see network::check_gas_sponsor.
+    pub fn gas_sponsor(mut self, wallet: &str) -> Self {
+        self.gas_sponsor = Some(wallet.to_string());
+        self
+    }
+
+    /// no SwapVerifier on the in-process node, Scenario::verifier_id is None.
+    pub fn without_verifier(mut self) -> Self {
+        self.deploy_verifier = false;
+        self
+    }
+
+    fn check(&self) -> StdResult<(), ScenarioError> {
+        let mut names: Vec<&str> = self.wallets.iter().map(|spec| spec.name.as_str()).collect();
+        names.extend(self.assets.iter().map(|spec| spec.name.as_str()));
+        names.sort();
+        if let Some(pair) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(ScenarioError::DuplicateName(pair[0].to_string()));
+        }
+        let wallet_names = self.assets.iter()
+            .flat_map(|spec| spec.coins.iter().map(|(wallet, _)| wallet))
+            .chain(self.base_coins.iter().map(|(wallet, _)| wallet))
+            .chain(self.messages.iter().map(|(wallet, _)| wallet))
+            .chain(self.gas_sponsor.iter());
+        for wallet in wallet_names {
+            if !self.wallets.iter().any(|spec| &spec.name == wallet) {
+                return Err(ScenarioError::UnknownWallet(wallet.clone()));
+            }
+        }
+        // the owner predicates are bound to the verifier, which is deployed with the sponsor.
+        if let Some(sponsor) = &self.gas_sponsor {
+            let is_owner_predicate = self.wallets.iter()
+                .any(|spec| &spec.name == sponsor && matches!(spec.kind, WalletKind::OwnerPredicate { .. }));
+            if is_owner_predicate {
+                return Err(ScenarioError::OwnerPredicateSponsor(sponsor.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    #[instrument(name = "scenario", skip_all, fields(target = ?self.config.network.target))]
+    pub async fn build(self) -> StdResult<Scenario, ScenarioError> {
+        self.check()?;
+        let config = self.config;
+        let target = NetworkTarget::from_config(config)?;
+
+        // on the in-process node every address is known before it is launched, as the message
+        // coins are in its genesis state. The provider is set on the wallets once it is.
+        let mut funder = match &target {
+            NetworkTarget::InProcess => WalletUnlocked::new_random(None),
+            NetworkTarget::External(_) if self.chain_config.is_some() => {
+                return Err(NetworkError::InProcessOnly("a chain config").into());
+            }
+            NetworkTarget::External(_) if !self.messages.is_empty() => {
+                return Err(NetworkError::InProcessOnly("message coins").into());
+            }
+            NetworkTarget::External(network) => network.connect(config).await?.1,
+        };
+
+        // the owner predicates are created once the verifier they are bound to is known.
+        let mut wallets = BTreeMap::new();
+        for spec in &self.wallets {
+            let account = match &spec.kind {
+                WalletKind::OwnerPredicate { .. } => continue,
+                WalletKind::DummyPredicate(configurable_byte) => {
+                    ScenarioAccount::predicate(get_dummypwallet_info(&config.artifacts, *configurable_byte).0)
+                }
+                WalletKind::Predicate(bytecode) => ScenarioAccount::predicate(bytecode.clone()),
+                WalletKind::Eoa => ScenarioAccount::Wallet(WalletUnlocked::new_random(None)),
+                WalletKind::Wallet(wallet) => ScenarioAccount::Wallet(wallet.clone()),
+                WalletKind::Funder => ScenarioAccount::Wallet(funder.clone()),
+            };
+            wallets.insert(spec.name.clone(), account);
+        }
+
+        let gas_sponsor = match &self.gas_sponsor {
+            Some(name) => wallets[name].address(),
+            None => Address::zeroed(),
+        };
+        if let (NetworkTarget::External(_), Some(_)) = (&target, &self.gas_sponsor) {
+            check_gas_sponsor(config, gas_sponsor)?;
+        }
+        // the id of the verifier deployed below, or the one it would have without a deploy.
+        let binding = match &target {
+            NetworkTarget::InProcess => OwnerBinding {
+                verifier: VerifierDeployment::new(config, &self.protocol_fee, &gas_sponsor)
+                    .contract_id()
+                    .map_err(NetworkError::from)?,
+                protocol_fee: self.protocol_fee.clone(),
+            },
+            NetworkTarget::External(network) => OwnerBinding {
+                verifier: network.verifier_contract_id,
+                protocol_fee: ProtocolFee::from_config(config),
+            },
+        };
+        for spec in &self.wallets {
+            if let WalletKind::OwnerPredicate { evm_address } = &spec.kind {
+                let bytecode = get_owner_predicate_info(&config.artifacts, &binding, *evm_address).0;
+                wallets.insert(spec.name.clone(), ScenarioAccount::predicate(bytecode));
+            }
+        }
+
+        let provider = match &target {
+            NetworkTarget::InProcess => self.launch_node(funder.address(), &wallets).await?,
+            NetworkTarget::External(_) => funder.provider().unwrap().clone(),
+        };
+        funder.set_provider(provider.clone());
+        let wallets: BTreeMap<String, ScenarioAccount> = wallets
+            .into_iter()
+            .map(|(name, account)| (name, account.with_provider(&provider)))
+            .collect();
+
+        let verifier_id = match &target {
+            NetworkTarget::InProcess if self.deploy_verifier => {
+                let (_, verifier_id) = contract_verifier_instance(config, funder.clone(), &self.protocol_fee, &gas_sponsor)
+                    .await
+                    .map_err(NetworkError::from)?;
+                Some(verifier_id)
+            }
+            NetworkTarget::InProcess => None,
+            NetworkTarget::External(network) => Some(network.verifier_contract_id),
+        };
+
+        for (wallet, amount) in &self.base_coins {
+            funder
+                .transfer(&wallets[wallet].b32address(), *amount, AssetId::BASE, TxPolicies::default())
+                .await
+                .map_err(|e| ScenarioError::Provider(e.to_string()))?;
+        }
+
+        let token_minter_id = match &target {
+            NetworkTarget::InProcess => tokenminter::deploy_tokenminter(config, &funder)
+                .await
+                .map_err(NetworkError::from)?,
+            NetworkTarget::External(network) => network.token_minter_contract_id,
+        };
+
+        let mut assets = BTreeMap::new();
+        for (index, spec) in self.assets.iter().enumerate() {
+            let sub_id = match (&target, spec.sub_id) {
+                (NetworkTarget::InProcess, Some(sub_id)) => sub_id,
+                (NetworkTarget::InProcess, None) => next_sub_id(index),
+                (NetworkTarget::External(_), _) => ExternalNetwork::fresh_sub_id(),
+            };
+            for (wallet, amount) in &spec.coins {
+                tokenminter::mint_to(token_minter_id, &funder, wallets[wallet].address(), *amount, sub_id).await;
+            }
+            let asset_id = AssetId::from(*get_assetid_for_subid_and_cid(Bytes32::from(sub_id.0), token_minter_id));
+            info!(asset = %spec.name, sub_id = %hex::encode(sub_id.0), asset_id = %hex::encode(asset_id), "asset minted");
+            assets.insert(spec.name.clone(), asset_id);
+        }
+
+        for (name, account) in &wallets {
+            info!(wallet = %name, address = %hex::encode(account.address()), "scenario wallet");
+        }
+
+        Ok(Scenario {
+            provider,
+            funder,
+            verifier_id,
+            binding,
+            token_minter_id,
+            wallets,
+            assets,
+        })
+    }
+
+    /// a new in-process node with the funder coins and the message coins in its genesis state.
+    async fn launch_node(
+        &self,
+        funder: &Bech32Address,
+        wallets: &BTreeMap<String, ScenarioAccount>,
+    ) -> StdResult<Provider, ScenarioError> {
+        let mut node_config = NodeConfig::default();
+        node_config.starting_gas_price = 1;
+
+        let (num_coins, amount_per_coin) = self.funder_coins;
+        let coins = setup_single_asset_coins(funder, AssetId::BASE, num_coins, amount_per_coin);
+        let message_sender = Bech32Address::from(Address::new(MESSAGE_SENDER));
+        let messages: Vec<Message> = self.messages
+            .iter()
+            .enumerate()
+            .map(|(index, (wallet, amount))| setup_single_message(
+                &message_sender,
+                &wallets[wallet].b32address(),
+                *amount,
+                message_nonce(index),
+                vec![],
+            ))
+            .collect();
+
+        setup_test_provider(coins, messages, Some(node_config), self.chain_config.clone())
+            .await
+            .map_err(|e| ScenarioError::Provider(e.to_string()))
+    }
+}
+
+/// the nonce of the message coin at index, from 1.
+fn message_nonce(index: usize) -> Nonce {
+    let mut nonce = [0u8; 32];
+    nonce[24..].copy_from_slice(&(index as u64 + 1).to_be_bytes());
+    Nonce::new(nonce)
+}
+
+/// the in-process sub id of the asset at index, 0 for the first as in setup_case_01.
+fn next_sub_id(index: usize) -> Bits256 {
+    let mut sub_id = [0u8; 32];
+    sub_id[24..].copy_from_slice(&(index as u64).to_be_bytes());
+    Bits256(sub_id)
+}
+
+
+/// a wallet of a scenario.
+#[derive(Clone, Debug)]
+pub enum ScenarioAccount {
+    Predicate { bytecode: Vec<u8>, predicate: Predicate },
+    Wallet(WalletUnlocked),
+}
+
+impl ScenarioAccount {
+
+    fn predicate(bytecode: Vec<u8>) -> Self {
+        let predicate = Predicate::from_code(bytecode.clone());
+        ScenarioAccount::Predicate { bytecode, predicate }
+    }
+
+    fn with_provider(self, provider: &Provider) -> Self {
+        match self {
+            ScenarioAccount::Predicate { bytecode, predicate } => {
+                ScenarioAccount::Predicate { bytecode, predicate: predicate.with_provider(provider.clone()) }
+            }
+            ScenarioAccount::Wallet(mut wallet) => {
+                wallet.set_provider(provider.clone());
+                ScenarioAccount::Wallet(wallet)
+            }
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        Address::from(&self.b32address())
+    }
+
+    pub fn b32address(&self) -> Bech32Address {
+        match self {
+            ScenarioAccount::Predicate { predicate, .. } => predicate.address().clone(),
+            ScenarioAccount::Wallet(wallet) => wallet.address().clone(),
+        }
+    }
+
+    /// the account paying the gas with the inputs of this wallet.
+    pub fn sponsor_account(&self) -> SponsorAccount {
+        match self {
+            ScenarioAccount::Predicate { predicate, .. } => SponsorAccount::Predicate(predicate.clone()),
+            ScenarioAccount::Wallet(wallet) => SponsorAccount::Wallet(wallet.clone()),
+        }
+    }
+
+    /// the predicate bytecode, None for an EOA.
+    pub fn bytecode(&self) -> Option<&Vec<u8>> {
+        match self {
+            ScenarioAccount::Predicate { bytecode, .. } => Some(bytecode),
+            ScenarioAccount::Wallet(_) => None,
+        }
+    }
+}
+
+
+/// what ScenarioBuilder::build set up, the wallets and assets by the names they were declared with.
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub provider: Provider,
+    /// the wallet that funded the setup, the in-process node wallet or FUEL_WALLET_SK.
+    pub funder: WalletUnlocked,
+    pub verifier_id: Option<ContractId>,
+    /// the verifier and protocol fee the owner predicate wallets are bound to.
+    pub binding: OwnerBinding,
+    pub token_minter_id: ContractId,
+    wallets: BTreeMap<String, ScenarioAccount>,
+    assets: BTreeMap<String, AssetId>,
+}
+
+impl Scenario {
+
+    pub fn wallet(&self, name: &str) -> StdResult<&ScenarioAccount, ScenarioError> {
+        self.wallets.get(name).ok_or(ScenarioError::UnknownWallet(name.to_string()))
+    }
+
+    pub fn asset_id(&self, name: &str) -> StdResult<AssetId, ScenarioError> {
+        self.assets.get(name).copied().ok_or(ScenarioError::UnknownAsset(name.to_string()))
+    }
+
+    /// the bytecode and address of a predicate wallet.
+    pub fn predicate(&self, name: &str) -> StdResult<(Vec<u8>, Bech32Address), ScenarioError> {
+        let account = self.wallet(name)?;
+        let bytecode = account.bytecode().ok_or(ScenarioError::NotAPredicate(name.to_string()))?;
+        Ok((bytecode.clone(), account.b32address()))
+    }
+
+    pub fn wallets(&self) -> impl Iterator<Item = (&String, &ScenarioAccount)> {
+        self.wallets.iter()
+    }
+
+    pub fn assets(&self) -> impl Iterator<Item = (&String, &AssetId)> {
+        self.assets.iter()
+    }
+}
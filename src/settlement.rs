@@ -0,0 +1,466 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::result::Result as StdResult;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use fuels::prelude::*;
+
+use crate::{
+    helpers::{conversions::bits256_to_u64, swap_math},
+    interfaces::generalized_swap_verifier_interface::{GenIO, ProtocolFee},
+    relay::intent_book::to_hex,
+};
+
+
+#[derive(Error, Debug)]
+pub enum SettlementError {
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("The intent input amounts of {0} overflow")]
+    AmountOverflow(AssetId),
+    #[error("The protocol fee is only taken on intents with a single input asset")]
+    MixedInputAssets,
+}
+
+
+/// the part an address plays in a swap, one address can play several.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// the owner predicate the intent inputs are spent from.
+    Owner,
+    /// the recipient signed into the intent.
+    Recipient,
+    /// the solver predicate delivering the output asset.
+    Solver,
+    FeeRecipient,
+    GasPayer,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Role::Owner => "owner",
+            Role::Recipient => "recipient",
+            Role::Solver => "solver",
+            Role::FeeRecipient => "fee_recipient",
+            Role::GasPayer => "gas_payer",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+
+/// the balance change an address may see, min None when the gas comes out of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Expected {
+    min: Option<i128>,
+    max: i128,
+}
+
+impl Default for Expected {
+    fn default() -> Self {
+        Expected::exact(0)
+    }
+}
+
+impl Expected {
+
+    fn exact(amount: i128) -> Self {
+        Expected { min: Some(amount), max: amount }
+    }
+
+    fn add(&mut self, other: Expected) {
+        self.min = self.min.zip(other.min).map(|(a, b)| a + b);
+        self.max += other.max;
+    }
+
+    fn contains(&self, delta: i128) -> bool {
+        self.min.map_or(true, |min| delta >= min) && delta <= self.max
+    }
+}
+
+
+/// the addresses and assets a swap of an intent touches, and the balance changes the intent allows.
+#[derive(Clone, Debug)]
+pub struct Settlement {
+    parties: Vec<(Role, Address)>,
+    assets: Vec<AssetId>,
+    expected: BTreeMap<(Address, AssetId), Expected>,
+    gas_payer: Address,
+    protocol_fee: u64,
+}
+
+impl Settlement {
+
+    /// the swap of the intent: the owner pays the intent input amounts, the recipient receives
+    /// the output amount within the tolerance, which the solver delivers for the inputs net of
+    /// the protocol fee. The fee is paid in the first input asset, as the SwapVerifier checks it,
+    /// so with a fee the inputs must all be of that asset.
+    pub fn new(
+        gio_data: &GenIO,
+        protocol_fee: &ProtocolFee,
+        owner: Address,
+        solver: Address,
+        gas_payer: Address,
+    ) -> StdResult<Self, SettlementError> {
+        let recipient = Address::from(gio_data.recipient.0);
+        let fee_asset = AssetId::from(gio_data.inputassets[0].0);
+        let asset_out = AssetId::from(gio_data.outputasset.0);
+
+        // the input amounts by asset, the slots with no amount are not inputs.
+        let mut totals_in: BTreeMap<AssetId, u64> = BTreeMap::new();
+        for (asset, amount) in gio_data.inputassets.iter().zip(gio_data.inputamounts.iter()) {
+            let amount = bits256_to_u64(*amount);
+            if amount == 0 {
+                continue;
+            }
+            let asset_id = AssetId::from(asset.0);
+            let total = totals_in.entry(asset_id).or_default();
+            *total = total.checked_add(amount).ok_or(SettlementError::AmountOverflow(asset_id))?;
+        }
+        let fee = match protocol_fee.bps {
+            0 => 0,
+            _ => protocol_fee.fee_for_intent(gio_data).ok_or(SettlementError::MixedInputAssets)?,
+        };
+        let (amount_out_lower, amount_out_upper) = swap_math::tolerance_bounds(
+            bits256_to_u64(gio_data.outputamount),
+            bits256_to_u64(gio_data.tolerance),
+        );
+
+        let mut parties = vec![
+            (Role::Owner, owner),
+            (Role::Recipient, recipient),
+            (Role::Solver, solver),
+            (Role::GasPayer, gas_payer),
+        ];
+        if protocol_fee.bps > 0 {
+            parties.push((Role::FeeRecipient, protocol_fee.recipient));
+        }
+
+        let mut assets: Vec<AssetId> = totals_in.keys().copied().collect();
+        assets.extend([fee_asset, asset_out, AssetId::BASE]);
+        assets.sort();
+        assets.dedup();
+
+        let mut settlement = Settlement {
+            parties,
+            assets,
+            expected: BTreeMap::new(),
+            gas_payer,
+            protocol_fee: fee,
+        };
+        for (asset_id, total_in) in &totals_in {
+            let solver_amount_in = if *asset_id == fee_asset { total_in - fee } else { *total_in };
+            settlement.expect(owner, *asset_id, Expected::exact(-(*total_in as i128)));
+            settlement.expect(solver, *asset_id, Expected::exact(solver_amount_in as i128));
+        }
+        settlement.expect(recipient, asset_out, Expected {
+            min: Some(amount_out_lower as i128),
+            max: amount_out_upper as i128,
+        });
+        settlement.expect(solver, asset_out, Expected {
+            min: Some(-(amount_out_upper as i128)),
+            max: -(amount_out_lower as i128),
+        });
+        if protocol_fee.bps > 0 {
+            settlement.expect(protocol_fee.recipient, fee_asset, Expected::exact(fee as i128));
+        }
+        Ok(settlement)
+    }
+
+    fn expect(&mut self, address: Address, asset_id: AssetId, expected: Expected) {
+        self.expected.entry((address, asset_id)).or_default().add(expected);
+    }
+
+    fn addresses(&self) -> Vec<Address> {
+        let mut addresses: Vec<Address> = self.parties.iter().map(|(_, address)| *address).collect();
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    /// the balances of every party in every asset of the swap.
+    #[instrument(name = "balance_snapshot", skip_all)]
+    pub async fn snapshot(&self, provider: &Provider) -> StdResult<BalanceSnapshot, SettlementError> {
+        let mut balances = BTreeMap::new();
+        for address in self.addresses() {
+            for asset_id in &self.assets {
+                let balance = provider
+                    .get_asset_balance(&Bech32Address::from(address), *asset_id)
+                    .await
+                    .map_err(|e| SettlementError::Provider(e.to_string()))?;
+                debug!(address = %address, asset_id = %asset_id, balance, "balance");
+                balances.insert((address, *asset_id), balance);
+            }
+        }
+        Ok(BalanceSnapshot { balances })
+    }
+
+    /// the balance changes between the snapshots, checked against the intent.
+    pub fn report(&self, before: &BalanceSnapshot, after: &BalanceSnapshot) -> SettlementReport {
+        let mut deltas = vec![];
+        let mut gas_fee = None;
+        for address in self.addresses() {
+            let roles: Vec<Role> = self.parties.iter()
+                .filter(|(_, party)| *party == address)
+                .map(|(role, _)| *role)
+                .collect();
+            for asset_id in &self.assets {
+                let key = (address, *asset_id);
+                let before = before.balance(&address, asset_id);
+                let after = after.balance(&address, asset_id);
+                let delta = after as i128 - before as i128;
+                let mut expected = self.expected.get(&key).copied().unwrap_or_default();
+
+                // the gas is what the gas payer lost beyond its part of the swap, when that part is exact.
+                if address == self.gas_payer && *asset_id == AssetId::BASE {
+                    if expected.min == Some(expected.max) {
+                        gas_fee = u64::try_from(expected.max - delta).ok();
+                    }
+                    expected.add(Expected { min: None, max: 0 });
+                }
+
+                deltas.push(BalanceDelta {
+                    roles: roles.clone(),
+                    address: to_hex(&*address),
+                    asset_id: to_hex(&**asset_id),
+                    before,
+                    after,
+                    delta,
+                    expected_min: expected.min,
+                    expected_max: expected.max,
+                    passed: expected.contains(delta),
+                });
+            }
+        }
+        SettlementReport {
+            deltas,
+            protocol_fee: self.protocol_fee,
+            gas_fee,
+        }
+    }
+}
+
+
+/// the balances of the parties of a swap at one point in time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BalanceSnapshot {
+    balances: BTreeMap<(Address, AssetId), u64>,
+}
+
+impl BalanceSnapshot {
+
+    pub fn balance(&self, address: &Address, asset_id: &AssetId) -> u64 {
+        self.balances.get(&(*address, *asset_id)).copied().unwrap_or_default()
+    }
+}
+
+
+/// the balance change of one address in one asset.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BalanceDelta {
+    pub roles: Vec<Role>,
+    pub address: String,
+    pub asset_id: String,
+    pub before: u64,
+    pub after: u64,
+    pub delta: i128,
+    /// None for the gas payer base asset, which loses the gas.
+    pub expected_min: Option<i128>,
+    pub expected_max: i128,
+    pub passed: bool,
+}
+
+/// who received and paid what in a swap, against what the intent allows.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SettlementReport {
+    pub deltas: Vec<BalanceDelta>,
+    /// the protocol fee the fee recipient is owed, in the first input asset.
+    pub protocol_fee: u64,
+    /// the gas the gas payer paid, None when its base asset also changed by a range in the swap.
+    pub gas_fee: Option<u64>,
+}
+
+impl SettlementReport {
+
+    pub fn passed(&self) -> bool {
+        self.deltas.iter().all(|delta| delta.passed)
+    }
+
+    /// the balance change of the address in the asset, 0 when it is not a party.
+    pub fn delta(&self, address: &Address, asset_id: &AssetId) -> i128 {
+        let (address, asset_id) = (to_hex(&**address), to_hex(&**asset_id));
+        self.deltas.iter()
+            .find(|delta| delta.address == address && delta.asset_id == asset_id)
+            .map_or(0, |delta| delta.delta)
+    }
+}
+
+impl fmt::Display for SettlementReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn short(hex: &str) -> String {
+            format!("{}..{}", &hex[..8], &hex[hex.len() - 4..])
+        }
+
+        writeln!(
+            f,
+            "{:<26} {:<14} {:<14} {:>20} {:>20} {:>21} {:>45}  {}",
+            "roles", "address", "asset", "before", "after", "delta", "expected", "ok",
+        )?;
+        for delta in &self.deltas {
+            let roles: Vec<String> = delta.roles.iter().map(|role| role.to_string()).collect();
+            let expected = match delta.expected_min {
+                Some(min) if min == delta.expected_max => min.to_string(),
+                Some(min) => format!("{}..={}", min, delta.expected_max),
+                None => format!("..={}", delta.expected_max),
+            };
+            writeln!(
+                f,
+                "{:<26} {:<14} {:<14} {:>20} {:>20} {:>21} {:>45}  {}",
+                roles.join(","),
+                short(&delta.address),
+                short(&delta.asset_id),
+                delta.before,
+                delta.after,
+                delta.delta,
+                expected,
+                if delta.passed { "OK" } else { "FAIL" },
+            )?;
+        }
+        writeln!(f, "protocol fee: {}", self.protocol_fee)?;
+        match self.gas_fee {
+            Some(gas_fee) => writeln!(f, "gas fee     : {}", gas_fee)?,
+            None => writeln!(f, "gas fee     : -")?,
+        }
+        write!(f, "passed      : {}", self.passed())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuels::types::Bits256;
+    use crate::{
+        helpers::conversions::u64_to_bits256,
+        interfaces::generalized_swap_verifier_interface::populate_genio,
+    };
+
+    const OWNER: Address = Address::new([1u8; 32]);
+    const SOLVER: Address = Address::new([2u8; 32]);
+    const GAS_PAYER: Address = Address::new([3u8; 32]);
+    const FEE_RECIPIENT: Address = Address::new([5u8; 32]);
+    const RECIPIENT: Address = Address::new([7u8; 32]);
+    const ASSET_IN: AssetId = AssetId::new([4u8; 32]);
+    const ASSET_OUT: AssetId = AssetId::new([9u8; 32]);
+
+    fn genio(assets_in: [AssetId; 2], amounts_in: [u64; 2]) -> GenIO {
+        let zero = Bits256::zeroed();
+        populate_genio(
+            [Bits256(*assets_in[0]), Bits256(*assets_in[1]), zero, zero, zero],
+            [Bits256([0x11; 32]), Bits256([0x22; 32]), zero, zero, zero],
+            [u64_to_bits256(amounts_in[0]), u64_to_bits256(amounts_in[1]), zero, zero, zero],
+            Bits256(*ASSET_OUT),
+            u64_to_bits256(2_000_000_000),
+            u64_to_bits256(250),
+            Bits256(*RECIPIENT),
+        ).unwrap()
+    }
+
+    fn fee(bps: u64) -> ProtocolFee {
+        ProtocolFee { recipient: FEE_RECIPIENT, bps }
+    }
+
+    fn snapshot(balances: &[(Address, AssetId, u64)]) -> BalanceSnapshot {
+        BalanceSnapshot {
+            balances: balances.iter().map(|(address, asset_id, balance)| ((*address, *asset_id), *balance)).collect(),
+        }
+    }
+
+    fn before() -> BalanceSnapshot {
+        snapshot(&[
+            (OWNER, ASSET_IN, 1_520_000_000),
+            (SOLVER, ASSET_OUT, 4_000_000_000),
+            (GAS_PAYER, AssetId::BASE, 1_000_000),
+        ])
+    }
+
+    fn after(recipient_amount_out: u64) -> BalanceSnapshot {
+        snapshot(&[
+            (SOLVER, ASSET_IN, 1_515_440_000),
+            (SOLVER, ASSET_OUT, 4_000_000_000 - recipient_amount_out),
+            (RECIPIENT, ASSET_OUT, recipient_amount_out),
+            (FEE_RECIPIENT, ASSET_IN, 4_560_000),
+            (GAS_PAYER, AssetId::BASE, 1_000_000 - 1_234),
+        ])
+    }
+
+    fn settlement(protocol_fee: &ProtocolFee, gio_data: &GenIO) -> StdResult<Settlement, SettlementError> {
+        Settlement::new(gio_data, protocol_fee, OWNER, SOLVER, GAS_PAYER)
+    }
+
+    #[test]
+    fn swap_within_the_intent_passes() {
+        let gio_data = genio([ASSET_IN, ASSET_IN], [1_000_000_000, 520_000_000]);
+        let report = settlement(&fee(30), &gio_data).unwrap().report(&before(), &after(1_960_000_000));
+
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.protocol_fee, 4_560_000);
+        assert_eq!(report.gas_fee, Some(1_234));
+        assert_eq!(report.delta(&OWNER, &ASSET_IN), -1_520_000_000);
+        assert_eq!(report.delta(&RECIPIENT, &ASSET_OUT), 1_960_000_000);
+        assert_eq!(report.delta(&FEE_RECIPIENT, &ASSET_IN), 4_560_000);
+    }
+
+    #[test]
+    fn recipient_below_the_tolerance_fails() {
+        let gio_data = genio([ASSET_IN, ASSET_IN], [1_000_000_000, 520_000_000]);
+        let report = settlement(&fee(30), &gio_data).unwrap().report(&before(), &after(1_940_000_000));
+
+        assert!(!report.passed());
+        let failed: Vec<&BalanceDelta> = report.deltas.iter().filter(|delta| !delta.passed).collect();
+        assert_eq!(failed.len(), 2);
+        assert!(failed.iter().all(|delta| delta.asset_id == to_hex(&*ASSET_OUT)));
+    }
+
+    #[test]
+    fn unpaid_fee_fails() {
+        let gio_data = genio([ASSET_IN, ASSET_IN], [1_000_000_000, 520_000_000]);
+        let mut after = after(2_000_000_000);
+        after.balances.remove(&(FEE_RECIPIENT, ASSET_IN));
+        after.balances.insert((SOLVER, ASSET_IN), 1_520_000_000);
+
+        let report = settlement(&fee(30), &gio_data).unwrap().report(&before(), &after);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn expected_deltas_per_input_asset() {
+        let other_asset = AssetId::new([6u8; 32]);
+        let gio_data = genio([ASSET_IN, other_asset], [1_000_000_000, 520_000_000]);
+        let settlement = settlement(&ProtocolFee::none(), &gio_data).unwrap();
+
+        assert_eq!(settlement.expected[&(OWNER, ASSET_IN)], Expected::exact(-1_000_000_000));
+        assert_eq!(settlement.expected[&(OWNER, other_asset)], Expected::exact(-520_000_000));
+        assert_eq!(settlement.expected[&(SOLVER, ASSET_IN)], Expected::exact(1_000_000_000));
+        assert_eq!(settlement.expected[&(SOLVER, other_asset)], Expected::exact(520_000_000));
+        assert_eq!(settlement.protocol_fee, 0);
+    }
+
+    #[test]
+    fn fee_with_mixed_input_assets_is_an_error() {
+        let gio_data = genio([ASSET_IN, AssetId::new([6u8; 32])], [1_000_000_000, 520_000_000]);
+        assert!(matches!(settlement(&fee(30), &gio_data), Err(SettlementError::MixedInputAssets)));
+    }
+
+    #[test]
+    fn overflowing_input_amounts_are_an_error() {
+        let gio_data = genio([ASSET_IN, ASSET_IN], [u64::MAX, 1]);
+        assert!(matches!(
+            settlement(&ProtocolFee::none(), &gio_data),
+            Err(SettlementError::AmountOverflow(asset_id)) if asset_id == ASSET_IN
+        ));
+    }
+}
@@ -1,181 +1,96 @@
 use sha2::{Sha256, Digest};
 use tokio::io::Error;
+use tracing::{info, instrument};
 use fuels::{
     prelude::*,
-    prelude::{
-        Address, ContractId,
-    },
-    types::{
-        Bits256, Bytes32,
-        transaction::TxPolicies,
-    },
-    accounts::wallet::WalletUnlocked,
+    prelude::ContractId,
+    types::Bytes32,
 };
 
 
 pub mod setup_case_01 {
     use super::*;
-    use crate::interfaces::{
-        dummy_pwallet::get_dummypwallet_info,
-        generalized_swap_verifier_interface::contract_verifier_instance,
-        tokenminter,
-    };
-
-    pub async fn setup_predicate_and_assets_sdk_provider() -> std::result::Result<(
+    use crate::interfaces::generalized_swap_verifier_interface::ProtocolFee;
+    use crate::config::Config;
+    use crate::scenario::{ScenarioBuilder, WalletKind};
+    use crate::sponsor::SponsorAccount;
+    use crate::wallets::{get_evm_addr, test_sender_evm_sk};
+
+    /// owner_bytecode: the predicate holding the asset going in to the swap,
+    /// None for the owner predicate of the EVM sender address.
+    ///
+    /// runs on the network target of the config: a new in-process node where everything is
+    /// deployed, or an external node where the deployed contracts are used and the test assets
+    /// are minted under new sub ids. protocol_fee only applies to a SwapVerifier deployed here.
+    #[instrument(name = "setup", skip_all, fields(case = "01", target = ?config.network.target))]
+    pub async fn setup_predicate_and_assets_sdk_provider(
+        config: &Config,
+        protocol_fee: &ProtocolFee,
+        owner_bytecode: Option<Vec<u8>>,
+    ) -> std::result::Result<(
         Provider,
         ContractId,     // SwapVerifier contractid.
-        Vec<u8>,        // dummypwallet predicate bytecode.
-        Bech32Address,  // dummypwallet address.
+        Vec<u8>,        // owner predicate bytecode.
+        Bech32Address,  // owner predicate address.
         Vec<u8>,        // dummypsolver predicate bytecode.
         Bech32Address,  // dummypsolver address.
+        SponsorAccount, // gas sponsor, the wallet that funded the setup.
         Bytes32,        // token in asset id.
         Bytes32,        // token out asset id.
     ), Error> {
 
-        let mut node_config = NodeConfig::default();
-        node_config.starting_gas_price = 1;
-
-        let wallets_config = WalletsConfig::new(
-                Some(1),             /* Single wallet */
-                Some(1),             /* Single coin (UTXO) */
-                Some(1_000_000_000_000), /* Amount per coin */
-        );
-
-        let mut wallets = launch_custom_provider_and_get_wallets(
-            wallets_config,
-            Some(node_config),
-            None,
-        )
-        .await
-        .unwrap();
-        let wallet = wallets.pop().unwrap();
-        let provider = wallet.provider().clone().unwrap();
-
-        let (_swapverifier_instance, swapverifier_cid) = contract_verifier_instance(wallet.clone()).await;
-
-        // create a dummy wallet that only holds the asset going in to the swap.
-        let (dummypwallet_bytecode, _, _) = get_dummypwallet_info(0u8);
-
-        let dummypwallet_predicate: Predicate =
-            Predicate::from_code(dummypwallet_bytecode.clone())
-                .with_provider(provider.clone());
-        let dummypwallet_predicate_address: Address = dummypwallet_predicate.address().into();
-
-        // create a dummy solver wallet that holds the desired swap asset (the out asset) and some gas
-        let (dummypsolver_bytecode, _, _) = get_dummypwallet_info(1u8);
-
-        let dummypsolver_predicate: Predicate =
-            Predicate::from_code(dummypsolver_bytecode.clone())
-                .with_provider(provider.clone());
-        let dummypsolver_predicate_address: Address = dummypsolver_predicate.address().into();
-
-        // wallet transfers base asset (for gas) to solver predicate. Solver will pay the gas for the tx.
-        wallet
-            .transfer(
-                &dummypsolver_predicate.address(),
-                5000001,
-                AssetId::BASE,
-                TxPolicies::default()
-            )
+        // the user's predicate wallet, that only holds the asset going in to the swap.
+        let owner_kind = match owner_bytecode {
+            Some(bytecode) => WalletKind::Predicate(bytecode),
+            None => WalletKind::OwnerPredicate { evm_address: get_evm_addr(test_sender_evm_sk()).0 },
+        };
+
+        // the solver holds the desired swap asset (the out asset) and some gas, the wallet that
+        // funds the setup pays the gas for solvers. The asset in comes in two coins.
+        let scenario = ScenarioBuilder::new(config)
+            .protocol_fee(protocol_fee)
+            .wallet("owner", owner_kind)
+            .dummy_predicate("solver", 1u8)
+            .funder("sponsor")
+            .gas_sponsor("sponsor")
+            .base_coins("solver", &[5000001])
+            .coins("asset_in", "owner", &[1_000_000_000, 520_000_000])
+            .coins("asset_out", "solver", &[2_000_000_000])
+            .build()
             .await
-            .unwrap();
-
-        // deploy a token minter contract, and send some amount different types of assets to the two
-        // dummy predicate wallet and solver addresses.
-        let pwals: Vec<Address> = vec![
-            dummypwallet_predicate_address,
-            dummypsolver_predicate_address
-        ];
-        let (assetin_assetid, assetout_assetid) = deploy_token_minter_mint_to(
-            &wallet,
-            pwals,
-        ).await;
-
-        println!("\n------------------------------------------------------------------- (addresses):");
-        println!("SwapVerifier: {}", hex::encode(swapverifier_cid));
-        println!(" ");
-        println!("DummyPredicateWallet address: {}", hex::encode(dummypwallet_predicate_address));
-        println!(" ");
-        println!("DummyPredicateSolver address: {}", hex::encode(dummypsolver_predicate_address));
+            .map_err(Error::other)?;
+
+        let (owner_bytecode, owner_b32addr) = scenario.predicate("owner").map_err(Error::other)?;
+        let (solver_bytecode, solver_b32addr) = scenario.predicate("solver").map_err(Error::other)?;
+        let sponsor = scenario.wallet("sponsor").map_err(Error::other)?.sponsor_account();
+        let swapverifier_cid = scenario.verifier_id.unwrap();
+        let assetin_assetid = Bytes32::from(*scenario.asset_id("asset_in").map_err(Error::other)?);
+        let assetout_assetid = Bytes32::from(*scenario.asset_id("asset_out").map_err(Error::other)?);
+
+        info!(
+            verifier_id = %hex::encode(swapverifier_cid),
+            owner_predicate = %hex::encode(owner_b32addr.hash()),
+            solver_predicate = %hex::encode(solver_b32addr.hash()),
+            gas_sponsor = %hex::encode(sponsor.address()),
+            "setup addresses",
+        );
 
         Ok((
-            provider.clone(),
+            scenario.provider,
             swapverifier_cid,
-            dummypwallet_bytecode,
-            dummypwallet_predicate.address().clone(),
-            dummypsolver_bytecode,
-            dummypsolver_predicate.address().clone(),
+            owner_bytecode,
+            owner_b32addr,
+            solver_bytecode,
+            solver_b32addr,
+            sponsor,
             assetin_assetid,
             assetout_assetid,
         ))
     }
 
-    /// Deploy a fresh TokenMinter contract.
-    pub async fn deploy_token_minter_mint_to(
-        wallet_with_gas: &WalletUnlocked,
-        address_to: Vec<Address>,
-    ) -> (Bytes32, Bytes32) {
-
-        // Deploying TokenMinter contract (uses sdk wallet
-        let tokenminter_cid = tokenminter::deploy_tokenminter(wallet_with_gas).await;
-
-        let assetin_sub_id = Bits256::from_hex_str(&u64_to_bits256_hex(0u64)).unwrap();
-        let assetout_sub_id = Bits256::from_hex_str(&u64_to_bits256_hex(1u64)).unwrap();
-
-        // mint the first 1_000_000_000 and send to address idx 0
-        tokenminter::mint_to(
-            tokenminter_cid,
-            wallet_with_gas,
-            *address_to.get(0).unwrap(),
-            1_000_000_000u64,
-            assetin_sub_id,
-        ).await;
-
-        // mint the second 500_000_000 and send to address idx 0
-        tokenminter::mint_to(
-            tokenminter_cid,
-            wallet_with_gas,
-            *address_to.get(0).unwrap(),
-            520_000_000u64,
-            assetin_sub_id,
-        ).await;
-
-        // mint the third 2_000_000_000 and send to address idx 1
-        tokenminter::mint_to(
-            tokenminter_cid,
-            wallet_with_gas,
-            *address_to.get(1).unwrap(),
-            2_000_000_000u64,
-            assetout_sub_id,
-        ).await;
-
-        println!(" ");
-        let tmcid_hex = tokenminter_cid.as_slice();
-        println!("TokenMinter contractid hex: {}", hex::encode(tmcid_hex));
-
-        let assetin_assetid = get_assetid_for_subid_and_cid(
-            Bytes32::from(assetin_sub_id.0), tokenminter_cid);
-        println!("Asset In: ");
-        println!("sub_id  : {}", hex::encode(assetin_sub_id.0));
-        println!("assetid : {}", hex::encode(assetin_assetid));
-
-        let assetout_assetid = get_assetid_for_subid_and_cid(
-            Bytes32::from(assetout_sub_id.0), tokenminter_cid);
-        println!("Asset Out: ");
-        println!("sub_id  : {}", hex::encode(assetout_sub_id.0));
-        println!("assetid : {}", hex::encode(assetout_assetid));
-
-        (assetin_assetid, assetout_assetid)
-
-    }
-
-}
-
-fn u64_to_bits256_hex(value: u64) -> String {
-    format!("0x{:064x}", value)
 }
 
-fn get_assetid_for_subid_and_cid(
+pub fn get_assetid_for_subid_and_cid(
     sub_id: Bytes32,
     contract: ContractId
 ) -> Bytes32 {
@@ -0,0 +1,96 @@
+use tokio::io::Error;
+use tracing::{info, instrument};
+use fuels::{
+    prelude::*,
+    types::Bytes32,
+};
+
+
+/// setup for swapping the base asset (ETH) for a minted token, and back.
+pub mod setup_case_02 {
+    use super::*;
+    use crate::interfaces::generalized_swap_verifier_interface::ProtocolFee;
+    use crate::config::Config;
+    use crate::scenario::ScenarioBuilder;
+    use crate::sponsor::SponsorAccount;
+
+    pub const SENDER_BASE_AMOUNT_1: u64 = 1_000_000_000;
+    pub const SENDER_BASE_AMOUNT_2: u64 = 520_000_000;
+    pub const SOLVER_BASE_AMOUNT: u64 = 5_000_000_000;
+    pub const SOLVER_TOKEN_AMOUNT: u64 = 4_000_000_000;
+
+    /// how the sender receives its base asset.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum SenderFunding {
+        /// transferred as coins from the funded wallet.
+        Coins,
+        /// message coins (as if bridged in), created in the node's genesis state, see
+        /// ScenarioBuilder::messages.
+        Messages,
+    }
+
+    /// on an external node the sender can only be funded with coins, message coins are only
+    /// created in the genesis state of the in-process node.
+    #[instrument(name = "setup", skip_all, fields(case = "02", target = ?config.network.target, sender_funding = ?sender_funding))]
+    pub async fn setup_base_asset_predicates_sdk_provider(
+        config: &Config,
+        protocol_fee: &ProtocolFee,
+        sender_funding: SenderFunding,
+        sender_evm_address: [u8; 20],
+    ) -> std::result::Result<(
+        Provider,
+        ContractId,     // SwapVerifier contractid.
+        Vec<u8>,        // owner predicate bytecode.
+        Bech32Address,  // owner predicate address.
+        Vec<u8>,        // dummypsolver predicate bytecode.
+        Bech32Address,  // dummypsolver address.
+        SponsorAccount, // gas sponsor, the wallet that funded the setup.
+        Bytes32,        // token asset id.
+    ), Error> {
+
+        // the sender's predicate wallet (for sender_evm_address) only holds the base asset, in
+        // two coins or two messages. The solver holds the token, and the base asset to buy the
+        // token back. The wallet that funds the setup pays the gas for solvers.
+        let sender_amounts = [SENDER_BASE_AMOUNT_1, SENDER_BASE_AMOUNT_2];
+        let builder = ScenarioBuilder::new(config)
+            .protocol_fee(protocol_fee)
+            .owner_predicate_of("owner", sender_evm_address)
+            .dummy_predicate("solver", 1u8)
+            .funder("sponsor")
+            .gas_sponsor("sponsor")
+            .base_coins("solver", &[SOLVER_BASE_AMOUNT])
+            .coins("token", "solver", &[SOLVER_TOKEN_AMOUNT]);
+        let builder = match sender_funding {
+            SenderFunding::Coins => builder.base_coins("owner", &sender_amounts),
+            SenderFunding::Messages => builder.messages("owner", &sender_amounts),
+        };
+        let scenario = builder.build().await.map_err(Error::other)?;
+
+        let (dummypwallet_bytecode, dummypwallet_b32addr) = scenario.predicate("owner").map_err(Error::other)?;
+        let (dummypsolver_bytecode, dummypsolver_b32addr) = scenario.predicate("solver").map_err(Error::other)?;
+        let sponsor = scenario.wallet("sponsor").map_err(Error::other)?.sponsor_account();
+        let swapverifier_cid = scenario.verifier_id.unwrap();
+        let token_assetid = Bytes32::from(*scenario.asset_id("token").map_err(Error::other)?);
+
+        info!(
+            verifier_id = %hex::encode(swapverifier_cid),
+            owner_predicate = %hex::encode(dummypwallet_b32addr.hash()),
+            solver_predicate = %hex::encode(dummypsolver_b32addr.hash()),
+            gas_sponsor = %hex::encode(sponsor.address()),
+            token_asset_id = %hex::encode(token_assetid),
+            "setup addresses",
+        );
+
+        Ok((
+            scenario.provider,
+            swapverifier_cid,
+            dummypwallet_bytecode,
+            dummypwallet_b32addr,
+            dummypsolver_bytecode,
+            dummypsolver_b32addr,
+            sponsor,
+            token_assetid,
+        ))
+    }
+
+}
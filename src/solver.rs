@@ -0,0 +1,270 @@
+use std::result::Result as StdResult;
+use thiserror::Error;
+use tracing::{info, instrument, warn, Span};
+use fuels::{
+    prelude::*,
+    types::{
+        Bytes32,
+        tx_status::TxStatus,
+        input::Input as SdkInput,
+        output::Output as SdkOutput,
+    },
+};
+
+use crate::{
+    config::Config,
+    helpers,
+    io::{utxo_input_utils, UTXOToolsError},
+    interfaces::{
+        generalized_swap_verifier_interface::{
+            build_recipient_output,
+            call_validate_solution,
+            ProtocolFee,
+            SwapVerifier,
+        },
+        owner_predicate,
+        predicate_validator::evm_address_to_bits256,
+    },
+    receipt_show::receipt_sniffer::{
+        TxPollError,
+        TxStatusResult,
+        wait_for_tx_success,
+    },
+    relay::{intent_book::{to_hex, SignedIntent}, RelayError},
+    sponsor::{GasSponsor, GasSponsorError},
+    store::decode_revert,
+    tx_policy::{SwapTxPolicy, TxPolicyError},
+};
+
+
+#[derive(Error, Debug)]
+pub enum SolverError {
+    #[error(transparent)]
+    Relay(#[from] RelayError),
+    #[error("The intent inputs are not unspent inputs of the sender")]
+    IntentInputsSpent,
+    #[error("The intent inputs are of more than one asset, the solver fills single asset intents")]
+    MixedInputAssets,
+    #[error("The intent input amounts overflow")]
+    AmountOverflow,
+    #[error("Solver has no {amount} of {asset_id} to deliver")]
+    NoLiquidity { asset_id: AssetId, amount: u64 },
+    #[error("The solver must pay the gas to deliver the base asset")]
+    GasPayerNotSolver,
+    #[error(transparent)]
+    GasSponsor(#[from] GasSponsorError),
+    #[error(transparent)]
+    TxPolicy(#[from] TxPolicyError),
+    #[error("Fill transaction failed: {0}")]
+    FillFailed(String),
+    #[error("Fill transaction reverted: {0}")]
+    Reverted(String),
+    #[error("Fill not settled after {attempts} attempts, last: {last_failure}")]
+    AttemptsExhausted { attempts: u32, last_failure: String },
+}
+
+
+/// fill a SignedIntent from the relay through the SwapVerifier, and wait for the fill tx.
+/// Returns the id of the fill tx once it succeeded.
+///
+/// a fill squeezed out of the pool or still pending at the timeout is sent again, up to
+/// resubmit.max_attempts, with the solver and gas inputs selected again and a higher tip. A
+/// revert stops at once with the decoded reason.
+#[instrument(name = "fill_intent", skip_all, fields(intent_id = tracing::field::Empty))]
+pub async fn fill_intent(
+    provider: &Provider,
+    config: &Config,
+    swapverifier_instance: &SwapVerifier<WalletUnlocked>,
+    protocol_fee: &ProtocolFee,
+    gas_sponsor: &mut GasSponsor,
+    solver: (&Vec<u8>, &Bech32Address),
+    intent: &SignedIntent,
+) -> StdResult<Bytes32, SolverError> {
+    Span::current().record("intent_id", tracing::field::display(to_hex(&intent.intent_id()?)));
+
+    let mut sent: Vec<Bytes32> = vec![];
+    let mut last_failure = String::new();
+    for attempt in 1..=config.resubmit.max_attempts {
+        let tip_bump = config.resubmit.tip_increment * u64::from(attempt - 1);
+        let tx_id = match submit_fill(provider, config, swapverifier_instance, protocol_fee, gas_sponsor, solver, intent, tip_bump).await {
+            Ok(tx_id) => tx_id,
+            // an earlier send that timed out may have landed since, and spent the intent inputs.
+            Err(SolverError::IntentInputsSpent) if !sent.is_empty() => return settled_send(provider, &sent).await,
+            Err(e) => return Err(e),
+        };
+        sent.push(tx_id);
+
+        match wait_for_tx_success(provider, &tx_id, &config.polling).await {
+            TxStatusResult::Ok { block, elapsed_time, .. } => {
+                info!(
+                    tx_id = %tx_id,
+                    attempt,
+                    block_height = block.height,
+                    elapsed_ms = elapsed_time.as_millis() as u64,
+                    "fill succeeded",
+                );
+                return Ok(tx_id);
+            }
+            TxStatusResult::Err { status: TxStatus::Revert { reason, revert_id, .. }, .. } => {
+                return Err(SolverError::Reverted(decode_revert(revert_id, &reason)));
+            }
+            TxStatusResult::Err { status: TxStatus::SqueezedOut { reason }, .. } => {
+                last_failure = format!("squeezed out: {}", reason);
+            }
+            TxStatusResult::Err { error: TxPollError::Timeout { duration, .. }, .. } => {
+                last_failure = format!("pending after {:?}", duration);
+            }
+            TxStatusResult::Err { error, .. } => return Err(SolverError::FillFailed(format!("{:?}", error))),
+        }
+        warn!(tx_id = %tx_id, attempt, reason = %last_failure, "fill not settled");
+    }
+
+    // the last send may still land, its inputs stay spendable by it.
+    Err(SolverError::AttemptsExhausted {
+        attempts: config.resubmit.max_attempts,
+        last_failure,
+    })
+}
+
+/// the earlier send that succeeded, once the intent inputs turned out spent.
+async fn settled_send(provider: &Provider, sent: &[Bytes32]) -> StdResult<Bytes32, SolverError> {
+    for tx_id in sent {
+        if let Ok(TxStatus::Success { .. }) = provider.tx_status(tx_id).await {
+            info!(tx_id = %tx_id, "earlier fill succeeded");
+            return Ok(*tx_id);
+        }
+    }
+    Err(SolverError::IntentInputsSpent)
+}
+
+/// send the fill of a SignedIntent: the solver delivers the full output amount to the recipient
+/// and receives the sender's inputs net of the protocol fee. The inputs must all be of one asset.
+///
+/// the sender inputs are spent by the sender's owner predicate with the intent as its data,
+/// and the tx has the tx_policy of the config, with tip_bump added to its tip. The maturity
+/// and expiration are checked against the intent expires_at first. Returns the id of the fill
+/// tx once the node accepted it, without waiting for its result.
+#[allow(clippy::too_many_arguments)]
+#[instrument(
+    name = "submit_fill",
+    skip_all,
+    fields(
+        solver = %solver.1,
+        asset_in = tracing::field::Empty,
+        amount_in = tracing::field::Empty,
+        asset_out = tracing::field::Empty,
+        amount_out = tracing::field::Empty,
+        tip_bump = tip_bump,
+        tx_id = tracing::field::Empty,
+    ),
+)]
+pub async fn submit_fill(
+    provider: &Provider,
+    config: &Config,
+    swapverifier_instance: &SwapVerifier<WalletUnlocked>,
+    protocol_fee: &ProtocolFee,
+    gas_sponsor: &mut GasSponsor,
+    solver: (&Vec<u8>, &Bech32Address),
+    intent: &SignedIntent,
+    tip_bump: u64,
+) -> StdResult<Bytes32, SolverError> {
+    let (solver_bytecode, solver_b32addr) = solver;
+
+    let gio_tx = intent.genio()?;
+    let sender_evm_address = intent.sender_address()?;
+    let compact_sig = intent.signature_bytes()?;
+    let utxos = intent.utxos()?;
+
+    // the proceeds and the change are built for one input asset.
+    let asset_in_id = AssetId::from(gio_tx.inputassets[0].0);
+    if utxos.iter().any(|(asset_id, _, _)| *asset_id != asset_in_id) {
+        return Err(SolverError::MixedInputAssets);
+    }
+    let total_amount_in = utxos
+        .iter()
+        .try_fold(0u64, |total, (_, _, amount)| total.checked_add(*amount))
+        .ok_or(SolverError::AmountOverflow)?;
+    let asset_out_id = AssetId::from(gio_tx.outputasset.0);
+    let amount_out = intent.io.outputamount;
+    Span::current()
+        .record("asset_in", tracing::field::display(asset_in_id))
+        .record("amount_in", total_amount_in)
+        .record("asset_out", tracing::field::display(asset_out_id))
+        .record("amount_out", amount_out);
+
+    if asset_out_id == AssetId::BASE && gas_sponsor.address() != Address::from(solver_b32addr) {
+        return Err(SolverError::GasPayerNotSolver);
+    }
+
+    let policy = SwapTxPolicy::for_intent(provider, &config.tx_policy, intent.expires_at).await?;
+    let policy = policy.with_tip(policy.tip + tip_bump);
+
+    let binding = owner_predicate::OwnerBinding {
+        verifier: ContractId::from(swapverifier_instance.contract_id()),
+        protocol_fee: protocol_fee.clone(),
+    };
+    let (owner_bytecode, owner_b32addr, _) = owner_predicate::get_owner_predicate_info(&config.artifacts, &binding, sender_evm_address);
+    let owner_predicate_data = owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(sender_evm_address),
+        compact_sig.clone(),
+        &gio_tx,
+    );
+    let sender_inputs = utxo_input_utils::find_inputs_by_utxo_ids(
+        provider,
+        &owner_bytecode,
+        Some(owner_predicate_data),
+        &utxos,
+    ).await.map_err(|e| match e {
+        UTXOToolsError::Provider(e) => SolverError::Relay(RelayError::Provider(e)),
+        _ => SolverError::IntentInputsSpent,
+    })?;
+
+    let (solver_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        provider,
+        solver_bytecode,
+        None,
+        asset_out_id,
+        amount_out,
+    ).await.map_err(|_| SolverError::NoLiquidity { asset_id: asset_out_id, amount: amount_out })?;
+
+    let amount_to_solver = helpers::swap_math::solver_net_amount_in(total_amount_in, protocol_fee.bps);
+
+    let mut tx_outputs: Vec<SdkOutput> = vec![
+        build_recipient_output(&gio_tx, amount_out),
+        SdkOutput::Coin {
+            to: Address::from(solver_b32addr),
+            amount: amount_to_solver,
+            asset_id: asset_in_id,
+        },
+    ];
+    // the base asset change output is added by the gas sponsor.
+    if asset_in_id != AssetId::BASE {
+        tx_outputs.push(SdkOutput::change(Address::from(&owner_b32addr), 0, asset_in_id));
+    }
+    if asset_out_id != AssetId::BASE {
+        tx_outputs.push(SdkOutput::change(Address::from(solver_b32addr), 0, asset_out_id));
+    }
+
+    let tx_inputs: Vec<SdkInput> = sender_inputs.into_iter().chain(solver_inputs).collect();
+
+    let stb = call_validate_solution(
+        swapverifier_instance.clone(),
+        sender_evm_address,
+        gio_tx,
+        compact_sig,
+        tx_inputs,
+        tx_outputs,
+        protocol_fee,
+        policy.tx_policies(),
+    ).await;
+    let stb = policy.apply(provider, stb, config.tx_policy.gas_margin_bps).await?;
+
+    let tx = gas_sponsor.sponsor(provider, stb).await?;
+    let tx_id = provider
+        .send_transaction(tx)
+        .await
+        .map_err(|e| SolverError::FillFailed(e.to_string()))?;
+    Span::current().record("tx_id", tracing::field::display(tx_id));
+    info!("fill submitted");
+    Ok(tx_id)
+}
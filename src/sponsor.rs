@@ -0,0 +1,200 @@
+use thiserror::Error;
+use fuels::{
+    prelude::*,
+    accounts::{
+        predicate::Predicate,
+        wallet::WalletUnlocked,
+        Account,
+    },
+    types::{
+        coin_type::CoinType,
+        coin_type_id::CoinTypeId,
+        input::Input as SdkInput,
+        output::Output as SdkOutput,
+        transaction::{ScriptTransaction, Transaction},
+        transaction_builders::ScriptTransactionBuilder,
+    },
+};
+
+
+#[derive(Error, Debug)]
+pub enum GasSponsorError {
+    #[error("Gas sponsor has no base asset input for {0}")]
+    NoGasInput(u64),
+    #[error("The base asset change output of the tx belongs to another address")]
+    BaseChangeTaken,
+    #[error("Gas sponsor budget exceeded: max_fee {max_fee}, remaining {remaining}")]
+    BudgetExceeded { max_fee: u64, remaining: u64 },
+    #[error("Transaction failed pre-flight validation: {0}")]
+    PreflightFailed(String),
+    #[error("Failed to build the sponsored transaction: {0}")]
+    Build(String),
+}
+
+impl GasSponsorError {
+
+    /// a predicate input of the tx did not verify, when the predicate gas is estimated at the
+    /// build or in the pre-flight, e.g. an intent predicate whose intent the tx does not fill.
+    pub fn is_predicate_rejection(&self) -> bool {
+        match self {
+            GasSponsorError::Build(reason) | GasSponsorError::PreflightFailed(reason) => {
+                reason.contains("PredicateVerificationFailed")
+            }
+            _ => false,
+        }
+    }
+}
+
+
+/// the account paying the gas, either a wallet (signs the tx) or a predicate (spends by its bytecode).
+#[derive(Clone, Debug)]
+pub enum SponsorAccount {
+    Wallet(WalletUnlocked),
+    Predicate(Predicate),
+}
+
+impl SponsorAccount {
+
+    pub fn address(&self) -> Address {
+        match self {
+            SponsorAccount::Wallet(wallet) => wallet.address().into(),
+            SponsorAccount::Predicate(predicate) => predicate.address().into(),
+        }
+    }
+
+    async fn get_gas_inputs(
+        &self,
+        gas_amount: u64,
+        excluded_coins: Vec<CoinTypeId>,
+    ) -> Result<Vec<SdkInput>> {
+        match self {
+            SponsorAccount::Wallet(wallet) => {
+                wallet.get_asset_inputs_for_amount(AssetId::BASE, gas_amount, Some(excluded_coins)).await
+            }
+            SponsorAccount::Predicate(predicate) => {
+                predicate.get_asset_inputs_for_amount(AssetId::BASE, gas_amount, Some(excluded_coins)).await
+            }
+        }
+    }
+}
+
+
+/// pays the gas of solution transactions on behalf of the solvers, up to a total budget.
+///
+/// the sponsor only adds its base asset inputs (and signs, for a wallet) once the transaction
+/// passes a dry run, and the transaction max_fee fits in what is left of the budget.
+pub struct GasSponsor {
+    pub account: SponsorAccount,
+    pub gas_amount: u64,
+    pub budget: u64,
+    pub spent: u64,
+}
+
+impl GasSponsor {
+
+    pub fn new(account: SponsorAccount, gas_amount: u64, budget: u64) -> Self {
+        GasSponsor {
+            account,
+            gas_amount,
+            budget,
+            spent: 0,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.account.address()
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.budget.saturating_sub(self.spent)
+    }
+
+    /// the base asset change Output back to the sponsor.
+    pub fn change_output(&self) -> SdkOutput {
+        SdkOutput::change(self.address(), 0, AssetId::BASE)
+    }
+
+    /// add the sponsor gas inputs and change output to the tx, build it and run the pre-flight
+    /// validation. The spent budget is only updated when the tx is returned.
+    ///
+    /// a tx can only have one base asset change output, which is the sponsor's. If the tx already
+    /// spends base asset inputs of the sponsor (e.g. a solver paying its own gas while delivering
+    /// the base asset), those coins are not selected again and the existing change output is kept.
+    pub async fn sponsor(
+        &mut self,
+        provider: &Provider,
+        mut stb: ScriptTransactionBuilder,
+    ) -> std::result::Result<ScriptTransaction, GasSponsorError> {
+
+        let mut has_change_output = false;
+        for output in stb.outputs() {
+            if let SdkOutput::Change { to, asset_id, .. } = output {
+                if *asset_id == AssetId::BASE {
+                    if *to != self.address() {
+                        return Err(GasSponsorError::BaseChangeTaken);
+                    }
+                    has_change_output = true;
+                }
+            }
+        }
+
+        let gas_inputs = self.account
+            .get_gas_inputs(self.gas_amount, get_spent_coins(stb.inputs()))
+            .await
+            .map_err(|_| GasSponsorError::NoGasInput(self.gas_amount))?;
+
+        for gas_input in gas_inputs {
+            stb.inputs_mut().push(gas_input);
+        }
+        if !has_change_output {
+            stb.outputs_mut().push(self.change_output());
+        }
+
+        if let SponsorAccount::Wallet(wallet) = &self.account {
+            stb.add_signer(wallet.clone())
+                .map_err(|e| GasSponsorError::Build(e.to_string()))?;
+        }
+
+        let tx = stb
+            .build(provider)
+            .await
+            .map_err(|e| GasSponsorError::Build(e.to_string()))?;
+
+        let max_fee = tx.max_fee().unwrap_or_default();
+        if max_fee > self.remaining() {
+            return Err(GasSponsorError::BudgetExceeded {
+                max_fee,
+                remaining: self.remaining(),
+            });
+        }
+
+        // pre-flight, the sponsor does not pay for transactions that would revert.
+        provider
+            .dry_run(tx.clone())
+            .await
+            .map_err(|e| GasSponsorError::PreflightFailed(e.to_string()))?
+            .check(None)
+            .map_err(|e| GasSponsorError::PreflightFailed(e.to_string()))?;
+
+        self.spent += max_fee;
+
+        Ok(tx)
+    }
+}
+
+
+/// the coins already spent by the inputs of a tx.
+fn get_spent_coins(inputs: &[SdkInput]) -> Vec<CoinTypeId> {
+    inputs
+        .iter()
+        .filter_map(|input| match input {
+            SdkInput::ResourcePredicate { resource, .. } | SdkInput::ResourceSigned { resource } => {
+                match resource {
+                    CoinType::Coin(coin) => Some(CoinTypeId::UtxoId(coin.utxo_id)),
+                    CoinType::Message(message) => Some(CoinTypeId::Nonce(message.nonce)),
+                }
+            },
+            SdkInput::Contract { .. } => None,
+        })
+        .collect()
+}
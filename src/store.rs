@@ -0,0 +1,456 @@
+use std::path::Path;
+use std::result::Result as StdResult;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+use fuels::{
+    prelude::*,
+    tx::Receipt,
+    types::{
+        Bytes32,
+        tx_status::TxStatus,
+    },
+};
+
+use crate::{
+    config::PollingConfig,
+    interfaces::generalized_swap_verifier_interface::verifier_revert_reason,
+    receipt_show::receipt_sniffer::{
+        TxPollError,
+        TxStatusResult,
+        wait_for_tx_success,
+        wait_for_txs,
+    },
+    relay::{
+        RelayError,
+        intent_book::{parse_hex, to_hex, SignedIntent},
+    },
+};
+
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("Intent store database error: {0}")]
+    Db(#[from] sled::Error),
+    #[error("Intent store encoding error: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error("Intent not found: {0}")]
+    NotFound(String),
+    #[error("Intent already stored: {0}")]
+    Duplicate(String),
+    #[error("Intent {intent_id} cannot go from {from} to {to}")]
+    InvalidTransition { intent_id: String, from: &'static str, to: &'static str },
+    #[error("Unknown intent state {0}, expected one of {}", IntentState::NAMES.join(", "))]
+    UnknownState(String),
+    #[error("Transaction {0} has no result yet")]
+    TxPending(String),
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error(transparent)]
+    Relay(#[from] RelayError),
+}
+
+
+/// the lifecycle of a stored intent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum IntentState {
+    /// signed, and not taken by a solver yet.
+    Pending,
+    /// a solver is building the fill.
+    Matched { solver: String },
+    Submitted { tx_id: String },
+    Settled { tx_id: String, receipts: Vec<Receipt> },
+    /// the fill reverted, with the verifier revert code decoded. The reverted tx spent the
+    /// intent inputs, so the intent can not be filled again.
+    Failed { tx_id: String, reason: String },
+    /// the block height passed expires_at before the intent settled.
+    Expired,
+    /// an intent input was spent by another tx than a fill.
+    Invalidated,
+}
+
+impl IntentState {
+
+    pub const NAMES: [&'static str; 7] = ["pending", "matched", "submitted", "settled", "failed", "expired", "invalidated"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntentState::Pending => "pending",
+            IntentState::Matched { .. } => "matched",
+            IntentState::Submitted { .. } => "submitted",
+            IntentState::Settled { .. } => "settled",
+            IntentState::Failed { .. } => "failed",
+            IntentState::Expired => "expired",
+            IntentState::Invalidated => "invalidated",
+        }
+    }
+
+    /// settled, failed, expired and invalidated intents do not change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            IntentState::Settled { .. } | IntentState::Failed { .. } | IntentState::Expired | IntentState::Invalidated
+        )
+    }
+
+    /// pending or matched, the intent inputs are still expected to be unspent.
+    pub fn is_open(&self) -> bool {
+        matches!(self, IntentState::Pending | IntentState::Matched { .. })
+    }
+
+    /// a fill squeezed out of the pool spent nothing, so the intent is pending again.
+    pub fn can_become(&self, next: &IntentState) -> bool {
+        use IntentState::*;
+        matches!(
+            (self, next),
+            (Pending, Matched { .. })
+                | (Matched { .. } | Submitted { .. }, Pending)
+                | (Pending | Matched { .. }, Submitted { .. })
+                | (Submitted { .. }, Settled { .. } | Failed { .. })
+                | (Pending | Matched { .. }, Expired | Invalidated)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredIntent {
+    pub intent_id: String,
+    pub state: IntentState,
+    pub intent: SignedIntent,
+    /// unix seconds of the last state change.
+    pub updated_at: u64,
+}
+
+
+/// the intents and their lifecycle state, by intent id, in an embedded sled database.
+/// Every change is flushed to disk, so the store survives restarts.
+#[derive(Clone, Debug)]
+pub struct IntentStore {
+    db: sled::Db,
+}
+
+impl IntentStore {
+
+    pub fn open(path: impl AsRef<Path>) -> StdResult<Self, StoreError> {
+        Ok(IntentStore { db: sled::open(path)? })
+    }
+
+    /// store a signed intent as pending.
+    pub fn insert(&self, intent: &SignedIntent) -> StdResult<StoredIntent, StoreError> {
+        let intent_id = intent.intent_id()?;
+        let stored = StoredIntent {
+            intent_id: to_hex(&intent_id),
+            state: IntentState::Pending,
+            intent: intent.clone(),
+            updated_at: unix_now(),
+        };
+        let swapped = self.db.compare_and_swap(
+            intent_id,
+            None as Option<&[u8]>,
+            Some(serde_json::to_vec(&stored)?),
+        )?;
+        if swapped.is_err() {
+            return Err(StoreError::Duplicate(stored.intent_id));
+        }
+        self.db.flush()?;
+        Ok(stored)
+    }
+
+    pub fn get(&self, intent_id: &[u8; 32]) -> StdResult<Option<StoredIntent>, StoreError> {
+        match self.db.get(intent_id)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn list(&self) -> StdResult<Vec<StoredIntent>, StoreError> {
+        self.db
+            .iter()
+            .values()
+            .map(|bytes| Ok(serde_json::from_slice(&bytes?)?))
+            .collect()
+    }
+
+    pub fn list_open(&self) -> StdResult<Vec<StoredIntent>, StoreError> {
+        Ok(self.list()?.into_iter().filter(|stored| stored.state.is_open()).collect())
+    }
+
+    /// the stored intents in the state with this name, e.g. "submitted".
+    pub fn list_by_state(&self, state_name: &str) -> StdResult<Vec<StoredIntent>, StoreError> {
+        if !IntentState::NAMES.contains(&state_name) {
+            return Err(StoreError::UnknownState(state_name.to_string()));
+        }
+        Ok(self.list()?.into_iter().filter(|stored| stored.state.name() == state_name).collect())
+    }
+
+    /// move the intent to the next state, when the lifecycle allows it.
+    pub fn transition(&self, intent_id: &[u8; 32], next: IntentState) -> StdResult<StoredIntent, StoreError> {
+        loop {
+            let current_bytes = self.db
+                .get(intent_id)?
+                .ok_or(StoreError::NotFound(to_hex(intent_id)))?;
+            let current: StoredIntent = serde_json::from_slice(&current_bytes)?;
+            let current_state = current.state.name();
+            if !current.state.can_become(&next) {
+                return Err(StoreError::InvalidTransition {
+                    intent_id: current.intent_id,
+                    from: current.state.name(),
+                    to: next.name(),
+                });
+            }
+            let updated = StoredIntent {
+                state: next.clone(),
+                updated_at: unix_now(),
+                ..current
+            };
+            let swapped = self.db.compare_and_swap(
+                intent_id,
+                Some(current_bytes),
+                Some(serde_json::to_vec(&updated)?),
+            )?;
+            // changed by another writer in between, check the transition again.
+            if swapped.is_ok() {
+                self.db.flush()?;
+                info!(intent_id = %updated.intent_id, from = current_state, to = next.name(), "intent state changed");
+                return Ok(updated);
+            }
+        }
+    }
+
+    pub fn mark_matched(&self, intent_id: &[u8; 32], solver: &Bech32Address) -> StdResult<StoredIntent, StoreError> {
+        self.transition(intent_id, IntentState::Matched { solver: solver.to_string() })
+    }
+
+    pub fn mark_submitted(&self, intent_id: &[u8; 32], tx_id: &Bytes32) -> StdResult<StoredIntent, StoreError> {
+        self.transition(intent_id, IntentState::Submitted { tx_id: to_hex(&**tx_id) })
+    }
+
+    /// settle or fail the submitted intent from the result of wait_for_tx_success. A tx that
+    /// was squeezed out puts the intent back to pending, one that has no result yet leaves
+    /// it submitted.
+    pub fn record_tx_result(
+        &self,
+        intent_id: &[u8; 32],
+        tx_id: &Bytes32,
+        result: &TxStatusResult,
+    ) -> StdResult<StoredIntent, StoreError> {
+        let tx_id = to_hex(&**tx_id);
+        let next = match result {
+            TxStatusResult::Ok { receipts, .. } => IntentState::Settled {
+                tx_id,
+                receipts: receipts.clone(),
+            },
+            TxStatusResult::Err { status: TxStatus::Revert { reason, revert_id, .. }, .. } => IntentState::Failed {
+                tx_id,
+                reason: decode_revert(*revert_id, reason),
+            },
+            TxStatusResult::Err { status: TxStatus::SqueezedOut { reason }, .. } => {
+                warn!(intent_id = %to_hex(intent_id), tx_id = %tx_id, reason = %reason, "fill squeezed out");
+                IntentState::Pending
+            }
+            TxStatusResult::Err { error: TxPollError::Timeout { .. }, .. } => {
+                return Err(StoreError::TxPending(tx_id));
+            }
+            TxStatusResult::Err { error, .. } => {
+                return Err(StoreError::Provider(format!("{:?}", error)));
+            }
+        };
+        self.transition(intent_id, next)
+    }
+
+    /// wait for the fill tx of a submitted intent, and record its result.
+    #[instrument(name = "track_fill", skip_all, fields(intent_id = %to_hex(intent_id)))]
+    pub async fn track_tx(
+        &self,
+        provider: &Provider,
+        intent_id: &[u8; 32],
+        polling: &PollingConfig,
+    ) -> StdResult<StoredIntent, StoreError> {
+        let stored = self.get(intent_id)?.ok_or(StoreError::NotFound(to_hex(intent_id)))?;
+        let IntentState::Submitted { tx_id } = &stored.state else {
+            return Err(StoreError::InvalidTransition {
+                intent_id: stored.intent_id,
+                from: stored.state.name(),
+                to: "settled",
+            });
+        };
+        let tx_id = Bytes32::from(parse_hex::<32>("tx_id", tx_id)?);
+        let result = wait_for_tx_success(provider, &tx_id, polling).await;
+        self.record_tx_result(intent_id, &tx_id, &result)
+    }
+
+    /// track every submitted intent, e.g. after a restart, waiting for their fill txs at once.
+    /// Returns the intents that settled, failed or are pending again.
+    pub async fn resume_submitted(
+        &self,
+        provider: &Provider,
+        polling: &PollingConfig,
+    ) -> StdResult<Vec<StoredIntent>, StoreError> {
+        let mut submitted = vec![];
+        for stored in self.list_by_state("submitted")? {
+            let IntentState::Submitted { tx_id } = &stored.state else {
+                continue;
+            };
+            let intent_id: [u8; 32] = parse_hex("intent_id", &stored.intent_id)?;
+            submitted.push((intent_id, Bytes32::from(parse_hex::<32>("tx_id", tx_id)?)));
+        }
+
+        let tx_ids: Vec<Bytes32> = submitted.iter().map(|(_, tx_id)| *tx_id).collect();
+        let results = wait_for_txs(provider, &tx_ids, polling).await;
+
+        let mut tracked = vec![];
+        for ((intent_id, tx_id), result) in submitted.iter().zip(results) {
+            match self.record_tx_result(intent_id, tx_id, &result) {
+                Ok(stored) => tracked.push(stored),
+                Err(StoreError::TxPending(tx_id)) => warn!(intent_id = %to_hex(intent_id), tx_id = %tx_id, "fill tx has no result yet"),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(tracked)
+    }
+}
+
+/// the verifier reason for its own revert codes, and the node's reason for the others.
+pub(crate) fn decode_revert(revert_id: u64, reason: &str) -> String {
+    format!("{} (revert {})", verifier_revert_reason(revert_id).unwrap_or(reason), revert_id)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use fuels::types::{errors::transaction::Reason, Bits256};
+    use crate::{
+        helpers::conversions::u64_to_bits256,
+        interfaces::generalized_swap_verifier_interface::populate_genio,
+        receipt_show::receipt_sniffer::TxBlock,
+    };
+
+    fn all_states() -> Vec<IntentState> {
+        vec![
+            IntentState::Pending,
+            IntentState::Matched { solver: "solver".to_string() },
+            IntentState::Submitted { tx_id: "00".to_string() },
+            IntentState::Settled { tx_id: "00".to_string(), receipts: vec![] },
+            IntentState::Failed { tx_id: "00".to_string(), reason: "revert".to_string() },
+            IntentState::Expired,
+            IntentState::Invalidated,
+        ]
+    }
+
+    fn state(name: &str) -> IntentState {
+        all_states().into_iter().find(|state| state.name() == name).unwrap()
+    }
+
+    #[test]
+    fn names_cover_every_state() {
+        let names: Vec<&str> = all_states().iter().map(IntentState::name).collect();
+        assert_eq!(names, IntentState::NAMES);
+    }
+
+    #[test]
+    fn lifecycle_transitions() {
+        let allowed = [
+            ("pending", "matched"),
+            ("pending", "submitted"),
+            ("pending", "expired"),
+            ("pending", "invalidated"),
+            ("matched", "pending"),
+            ("matched", "submitted"),
+            ("matched", "expired"),
+            ("matched", "invalidated"),
+            ("submitted", "pending"),
+            ("submitted", "settled"),
+            ("submitted", "failed"),
+        ];
+        for from in all_states() {
+            for to in all_states() {
+                let expected = allowed.contains(&(from.name(), to.name()));
+                assert_eq!(from.can_become(&to), expected, "{} -> {}", from.name(), to.name());
+            }
+        }
+    }
+
+    #[test]
+    fn final_states_do_not_change() {
+        for from in all_states().iter().filter(|state| state.is_final()) {
+            assert!(all_states().iter().all(|to| !from.can_become(to)), "{} changed", from.name());
+        }
+    }
+
+    #[test]
+    fn reverted_fill_is_not_open() {
+        assert!(state("pending").is_open());
+        assert!(state("matched").is_open());
+        assert!(!state("submitted").is_open());
+        assert!(!state("failed").is_open());
+        assert!(state("failed").is_final());
+    }
+
+    /// an intent of one input, with a zero signature as the store does not check it.
+    fn insert_intent(store: &IntentStore) -> [u8; 32] {
+        let zero = Bits256::zeroed();
+        let gio_data = populate_genio(
+            [Bits256([4u8; 32]), zero, zero, zero, zero],
+            [Bits256([0x11; 32]), zero, zero, zero, zero],
+            [u64_to_bits256(1_520_000_000), zero, zero, zero, zero],
+            Bits256([9u8; 32]),
+            u64_to_bits256(2_000_000_000),
+            u64_to_bits256(250),
+            Bits256([7u8; 32]),
+        ).unwrap();
+        let intent = SignedIntent::new([1u8; 20], &[0u8; 64], &gio_data);
+        store.insert(&intent).unwrap();
+        intent.intent_id().unwrap()
+    }
+
+    /// the result wait_for_tx_success gives for a fill the verifier reverted with 6663 is a
+    /// final failure with the decoded reason.
+    #[test]
+    fn reverted_fill_is_recorded_as_failed() {
+        let store = IntentStore { db: sled::Config::new().temporary(true).open().unwrap() };
+        let intent_id = insert_intent(&store);
+        let tx_id = Bytes32::from([7u8; 32]);
+        store.mark_submitted(&intent_id, &tx_id).unwrap();
+
+        let reverted = TxStatusResult::Err {
+            error: TxPollError::TransactionError(Error::Transaction(Reason::Reverted {
+                reason: "Revert(6663)".to_string(),
+                revert_id: 6663,
+                receipts: vec![],
+            })),
+            status: TxStatus::Revert {
+                reason: "Revert(6663)".to_string(),
+                revert_id: 6663,
+                receipts: vec![],
+            },
+            block: Some(TxBlock { height: 12, timestamp: 0 }),
+            elapsed_time: Duration::ZERO,
+        };
+        let failed = store.record_tx_result(&intent_id, &tx_id, &reverted).unwrap();
+        let IntentState::Failed { reason, .. } = &failed.state else {
+            panic!("expected the intent to fail, got {:?}", failed.state);
+        };
+        assert!(reason.contains("outputs do not match the intent"));
+
+        // the reverted fill spent the inputs, the intent is not filled again.
+        assert!(matches!(
+            store.mark_matched(&intent_id, &Bech32Address::from(Address::zeroed())),
+            Err(StoreError::InvalidTransition { .. })
+        ));
+        assert!(store.list_open().unwrap().is_empty());
+    }
+
+    #[test]
+    fn revert_reasons() {
+        assert_eq!(decode_revert(6668, "Revert(6668)"), "protocol fee not paid (revert 6668)");
+        assert_eq!(decode_revert(42, "Revert(42)"), "Revert(42) (revert 42)");
+    }
+}
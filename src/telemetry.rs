@@ -0,0 +1,36 @@
+use std::result::Result as StdResult;
+use thiserror::Error;
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{LogFormat, LoggingConfig};
+
+
+#[derive(Error, Debug)]
+pub enum TelemetryError {
+    #[error("Invalid logging.level: {0}")]
+    Filter(String),
+    #[error("Tracing subscriber not installed: {0}")]
+    Init(String),
+}
+
+
+/// install the global tracing subscriber of the logging config. The events go to stderr, so
+/// stdout only carries the command output, e.g. the --json result.
+///
+/// the spans carry the intent ids, tx ids, amounts and asset ids. Keys never go in a span or
+/// an event, the signing spans skip their key arguments.
+pub fn init_tracing(logging: &LoggingConfig) -> StdResult<(), TelemetryError> {
+    let filter = EnvFilter::try_new(&logging.level).map_err(|e| TelemetryError::Filter(e.to_string()))?;
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match logging.format {
+        LogFormat::Human => builder.try_init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init(),
+    }
+    .map_err(|e| TelemetryError::Init(e.to_string()))
+}
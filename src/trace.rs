@@ -0,0 +1,525 @@
+use std::fmt;
+use std::result::Result as StdResult;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use fuels::{
+    core::codec::LogDecoder,
+    prelude::*,
+    tx::Receipt,
+    types::{AsciiString, Bits256},
+};
+
+use crate::{
+    helpers::conversions::bits256_to_u64,
+    interfaces::generalized_swap_verifier_interface::{GenIO, Intent, SwapVerifier},
+    relay::intent_book::{to_hex, GenIOJson, SignedIntent},
+};
+
+
+#[derive(Error, Debug)]
+pub enum TraceError {
+    #[error("Log decode error: {0}")]
+    Decode(String),
+    #[error("Log {index} of the SwapVerifier is none of the types it logs")]
+    UnknownLog { index: usize },
+}
+
+
+/// a SwapVerifier log, decoded by its log id into the type the contract logged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum VerifierLog {
+    /// the intent of validate_solution and intent_messenger.
+    Intent(SignedIntent),
+    /// the GenIO of verify_intent_sender.
+    GenIo(GenIOJson),
+    /// the input and output counts, and the failure codes.
+    U64(u64),
+    /// the signature halves, digest and signer of verify_intent_sender.
+    B256(String),
+    /// the asset of each change output.
+    AssetId(String),
+    /// the check labels, and the b256 values as hex without 0x.
+    String(String),
+    /// the labels before a failure code.
+    Str(String),
+}
+
+/// the SwapVerifier logs of a tx, in receipt order.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerifierTrace {
+    pub logs: Vec<VerifierLog>,
+}
+
+impl VerifierTrace {
+
+    /// decode the logs of the SwapVerifier instance in the receipts, other contracts' logs are
+    /// skipped. Works on the receipts of a reverted tx too, the logs before the revert are kept.
+    pub fn decode(
+        swapverifier_instance: &SwapVerifier<WalletUnlocked>,
+        receipts: &[Receipt],
+    ) -> StdResult<Self, TraceError> {
+        let contract_id = ContractId::from(swapverifier_instance.contract_id());
+        let decoder = swapverifier_instance.log_decoder();
+
+        let mut logs = vec![];
+        for (index, receipt) in receipts.iter().enumerate() {
+            let Receipt::LogData { id, .. } = receipt else {
+                continue;
+            };
+            if *id != contract_id {
+                continue;
+            }
+            let log = decode_log(&decoder, receipt)?.ok_or(TraceError::UnknownLog { index })?;
+            logs.push(log);
+        }
+        Ok(VerifierTrace { logs })
+    }
+
+    /// the validate_solution checks, rebuilt from the labels and the values that follow them.
+    pub fn report(&self) -> VerifierReport {
+        let mut report = VerifierReport::default();
+        let mut section = Section::None;
+
+        for log in &self.logs {
+            match log {
+                VerifierLog::Intent(intent) => report.intent = Some(intent.clone()),
+                VerifierLog::GenIo(_) | VerifierLog::B256(_) => {}
+                VerifierLog::AssetId(asset_id) => report.change_assets.push(asset_id.clone()),
+                VerifierLog::U64(value) => match section {
+                    Section::InputErrorCode => {
+                        report.failure = Some(format!("input processing failed with error code {}", value));
+                    }
+                    Section::ReconstructErrorCode => {
+                        report.failure = Some(format!("intent reconstruction failed with error code {}", value));
+                    }
+                    _ if report.input_count.is_none() => report.input_count = Some(*value),
+                    _ if report.output_count.is_none() => report.output_count = Some(*value),
+                    _ => {}
+                },
+                VerifierLog::Str(label) => {
+                    if label.starts_with("Input processing failed") {
+                        section = Section::InputErrorCode;
+                    } else if label.starts_with("Output processing failed") {
+                        report.failure = Some("output processing failed".to_string());
+                    }
+                }
+                VerifierLog::String(value) if is_b256_hex(value) => {
+                    report.on_value(&section, format!("0x{}", value));
+                    section = section.after_value();
+                }
+                VerifierLog::String(label) => {
+                    section = report.on_label(label);
+                }
+            }
+        }
+        report
+    }
+}
+
+/// which value the next logged b256 is, from the label before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Section {
+    None,
+    OrderedUtxos,
+    MatchAsset,
+    Sender,
+    ReconInputAssets,
+    ReconInputAmounts,
+    ReconUtxos,
+    ReconOutputAsset,
+    ReconOutputAmount,
+    ReconTolerance,
+    RecoveredSigner,
+    InputErrorCode,
+    ReconstructErrorCode,
+}
+
+impl Section {
+
+    /// the single value labels take one value, the lists take all that follow.
+    fn after_value(self) -> Section {
+        match self {
+            Section::MatchAsset
+            | Section::Sender
+            | Section::ReconOutputAsset
+            | Section::ReconOutputAmount
+            | Section::ReconTolerance
+            | Section::RecoveredSigner => Section::None,
+            list => list,
+        }
+    }
+}
+
+/// the outcome of each validate_solution check, None when the trace stops before it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerifierChecks {
+    pub inputs: Option<bool>,
+    pub outputs: Option<bool>,
+    pub utxos: Option<bool>,
+    pub change: Option<bool>,
+    pub recipient: Option<bool>,
+    pub fee: Option<bool>,
+}
+
+/// the intent as the SwapVerifier rebuilt it from the tx, the signature is checked over it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReconstructedIntent {
+    pub input_assets: Vec<String>,
+    pub input_amounts: Vec<u64>,
+    pub input_utxos: Vec<String>,
+    pub output_asset: Option<String>,
+    pub output_amount: Option<u64>,
+    pub tolerance: Option<u64>,
+}
+
+/// what validate_solution saw and decided, from its logs.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerifierReport {
+    pub intent: Option<SignedIntent>,
+    pub input_count: Option<u64>,
+    pub output_count: Option<u64>,
+    pub change_assets: Vec<String>,
+    /// the sender inputs in the order of the intent utxo ids.
+    pub ordered_utxos: Vec<String>,
+    pub checks: VerifierChecks,
+    /// the intent input asset the inputs matched.
+    pub match_asset: Option<String>,
+    /// the owner of the first intent utxo.
+    pub sender: Option<String>,
+    pub reconstructed: Option<ReconstructedIntent>,
+    /// the EVM address the signature recovers to over the reconstructed intent, left padded.
+    pub recovered_signer: Option<String>,
+    pub failure: Option<String>,
+}
+
+impl VerifierReport {
+
+    /// every check passed, and the signature recovered to the intent sender.
+    pub fn passed(&self) -> bool {
+        let checks = [
+            self.checks.inputs,
+            self.checks.outputs,
+            self.checks.utxos,
+            self.checks.change,
+            self.checks.recipient,
+            self.checks.fee,
+        ];
+        let signed_by_sender = match (&self.recovered_signer, &self.intent) {
+            (Some(signer), Some(intent)) => signer.ends_with(intent.sender.trim_start_matches("0x")),
+            _ => false,
+        };
+        self.failure.is_none() && checks.iter().all(|check| *check == Some(true)) && signed_by_sender
+    }
+
+    fn on_label(&mut self, label: &str) -> Section {
+        if let Some((check, result)) = label.split_once(':') {
+            let passed = match result.trim() {
+                "OK" => Some(true),
+                "FAIL" => Some(false),
+                _ => None,
+            };
+            if let Some(passed) = passed {
+                match check.trim() {
+                    "Inputs" => self.checks.inputs = Some(passed),
+                    "Outputs" => self.checks.outputs = Some(passed),
+                    // the match asset is logged right after the utxo check.
+                    "UTXOs" => {
+                        self.checks.utxos = Some(passed);
+                        return Section::MatchAsset;
+                    }
+                    "Change" => self.checks.change = Some(passed),
+                    "Recipient" => self.checks.recipient = Some(passed),
+                    "Fee" => self.checks.fee = Some(passed),
+                    _ => {}
+                }
+                return Section::None;
+            }
+        }
+        match label {
+            "Ordered UTXOs:" => Section::OrderedUtxos,
+            "Sender:" => Section::Sender,
+            "Reconstructed Intent:" => {
+                self.reconstructed = Some(ReconstructedIntent::default());
+                Section::None
+            }
+            "Input Assets:" => Section::ReconInputAssets,
+            "Input Amounts:" => Section::ReconInputAmounts,
+            "UTXOs:" => Section::ReconUtxos,
+            "Output Asset:" => Section::ReconOutputAsset,
+            "Output Amount:" => Section::ReconOutputAmount,
+            "Tolerance (from intent tx data):" => Section::ReconTolerance,
+            "Recovered Signer:" => Section::RecoveredSigner,
+            "Failed to reconstruct intent" => {
+                self.failure = Some("intent reconstruction failed".to_string());
+                Section::None
+            }
+            "Error code:" => Section::ReconstructErrorCode,
+            _ => Section::None,
+        }
+    }
+
+    fn on_value(&mut self, section: &Section, value: String) {
+        let reconstructed = self.reconstructed.get_or_insert_with(ReconstructedIntent::default);
+        match section {
+            Section::OrderedUtxos => self.ordered_utxos.push(value),
+            Section::MatchAsset => self.match_asset = Some(value),
+            Section::Sender => self.sender = Some(value),
+            Section::ReconInputAssets => reconstructed.input_assets.push(value),
+            Section::ReconInputAmounts => reconstructed.input_amounts.push(hex_to_u64(&value)),
+            Section::ReconUtxos => reconstructed.input_utxos.push(value),
+            Section::ReconOutputAsset => reconstructed.output_asset = Some(value),
+            Section::ReconOutputAmount => reconstructed.output_amount = Some(hex_to_u64(&value)),
+            Section::ReconTolerance => reconstructed.tolerance = Some(hex_to_u64(&value)),
+            Section::RecoveredSigner => self.recovered_signer = Some(value),
+            Section::None | Section::InputErrorCode | Section::ReconstructErrorCode => {}
+        }
+        // only the reconstruction sections fill the reconstructed intent.
+        if self.reconstructed == Some(ReconstructedIntent::default()) {
+            self.reconstructed = None;
+        }
+    }
+}
+
+impl fmt::Display for VerifierReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn check(result: Option<bool>) -> &'static str {
+            match result {
+                Some(true) => "OK",
+                Some(false) => "FAIL",
+                None => "-",
+            }
+        }
+        fn value<T: fmt::Display>(value: &Option<T>) -> String {
+            value.as_ref().map(|v| v.to_string()).unwrap_or("-".to_string())
+        }
+
+        if let Some(intent) = &self.intent {
+            writeln!(f, "intent sender  : {}", intent.sender)?;
+            writeln!(f, "intent utxos   : {}", intent.io.inpututxoids.join(", "))?;
+        }
+        writeln!(f, "inputs/outputs : {}/{}", value(&self.input_count), value(&self.output_count))?;
+        writeln!(f, "change assets  : {}", self.change_assets.join(", "))?;
+        writeln!(f, "ordered utxos  : {}", self.ordered_utxos.join(", "))?;
+        writeln!(
+            f,
+            "checks         : inputs {}, outputs {}, utxos {}, change {}, recipient {}, fee {}",
+            check(self.checks.inputs),
+            check(self.checks.outputs),
+            check(self.checks.utxos),
+            check(self.checks.change),
+            check(self.checks.recipient),
+            check(self.checks.fee),
+        )?;
+        writeln!(f, "match asset    : {}", value(&self.match_asset))?;
+        writeln!(f, "sender         : {}", value(&self.sender))?;
+        if let Some(reconstructed) = &self.reconstructed {
+            writeln!(f, "reconstructed  : {:?} of {:?} from {:?}", reconstructed.input_amounts, reconstructed.input_assets, reconstructed.input_utxos)?;
+            writeln!(
+                f,
+                "                 for {} of {}, tolerance {}",
+                value(&reconstructed.output_amount),
+                value(&reconstructed.output_asset),
+                value(&reconstructed.tolerance),
+            )?;
+        }
+        writeln!(f, "signer         : {}", value(&self.recovered_signer))?;
+        if let Some(failure) = &self.failure {
+            writeln!(f, "failure        : {}", failure)?;
+        }
+        write!(f, "passed         : {}", self.passed())
+    }
+}
+
+/// the log as the first type its log id decodes to, None for a log id of no SwapVerifier type.
+fn decode_log(decoder: &LogDecoder, receipt: &Receipt) -> StdResult<Option<VerifierLog>, TraceError> {
+    let receipts = std::slice::from_ref(receipt);
+    let decode_err = |e: fuels::types::errors::Error| TraceError::Decode(e.to_string());
+
+    if let Some(intent) = decoder.decode_logs_with_type::<Intent>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::Intent(SignedIntent::from_intent(&intent))));
+    }
+    if let Some(gio_data) = decoder.decode_logs_with_type::<GenIO>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::GenIo(SignedIntent::new([0u8; 20], &[], &gio_data).io)));
+    }
+    if let Some(value) = decoder.decode_logs_with_type::<u64>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::U64(value)));
+    }
+    if let Some(value) = decoder.decode_logs_with_type::<Bits256>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::B256(to_hex(&value.0))));
+    }
+    if let Some(asset_id) = decoder.decode_logs_with_type::<AssetId>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::AssetId(to_hex(&*asset_id))));
+    }
+    if let Some(value) = decoder.decode_logs_with_type::<String>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::String(value)));
+    }
+    if let Some(value) = decoder.decode_logs_with_type::<AsciiString>(receipts).map_err(decode_err)?.pop() {
+        return Ok(Some(VerifierLog::Str(value.to_string())));
+    }
+    Ok(None)
+}
+
+/// b256_to_hex logs 64 hex characters, without 0x.
+fn is_b256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn hex_to_u64(value: &str) -> u64 {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(value.trim_start_matches("0x"), &mut bytes).ok();
+    bits256_to_u64(Bits256(bytes))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::conversions::u64_to_bits256,
+        interfaces::generalized_swap_verifier_interface::populate_genio,
+    };
+
+    const SENDER: [u8; 20] = [0xaa; 20];
+
+    fn b256(byte: u8) -> VerifierLog {
+        VerifierLog::String(hex::encode([byte; 32]))
+    }
+
+    fn amount(value: u64) -> VerifierLog {
+        VerifierLog::String(hex::encode(u64_to_bits256(value).0))
+    }
+
+    fn label(label: &str) -> VerifierLog {
+        VerifierLog::String(label.to_string())
+    }
+
+    fn signer(evm_address: [u8; 20]) -> VerifierLog {
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&evm_address);
+        VerifierLog::String(hex::encode(padded))
+    }
+
+    fn intent() -> VerifierLog {
+        let gio_data = populate_genio(
+            [Bits256([3u8; 32]); 5],
+            [Bits256([1u8; 32]); 5],
+            [u64_to_bits256(1_000), Bits256::zeroed(), Bits256::zeroed(), Bits256::zeroed(), Bits256::zeroed()],
+            Bits256([9u8; 32]),
+            u64_to_bits256(2_000),
+            u64_to_bits256(250),
+            Bits256([7u8; 32]),
+        ).unwrap();
+        VerifierLog::Intent(SignedIntent::new(SENDER, &[0u8; 64], &gio_data))
+    }
+
+    /// the logs of a validate_solution that passes, with the check results given.
+    fn logs(fee_check: &str, recovered_signer: [u8; 20]) -> Vec<VerifierLog> {
+        vec![
+            intent(),
+            VerifierLog::U64(2),
+            VerifierLog::U64(3),
+            VerifierLog::AssetId(to_hex(&[3u8; 32])),
+            label("Inputs: OK"),
+            label("Outputs: OK"),
+            label("Ordered UTXOs:"),
+            b256(1),
+            b256(2),
+            label("UTXOs: OK"),
+            b256(3),
+            label("Change: OK"),
+            label("Recipient: OK"),
+            label(fee_check),
+            label("Sender:"),
+            b256(4),
+            label("Reconstructed Intent:"),
+            label("Input Assets:"),
+            b256(3),
+            label("Input Amounts:"),
+            amount(1_000),
+            label("UTXOs:"),
+            b256(1),
+            label("Output Asset:"),
+            b256(9),
+            label("Output Amount:"),
+            amount(2_000),
+            label("Tolerance (from intent tx data):"),
+            amount(250),
+            label("Recovered Signer:"),
+            signer(recovered_signer),
+        ]
+    }
+
+    fn hex_of(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 32]))
+    }
+
+    #[test]
+    fn report_of_a_passing_validation() {
+        let report = VerifierTrace { logs: logs("Fee: OK", SENDER) }.report();
+
+        assert_eq!(report.input_count, Some(2));
+        assert_eq!(report.output_count, Some(3));
+        assert_eq!(report.ordered_utxos, vec![hex_of(1), hex_of(2)]);
+        assert_eq!(report.match_asset, Some(hex_of(3)));
+        assert_eq!(report.sender, Some(hex_of(4)));
+        assert_eq!(
+            report.reconstructed,
+            Some(ReconstructedIntent {
+                input_assets: vec![hex_of(3)],
+                input_amounts: vec![1_000],
+                input_utxos: vec![hex_of(1)],
+                output_asset: Some(hex_of(9)),
+                output_amount: Some(2_000),
+                tolerance: Some(250),
+            }),
+        );
+        assert_eq!(
+            report.checks,
+            VerifierChecks {
+                inputs: Some(true),
+                outputs: Some(true),
+                utxos: Some(true),
+                change: Some(true),
+                recipient: Some(true),
+                fee: Some(true),
+            },
+        );
+        assert_eq!(report.failure, None);
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn failed_check_does_not_pass() {
+        let report = VerifierTrace { logs: logs("Fee: FAIL", SENDER) }.report();
+        assert_eq!(report.checks.fee, Some(false));
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn signer_other_than_the_sender_does_not_pass() {
+        let report = VerifierTrace { logs: logs("Fee: OK", [0xbb; 20]) }.report();
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn failure_codes_follow_their_labels() {
+        let report = VerifierTrace {
+            logs: vec![
+                intent(),
+                VerifierLog::U64(2),
+                VerifierLog::U64(3),
+                VerifierLog::Str("Input processing failed".to_string()),
+                VerifierLog::U64(4),
+            ],
+        }.report();
+        assert_eq!(report.failure, Some("input processing failed with error code 4".to_string()));
+        assert_eq!(report.checks.inputs, None);
+        assert!(!report.passed());
+
+        let report = VerifierTrace {
+            logs: vec![label("Failed to reconstruct intent"), label("Error code:"), VerifierLog::U64(5)],
+        }.report();
+        assert_eq!(report.failure, Some("intent reconstruction failed with error code 5".to_string()));
+    }
+}
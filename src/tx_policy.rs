@@ -0,0 +1,191 @@
+use std::result::Result as StdResult;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use fuels::{
+    prelude::*,
+    tx::{Receipt, ScriptExecutionResult},
+    types::{
+        transaction::TxPolicies,
+        transaction_builders::ScriptTransactionBuilder,
+    },
+};
+
+use crate::{
+    config::TxPolicyConfig,
+    store::decode_revert,
+};
+
+
+#[derive(Error, Debug)]
+pub enum TxPolicyError {
+    #[error("Provider error: {0}")]
+    Provider(String),
+    #[error("Gas estimate dry run failed: {0}")]
+    Estimate(String),
+    #[error("Gas estimate dry run reverted: {0}")]
+    Reverted(String),
+    #[error("The intent expired at block {deadline}, the chain is at block {block_height}")]
+    IntentExpired { deadline: u32, block_height: u32 },
+    #[error("Maturity {maturity} is not before the intent deadline {deadline}")]
+    MaturityPastDeadline { maturity: u32, deadline: u32 },
+}
+
+
+/// the policies of a swap tx, at the block height they were made for.
+///
+/// fuel-tx 0.58 has no expiration policy, so the expiration is not part of the tx. It is the
+/// last block the tx may be sent at, checked again before every send.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SwapTxPolicy {
+    pub tip: u64,
+    /// None to size it from a dry run, see estimate_script_gas_limit.
+    pub script_gas_limit: Option<u64>,
+    /// the first block the tx can be included in.
+    pub maturity: Option<u32>,
+    pub expiration: Option<u32>,
+}
+
+impl SwapTxPolicy {
+
+    /// the policy of the config at block_height, for an intent valid until deadline. The
+    /// expiration is capped at the deadline, and the maturity must come before it.
+    pub fn new(
+        config: &TxPolicyConfig,
+        block_height: u32,
+        deadline: Option<u32>,
+    ) -> StdResult<Self, TxPolicyError> {
+        let maturity = config.maturity_blocks.map(|blocks| block_height + blocks);
+        let expiration = match (config.expiration_blocks.map(|blocks| block_height + blocks), deadline) {
+            (Some(expiration), Some(deadline)) => Some(expiration.min(deadline)),
+            (expiration, deadline) => expiration.or(deadline),
+        };
+        let policy = SwapTxPolicy {
+            tip: config.tip,
+            script_gas_limit: config.script_gas_limit,
+            maturity,
+            expiration,
+        };
+        policy.check(block_height, deadline)?;
+        Ok(policy)
+    }
+
+    /// the policy of the config at the current height of the chain.
+    pub async fn for_intent(
+        provider: &Provider,
+        config: &TxPolicyConfig,
+        deadline: Option<u32>,
+    ) -> StdResult<Self, TxPolicyError> {
+        let block_height = provider
+            .latest_block_height()
+            .await
+            .map_err(|e| TxPolicyError::Provider(e.to_string()))?;
+        SwapTxPolicy::new(config, block_height, deadline)
+    }
+
+    /// the tx can still land before the intent deadline and its own expiration.
+    pub fn check(&self, block_height: u32, deadline: Option<u32>) -> StdResult<(), TxPolicyError> {
+        if let Some(deadline) = deadline {
+            if block_height >= deadline {
+                return Err(TxPolicyError::IntentExpired { deadline, block_height });
+            }
+            if let Some(maturity) = self.maturity.filter(|maturity| *maturity >= deadline) {
+                return Err(TxPolicyError::MaturityPastDeadline { maturity, deadline });
+            }
+        }
+        if let Some(expiration) = self.expiration.filter(|expiration| block_height >= *expiration) {
+            return Err(TxPolicyError::IntentExpired { deadline: expiration, block_height });
+        }
+        Ok(())
+    }
+
+    pub fn with_tip(mut self, tip: u64) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn with_script_gas_limit(mut self, script_gas_limit: u64) -> Self {
+        self.script_gas_limit = Some(script_gas_limit);
+        self
+    }
+
+    pub fn tx_policies(&self) -> TxPolicies {
+        let mut tx_policies = TxPolicies::default().with_tip(self.tip);
+        if let Some(script_gas_limit) = self.script_gas_limit {
+            tx_policies = tx_policies.with_script_gas_limit(script_gas_limit);
+        }
+        if let Some(maturity) = self.maturity {
+            tx_policies = tx_policies.with_maturity(maturity);
+        }
+        tx_policies
+    }
+
+    /// set the policies on the tx, with a script gas limit sized by a dry run when none is set.
+    pub async fn apply(
+        &self,
+        provider: &Provider,
+        stb: ScriptTransactionBuilder,
+        gas_margin_bps: u64,
+    ) -> StdResult<ScriptTransactionBuilder, TxPolicyError> {
+        let policy = match self.script_gas_limit {
+            Some(_) => *self,
+            None => self.with_script_gas_limit(estimate_script_gas_limit(provider, &stb, gas_margin_bps).await?),
+        };
+        Ok(stb.with_tx_policies(policy.tx_policies()))
+    }
+}
+
+
+/// the gas the script of the tx uses in a dry run, plus gas_margin_bps of it. A dry run that
+/// does not succeed is an error, with the SwapVerifier reason of its revert code.
+///
+/// the dry run is at the maximum gas per tx and without fees, so it runs before the gas
+/// sponsor adds its inputs.
+#[instrument(name = "estimate_gas", skip_all, fields(gas_margin_bps = gas_margin_bps))]
+pub async fn estimate_script_gas_limit(
+    provider: &Provider,
+    stb: &ScriptTransactionBuilder,
+    gas_margin_bps: u64,
+) -> StdResult<u64, TxPolicyError> {
+    let max_gas_per_tx = provider.consensus_parameters().tx_params().max_gas_per_tx();
+    let tx = stb
+        .clone()
+        .with_tx_policies(
+            TxPolicies::default()
+                .with_script_gas_limit(max_gas_per_tx)
+                .with_max_fee(0),
+        )
+        .build(provider)
+        .await
+        .map_err(|e| TxPolicyError::Estimate(e.to_string()))?;
+
+    let receipts = provider
+        .dry_run_opt(tx, false, Some(0))
+        .await
+        .map_err(|e| TxPolicyError::Estimate(e.to_string()))?
+        .take_receipts();
+    let (result, gas_used) = receipts
+        .iter()
+        .find_map(|receipt| match receipt {
+            Receipt::ScriptResult { result, gas_used } => Some((result.clone(), *gas_used)),
+            _ => None,
+        })
+        .ok_or(TxPolicyError::Estimate("no script result".to_string()))?;
+
+    // the gas of a fill that reverts says nothing about the gas of one that succeeds.
+    if !matches!(result, ScriptExecutionResult::Success) {
+        let reason = format!("{:?}", result);
+        let revert_id = receipts.iter().find_map(|receipt| match receipt {
+            Receipt::Revert { ra, .. } => Some(*ra),
+            _ => None,
+        });
+        return Err(TxPolicyError::Reverted(match revert_id {
+            Some(revert_id) => decode_revert(revert_id, &reason),
+            None => reason,
+        }));
+    }
+
+    let script_gas_limit = (gas_used + gas_used * gas_margin_bps / 10_000).min(max_gas_per_tx);
+    debug!(gas_used, script_gas_limit, "script gas limit");
+    Ok(script_gas_limit)
+}
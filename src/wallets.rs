@@ -1,15 +1,17 @@
 use std::str::FromStr;
+use std::sync::OnceLock;
 use ethers::types::Address as EthAddress;
 use ethers_signers::{LocalWallet, Signer as EthSigner};
 
 use fuels::prelude::*;
 use fuel_crypto::SecretKey;
 
+use crate::config::Config;
 use crate::consts::*;
 
 
-pub fn get_evm_addr() -> EthAddress {
-    let eth_wallet = create_account(SENDER_EVM_SK);
+pub fn get_evm_addr(evm_sk: &str) -> EthAddress {
+    let eth_wallet = create_account(evm_sk);
     eth_wallet.address()
 }
 
@@ -22,11 +24,36 @@ fn create_account(private_key: &str) -> LocalWallet {
 }
 
 
-pub fn get_fuel_wallet(provider: &Provider) -> WalletUnlocked {
-    let fuel_sk = FUEL_EMPTY_WALLET_SK.to_string();
-    let wallet = WalletUnlocked::new_from_private_key(
-        SecretKey::from_str(&fuel_sk).unwrap(),
-        Some(provider.clone())
-    );
-    wallet
+/// the EVM key of the scenario sender, new for every run so that no key is kept in the source.
+pub fn test_sender_evm_sk() -> &'static str {
+    static SENDER_EVM_SK: OnceLock<String> = OnceLock::new();
+    SENDER_EVM_SK.get_or_init(random_evm_sk)
+}
+
+/// a second scenario sender, each owner predicate is bound to the EVM address that signs its intents.
+pub fn test_sender_2_evm_sk() -> &'static str {
+    static SENDER_2_EVM_SK: OnceLock<String> = OnceLock::new();
+    SENDER_2_EVM_SK.get_or_init(random_evm_sk)
+}
+
+/// a random secp256k1 key as hex, retried on the rare bytes that are not a valid scalar.
+fn random_evm_sk() -> String {
+    loop {
+        if let Ok(wallet) = LocalWallet::from_bytes(&rand::random::<[u8; 32]>()) {
+            return hex::encode(wallet.signer().to_bytes());
+        }
+    }
+}
+
+
+/// the fuel wallet of FUEL_WALLET_SK, or a random one. It only builds and reads, the gas
+/// is paid by a sponsor.
+pub fn get_fuel_wallet(provider: &Provider, config: &Config) -> WalletUnlocked {
+    match config.secrets.fuel_wallet_sk() {
+        Some(fuel_sk) => WalletUnlocked::new_from_private_key(
+            SecretKey::from_str(fuel_sk).unwrap(),
+            Some(provider.clone())
+        ),
+        None => WalletUnlocked::new_random(Some(provider.clone())),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,175 @@
+use std::result::Result as StdResult;
+use std::time::Duration;
+use thiserror::Error;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{info, instrument, warn};
+use fuels::{
+    prelude::*,
+    types::input::Input as SdkInput,
+};
+
+use crate::{
+    config::ArtifactPaths,
+    interfaces::owner_predicate::{get_owner_predicate_info, OwnerBinding},
+    io::utxo_input_utils::{find_spent_utxo_ids, get_input_txid_and_amount},
+    relay::{
+        RelayError,
+        intent_book::{parse_hex, to_hex, SignedIntent},
+    },
+    store::{IntentState, IntentStore, StoreError, StoredIntent},
+};
+
+
+#[derive(Error, Debug)]
+pub enum WatcherError {
+    #[error(transparent)]
+    Store(#[from] StoreError),
+    #[error(transparent)]
+    Relay(#[from] RelayError),
+    #[error("Provider error: {0}")]
+    Provider(String),
+}
+
+
+/// an open intent the watcher invalidated, with the intent inputs that were spent elsewhere.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InvalidatedIntent {
+    pub intent_id: String,
+    pub spent_utxo_ids: Vec<String>,
+    pub block_height: u32,
+}
+
+/// the events buffered per subscriber, a subscriber that falls further behind skips events.
+pub const WATCHER_EVENTS_CAPACITY: usize = 256;
+
+
+/// watches the inputs of the open intents in the store, and invalidates an intent as soon
+/// as any of its inpututxoids is spent, instead of a solver finding out from a 6664 revert.
+pub struct UtxoWatcher {
+    provider: Provider,
+    store: IntentStore,
+    artifacts: ArtifactPaths,
+    binding: OwnerBinding,
+    events: broadcast::Sender<InvalidatedIntent>,
+    checked_height: Option<u32>,
+}
+
+impl UtxoWatcher {
+
+    pub fn new(provider: Provider, store: IntentStore, artifacts: ArtifactPaths, binding: OwnerBinding) -> Self {
+        let (events, _) = broadcast::channel(WATCHER_EVENTS_CAPACITY);
+        UtxoWatcher {
+            provider,
+            store,
+            artifacts,
+            binding,
+            events,
+            checked_height: None,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<InvalidatedIntent> {
+        self.events.subscribe()
+    }
+
+    /// check the open intents once per new block, nothing is spent without a block.
+    pub async fn on_new_block(&mut self) -> StdResult<Vec<InvalidatedIntent>, WatcherError> {
+        let block_height = self.provider
+            .latest_block_height()
+            .await
+            .map_err(|e| WatcherError::Provider(e.to_string()))?;
+        if self.checked_height == Some(block_height) {
+            return Ok(vec![]);
+        }
+        let invalidated = self.check_open_intents(block_height).await?;
+        self.checked_height = Some(block_height);
+        Ok(invalidated)
+    }
+
+    /// expire the open intents past their expires_at height, invalidate the ones with a spent
+    /// input, and emit an event for each invalidated one.
+    #[instrument(name = "watch_utxos", skip(self))]
+    pub async fn check_open_intents(&self, block_height: u32) -> StdResult<Vec<InvalidatedIntent>, WatcherError> {
+        let mut invalidated = vec![];
+        for stored in self.store.list_open()? {
+            if stored.intent.expires_at.is_some_and(|height| block_height >= height) {
+                let intent_id: [u8; 32] = parse_hex("intent_id", &stored.intent_id)?;
+                match self.store.transition(&intent_id, IntentState::Expired) {
+                    Ok(_) | Err(StoreError::InvalidTransition { .. }) => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            let spent = spent_intent_utxo_ids(&self.provider, &self.artifacts, &self.binding, &stored.intent).await?;
+            if spent.is_empty() {
+                continue;
+            }
+            let intent_id: [u8; 32] = parse_hex("intent_id", &stored.intent_id)?;
+            match self.store.transition(&intent_id, IntentState::Invalidated) {
+                Ok(_) => {}
+                // submitted by a solver since it was listed, its fill tx settles it instead.
+                Err(StoreError::InvalidTransition { .. }) => continue,
+                Err(e) => return Err(e.into()),
+            }
+            let event = InvalidatedIntent {
+                intent_id: stored.intent_id,
+                spent_utxo_ids: spent.iter().map(|utxo_id| to_hex(utxo_id)).collect(),
+                block_height,
+            };
+            info!(intent_id = %event.intent_id, spent = event.spent_utxo_ids.len(), "intent invalidated");
+            // no subscribers is not an error.
+            let _ = self.events.send(event.clone());
+            invalidated.push(event);
+        }
+        Ok(invalidated)
+    }
+
+    /// check for spent inputs after every new block, polled every interval.
+    pub async fn run(mut self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.on_new_block().await {
+                warn!(error = %e, "utxo watcher failed");
+            }
+        }
+    }
+}
+
+/// the intent inputs that are no longer unspent inputs of the sender's owner predicate.
+pub async fn spent_intent_utxo_ids(
+    provider: &Provider,
+    artifacts: &ArtifactPaths,
+    binding: &OwnerBinding,
+    intent: &SignedIntent,
+) -> StdResult<Vec<[u8; 32]>, WatcherError> {
+    let (_, owner_b32addr, _) = get_owner_predicate_info(artifacts, binding, intent.sender_address()?);
+    find_spent_utxo_ids(provider, &owner_b32addr, &intent.utxos()?)
+        .await
+        .map_err(|e| WatcherError::Provider(e.to_string()))
+}
+
+/// the open intents a planned transfer from the owner predicate would invalidate, as the
+/// transfer spends some of their inputs.
+pub fn preview_transfer(
+    store: &IntentStore,
+    transfer_inputs: &[SdkInput],
+) -> StdResult<Vec<StoredIntent>, WatcherError> {
+    let spent: Vec<[u8; 32]> = transfer_inputs
+        .iter()
+        .filter_map(|input| get_input_txid_and_amount(input).ok())
+        .map(|(utxo_id, _, _)| utxo_id)
+        .collect();
+
+    let mut affected = vec![];
+    for stored in store.list_open()? {
+        let uses_spent_input = stored.intent
+            .utxos()?
+            .iter()
+            .any(|(_, utxo_id, _)| spent.contains(utxo_id));
+        if uses_spent_input {
+            affected.push(stored);
+        }
+    }
+    Ok(affected)
+}
@@ -0,0 +1,186 @@
+use fuels::{
+    prelude::*,
+    types::{
+        Bytes32,
+        input::Input as SdkInput,
+        output::Output as SdkOutput,
+        transaction::{ScriptTransaction, TxPolicies},
+        transaction_builders::ScriptTransactionBuilder,
+        TxPointer,
+        UtxoId,
+    },
+};
+
+use tracing::{info, instrument};
+
+use intent_swap::{
+    config::Config,
+    evm_712_sign,
+    helpers,
+    io::utxo_input_utils,
+    interfaces::{
+        generalized_swap_verifier_interface::{build_recipient_output, genio_for_inputs, GenIO},
+        owner_predicate,
+        predicate_validator::evm_address_to_bits256,
+    },
+    scenario::{Scenario, ScenarioBuilder},
+    sponsor::{GasSponsor, GasSponsorError},
+    wallets::{get_evm_addr, test_sender_evm_sk},
+};
+
+
+/// two intents of one owner, each over one input and for the same output to the same
+/// recipient, filled in one tx with a single payment to the recipient and a single fee
+/// output. The owner predicate rejects the tx, as its inputs carry different intents. The
+/// same inputs and outputs under one intent listing both inputs pass.
+///
+/// neither tx calls validate_solution, the SwapVerifier is only a contract input, so the
+/// rejection comes from the owner predicate alone.
+#[instrument(skip_all)]
+async fn run_shared_output_rejection(config: &Config) {
+    let scenario = ScenarioBuilder::new(config)
+        .owner_predicate("owner", test_sender_evm_sk())
+        .dummy_predicate("solver", 1u8)
+        .funder("sponsor")
+        .gas_sponsor("sponsor")
+        .coins("asset_in", "owner", &[1_000_000_000, 1_000_000_000])
+        .coins("asset_out", "solver", &[2_000_000_000])
+        .build()
+        .await
+        .unwrap();
+
+    let mut gas_sponsor = GasSponsor::new(
+        scenario.wallet("sponsor").unwrap().sponsor_account(),
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+    let (owner_bytecode, owner_b32addr) = scenario.predicate("owner").unwrap();
+    let asset_in_id = scenario.asset_id("asset_in").unwrap();
+    let asset_out_id = scenario.asset_id("asset_out").unwrap();
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &scenario.provider,
+        &owner_bytecode,
+        None,
+        asset_in_id,
+        2_000_000_000,
+    ).await.unwrap();
+    assert_eq!(owner_inputs.len(), 2);
+
+    helpers::display::print_separator_line(170, "(OWNER PREDICATE, TWO INTENTS SHARE ONE OUTPUT):");
+
+    let mut inputs = vec![];
+    let mut intents = vec![];
+    for owner_input in &owner_inputs {
+        let (gio_data, _) = genio_for_inputs(
+            std::slice::from_ref(owner_input),
+            asset_in_id,
+            asset_out_id,
+            2_000_000_000,
+            0,
+            &owner_b32addr,
+        ).unwrap();
+        inputs.extend(utxo_input_utils::with_predicate_data(
+            vec![owner_input.clone()],
+            intent_data(&gio_data).await,
+        ));
+        intents.push(gio_data);
+    }
+    // both intents are paid by the one recipient output and the one fee output of the first.
+    match sponsor_fill(&scenario, &mut gas_sponsor, inputs, &intents[0]).await {
+        Err(e) if e.is_predicate_rejection() => info!(error = %e, "rejected as expected"),
+        other => panic!("expected the owner predicate to reject two intents sharing one output, got {:?}", other.map(|_| ())),
+    }
+
+    helpers::display::print_separator_line(170, "(OWNER PREDICATE, ONE INTENT):");
+
+    let (gio_data, _) = genio_for_inputs(
+        &owner_inputs,
+        asset_in_id,
+        asset_out_id,
+        2_000_000_000,
+        0,
+        &owner_b32addr,
+    ).unwrap();
+    let inputs = utxo_input_utils::with_predicate_data(owner_inputs, intent_data(&gio_data).await);
+    sponsor_fill(&scenario, &mut gas_sponsor, inputs, &gio_data).await.unwrap();
+}
+
+/// the owner predicate data spending the owner inputs of the intent, signed by the owner.
+async fn intent_data(gio_data: &GenIO) -> Vec<u8> {
+    let compact_sig = evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+        test_sender_evm_sk(),
+        gio_data.inputassets.map(|b| b.0),
+        gio_data.inpututxoids.map(|b| b.0),
+        gio_data.inputamounts.map(|b| b.0),
+        gio_data.outputasset.0,
+        gio_data.outputamount.0,
+        gio_data.tolerance.0,
+        gio_data.recipient.0,
+    ).await;
+    owner_predicate::get_owner_predicate_intent_data(
+        evm_address_to_bits256(get_evm_addr(test_sender_evm_sk()).0),
+        compact_sig,
+        gio_data,
+    )
+}
+
+/// the owner inputs for a transfer script with the SwapVerifier as a contract input: the
+/// solver pays the output amount of gio_data to its recipient, and takes the owner inputs net
+/// of the protocol fee of gio_data. The gas sponsor runs the pre-flight.
+async fn sponsor_fill(
+    scenario: &Scenario,
+    gas_sponsor: &mut GasSponsor,
+    owner_inputs: Vec<SdkInput>,
+    gio_data: &GenIO,
+) -> Result<ScriptTransaction, GasSponsorError> {
+    let (solver_bytecode, solver_b32addr) = scenario.predicate("solver").unwrap();
+    let asset_in_id = AssetId::from(gio_data.inputassets[0].0);
+    let asset_out_id = AssetId::from(gio_data.outputasset.0);
+    let amount_out = helpers::conversions::bits256_to_u64(gio_data.outputamount);
+
+    let (solver_input, _, _) = utxo_input_utils::find_single_bytecode_predicate_input_for_amount(
+        &scenario.provider,
+        &solver_bytecode,
+        None,
+        asset_out_id,
+        amount_out,
+    ).await.unwrap();
+
+    let fee_output = scenario.binding.protocol_fee.fee_output(gio_data);
+    let fee_amount = match &fee_output {
+        Some(SdkOutput::Coin { amount, .. }) => *amount,
+        _ => 0,
+    };
+
+    let mut inputs = owner_inputs;
+    inputs.push(solver_input);
+    let verifier_index = inputs.len() as u16;
+    inputs.push(SdkInput::contract(
+        UtxoId::new(Bytes32::zeroed(), 0),
+        Bytes32::zeroed(),
+        Bytes32::zeroed(),
+        TxPointer::default(),
+        scenario.binding.verifier,
+    ));
+
+    let mut outputs: Vec<SdkOutput> = vec![
+        build_recipient_output(gio_data, amount_out),
+        SdkOutput::Coin {
+            to: Address::from(&solver_b32addr),
+            amount: 2_000_000_000 - fee_amount,
+            asset_id: asset_in_id,
+        },
+        SdkOutput::contract(verifier_index, Bytes32::zeroed(), Bytes32::zeroed()),
+    ];
+    outputs.extend(fee_output);
+
+    let stb = ScriptTransactionBuilder::prepare_transfer(inputs, outputs, TxPolicies::default());
+    gas_sponsor.sponsor(&scenario.provider, stb).await
+}
+
+#[tokio::test]
+async fn shared_output_rejection() {
+    dotenv::dotenv().ok();
+    run_shared_output_rejection(&Config::load(None).unwrap()).await;
+}
@@ -0,0 +1,218 @@
+use tracing::{info, instrument};
+use fuels::{
+    prelude::*,
+    types::{
+        output::Output as SdkOutput,
+        transaction::ScriptTransaction,
+    },
+};
+
+use intent_swap::{
+    config::Config,
+    evm_712_sign,
+    helpers,
+    io::{self, utxo_input_utils},
+    interfaces::{
+        generalized_swap_verifier_interface::{
+            genio_for_inputs,
+            build_recipient_output,
+            ProtocolFee,
+        },
+        predicate_validator,
+    },
+    receipt_show::receipt_sniffer::{
+        TxStatusResult,
+        wait_for_tx_success,
+    },
+    setup_01,
+    sponsor::{GasSponsor, GasSponsorError},
+    wallets::{get_evm_addr, test_sender_evm_sk},
+};
+
+
+/// swap through the intent predicate (no contract call). The owner's asset_in is held by the
+/// predicate_validator for the owner's EVM address, and is only spent for a tx that fills the intent.
+///
+/// first a solver tries to deliver less than the signed tolerance allows, then to skip the
+/// protocol fee, which the predicate rejects (the gas sponsor refuses the tx when the predicate
+/// gas is estimated). Then the intent is filled in full.
+#[instrument(skip_all)]
+async fn run_predicate_validated_intent_swaps(config: &Config) {
+    let owner_evm_address = get_evm_addr(test_sender_evm_sk());
+    let protocol_fee = ProtocolFee::from_config(config);
+    let (owner_bytecode, _, _) = predicate_validator::get_predicate_validator_info(&config.artifacts, &protocol_fee, owner_evm_address.0);
+
+    // the predicate path does not call the SwapVerifier, the predicate checks the protocol fee.
+    let (
+        provider,
+        _giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &ProtocolFee::none(),
+        Some(owner_bytecode),
+    ).await.unwrap();
+
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let swap = PredicateSwap {
+        owner: (&predicate_owner_bytecode, &predicate_owner_b32addr),
+        owner_evm_address: owner_evm_address.0,
+        solver: (&predicate_solver_bytecode, &predicate_solver_b32addr),
+        asset_in: (*AssetId::from_bytes_ref(&asset_in_id), 1_520_000_000),
+        asset_out: (*AssetId::from_bytes_ref(&asset_out_id), 2_000_000_000),
+        tolerance_bps: 250,
+        protocol_fee: &protocol_fee,
+    };
+
+    helpers::display::print_separator_line(170, "(PREDICATE SWAP, OUTPUT BELOW TOLERANCE):");
+
+    // 2.5% tolerance on 2_000_000_000 allows down to 1_950_000_000.
+    match build_predicate_swap_tx(&provider, &mut gas_sponsor, &swap, 1_900_000_000, true).await {
+        Err(e) if e.is_predicate_rejection() => info!(error = %e, "rejected as expected"),
+        other => panic!("expected the intent predicate to reject an output below the tolerance, got {:?}", other.map(|_| ())),
+    }
+
+    if protocol_fee.bps > 0 {
+        helpers::display::print_separator_line(170, "(PREDICATE SWAP, NO PROTOCOL FEE):");
+
+        match build_predicate_swap_tx(&provider, &mut gas_sponsor, &swap, swap.asset_out.1, false).await {
+            Err(e) if e.is_predicate_rejection() => info!(error = %e, "rejected as expected"),
+            other => panic!("expected the intent predicate to reject a fill without the fee, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    helpers::display::print_separator_line(170, "(PREDICATE SWAP):");
+
+    let tx = build_predicate_swap_tx(&provider, &mut gas_sponsor, &swap, swap.asset_out.1, true).await.unwrap();
+    let tx_id = provider.send_transaction(tx).await.unwrap();
+
+    match wait_for_tx_success(&provider, &tx_id, &config.polling).await {
+        TxStatusResult::Ok { elapsed_time, .. } => {
+            info!(tx_id = %tx_id, elapsed_ms = elapsed_time.as_millis() as u64, "predicate swap settled");
+        }
+        TxStatusResult::Err { error, .. } => {
+            panic!("Transaction failed: {:?}", error);
+        }
+    }
+
+    let recipient_asset_out_bal = io::tools::get_asset_balance_for_address(
+        &provider,
+        &predicate_owner_b32addr,
+        swap.asset_out.0,
+    ).await;
+    assert_eq!(recipient_asset_out_bal, swap.asset_out.1);
+}
+
+
+struct PredicateSwap<'a> {
+    owner: (&'a Vec<u8>, &'a Bech32Address),
+    owner_evm_address: [u8; 20],
+    solver: (&'a Vec<u8>, &'a Bech32Address),
+    asset_in: (AssetId, u64),
+    asset_out: (AssetId, u64),
+    tolerance_bps: u64,
+    protocol_fee: &'a ProtocolFee,
+}
+
+/// sign the intent over the owner's inputs, and build the tx where the solver delivers
+/// amount_delivered of the asset_out to the owner, paying the protocol fee out of the input
+/// asset if pay_fee. The gas sponsor runs the pre-flight.
+async fn build_predicate_swap_tx(
+    provider: &Provider,
+    gas_sponsor: &mut GasSponsor,
+    swap: &PredicateSwap<'_>,
+    amount_delivered: u64,
+    pay_fee: bool,
+) -> Result<ScriptTransaction, GasSponsorError> {
+    let (owner_bytecode, owner_b32addr) = swap.owner;
+    let (solver_bytecode, solver_b32addr) = swap.solver;
+    let (asset_in_id, amount_in) = swap.asset_in;
+    let (asset_out_id, amount_out) = swap.asset_out;
+
+    // the owner inputs without the predicate data, it is added once the intent is signed.
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        provider,
+        owner_bytecode,
+        None,
+        asset_in_id,
+        amount_in,
+    ).await.unwrap();
+
+    let (gio_tx, total_amount_in) = genio_for_inputs(
+        &owner_inputs,
+        asset_in_id,
+        asset_out_id,
+        amount_out,
+        swap.tolerance_bps,
+        owner_b32addr,
+    ).unwrap();
+
+    let (solver_input, _, _) = utxo_input_utils::find_single_bytecode_predicate_input_for_amount(
+        provider,
+        solver_bytecode,
+        None,
+        asset_out_id,
+        amount_out,
+    ).await.unwrap();
+
+    let compact_sig = evm_712_sign::build_eip712_genio_ethers::get_sig_eip712_by_ethers_genio(
+        test_sender_evm_sk(),
+        gio_tx.inputassets.map(|b| b.0),
+        gio_tx.inpututxoids.map(|b| b.0),
+        gio_tx.inputamounts.map(|b| b.0),
+        gio_tx.outputasset.0,
+        gio_tx.outputamount.0,
+        gio_tx.tolerance.0,
+        gio_tx.recipient.0,
+    ).await;
+
+    let predicate_data = predicate_validator::get_predicate_validator_data(
+        predicate_validator::evm_address_to_bits256(swap.owner_evm_address),
+        compact_sig,
+        &gio_tx,
+    );
+
+    let fee_output = if pay_fee { swap.protocol_fee.fee_output(&gio_tx) } else { None };
+    let fee_amount = match &fee_output {
+        Some(SdkOutput::Coin { amount, .. }) => *amount,
+        _ => 0,
+    };
+
+    let mut outputs: Vec<SdkOutput> = vec![
+        build_recipient_output(&gio_tx, amount_delivered),
+        SdkOutput::Coin {
+            to: Address::from(solver_b32addr),
+            amount: total_amount_in - fee_amount,
+            asset_id: asset_in_id,
+        },
+        SdkOutput::change(Address::from(owner_b32addr), 0, asset_in_id),
+        SdkOutput::change(Address::from(solver_b32addr), 0, asset_out_id),
+    ];
+    outputs.extend(fee_output);
+
+    let stb = predicate_validator::build_predicate_swap(
+        owner_inputs,
+        predicate_data,
+        vec![solver_input],
+        outputs,
+    );
+
+    gas_sponsor.sponsor(provider, stb).await
+}
+
+#[tokio::test]
+async fn predicate_validated_intent_swaps() {
+    dotenv::dotenv().ok();
+    run_predicate_validated_intent_swaps(&Config::load(None).unwrap()).await;
+}
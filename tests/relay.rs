@@ -0,0 +1,570 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use fuels::prelude::*;
+
+use futures::{Stream, StreamExt};
+use tracing::{info, instrument};
+
+use intent_swap::{
+    config::Config,
+    evm_712_sign::personal_sign_ethers::{personal_sign_compact, EvmOwnerSigner},
+    helpers,
+    indexer::{publish_intent_onchain, IndexerCursor, IntentIndexer},
+    io::utxo_input_utils,
+    interfaces::{
+        generalized_swap_verifier_interface::{
+            genio_for_inputs,
+            GenIO,
+            ProtocolFee,
+            SwapVerifier,
+        },
+        owner_predicate::{build_owner_transfer, OwnerBinding},
+    },
+    network::setup_owner_binding,
+    receipt_show::receipt_sniffer::{TxStatusResult, wait_for_tx_success},
+    relay::{
+        RelayError,
+        client::{sign_intent, RelayClient},
+        intent_book::{IntentBook, IntentEvent, IntentStatus},
+        server::{serve, RelayState},
+    },
+    setup_01,
+    solver::{fill_intent, submit_fill},
+    sponsor::GasSponsor,
+    store::{IntentState, IntentStore, StoreError},
+    watcher::{preview_transfer, UtxoWatcher},
+    wallets::{get_fuel_wallet, test_sender_2_evm_sk, test_sender_evm_sk},
+};
+
+
+/// run the intent relay against the in-process node: submit an intent, find it by asset pair
+/// and by id, and cancel it. Intents signed by another key, or over inputs the sender does not
+/// hold, are rejected, as is a cancel by anyone but the sender.
+#[instrument(skip_all)]
+async fn run_intent_relay_book(config: &Config) {
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        _predicate_solver_bytecode,
+        _predicate_solver_b32addr,
+        _sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &ProtocolFee::none(),
+        None,
+    ).await.unwrap();
+    let asset_in_id = *AssetId::from_bytes_ref(&asset_in_id);
+    let asset_out_id = *AssetId::from_bytes_ref(&asset_out_id);
+
+    let binding = setup_owner_binding(config, giov_cid, &ProtocolFee::none()).unwrap();
+    let client = start_relay(&provider, config, binding).await;
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+
+    let (gio_tx, _) = genio_for_inputs(&owner_inputs, asset_in_id, asset_out_id, 2_000_000_000, 250, &predicate_owner_b32addr).unwrap();
+
+    helpers::display::print_separator_line(170, "(RELAY REJECTS):");
+
+    // signed by another key than the sender it names.
+    let mut wrong_signer = sign_intent(test_sender_2_evm_sk(), &gio_tx).await;
+    wrong_signer.sender = sign_intent(test_sender_evm_sk(), &gio_tx).await.sender;
+    assert_rejected(client.submit(&wrong_signer).await, 400);
+
+    // signed by the second key, which does not hold the inputs.
+    let not_owner = sign_intent(test_sender_2_evm_sk(), &gio_tx).await;
+    assert_rejected(client.submit(&not_owner).await, 400);
+
+    helpers::display::print_separator_line(170, "(RELAY SUBMIT):");
+
+    let signed_intent = sign_intent(test_sender_evm_sk(), &gio_tx).await;
+    let entry = client.submit(&signed_intent).await.unwrap();
+    assert_eq!(entry.intent_id, format!("0x{}", hex::encode(signed_intent.intent_id().unwrap())));
+    assert_eq!(entry.status, IntentStatus::Open);
+    info!(intent_id = %entry.intent_id, "intent submitted to the relay");
+
+    assert_rejected(client.submit(&signed_intent).await, 409);
+
+    let by_pair = client.list(Some(asset_in_id), Some(asset_out_id)).await.unwrap();
+    assert_eq!(by_pair.len(), 1);
+    assert_eq!(by_pair[0].intent, signed_intent);
+    let reverse_pair = client.list(Some(asset_out_id), Some(asset_in_id)).await.unwrap();
+    assert!(reverse_pair.is_empty());
+
+    let fetched = client.get(&entry.intent_id).await.unwrap();
+    assert_eq!(fetched, entry);
+
+    helpers::display::print_separator_line(170, "(RELAY CANCEL):");
+
+    let intent_id = signed_intent.intent_id().unwrap();
+    let not_sender_cancel = personal_sign_compact(test_sender_2_evm_sk(), &intent_id).await;
+    assert_rejected(client.cancel(&entry.intent_id, &not_sender_cancel).await, 400);
+
+    let sender_cancel = personal_sign_compact(test_sender_evm_sk(), &intent_id).await;
+    let cancelled = client.cancel(&entry.intent_id, &sender_cancel).await.unwrap();
+    assert_eq!(cancelled.status, IntentStatus::Cancelled);
+
+    assert!(client.list(None, None).await.unwrap().is_empty());
+    assert_rejected(client.cancel(&entry.intent_id, &sender_cancel).await, 409);
+}
+
+
+fn assert_rejected<T: std::fmt::Debug>(result: std::result::Result<T, RelayError>, expected_status: u16) {
+    match result {
+        Err(RelayError::Rejected { status, message }) => {
+            info!(status, message = %message, "rejected as expected");
+            assert_eq!(status, expected_status);
+        }
+        other => panic!("expected the relay to reject with {}, got {:?}", expected_status, other),
+    }
+}
+
+
+/// a solver follows the relay over WebSocket: it receives the intent as accepted, fills it
+/// through the SwapVerifier, and then receives it as filled once the relay sees its inputs spent.
+#[instrument(skip_all)]
+async fn run_intent_relay_stream(config: &Config) {
+    let protocol_fee = ProtocolFee::from_config(config);
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &protocol_fee,
+        None,
+    ).await.unwrap();
+    let asset_in_id = *AssetId::from_bytes_ref(&asset_in_id);
+    let asset_out_id = *AssetId::from_bytes_ref(&asset_out_id);
+
+    let swapverifier_instance = SwapVerifier::new(giov_cid.clone(), get_fuel_wallet(&provider, config));
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let client = start_relay(&provider, config, setup_owner_binding(config, giov_cid, &protocol_fee).unwrap()).await;
+
+    // the solver only follows intents selling asset_in.
+    let events = client.subscribe(Some(asset_in_id), None).await.unwrap();
+    futures::pin_mut!(events);
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+    let amount_out: u64 = 2_000_000_000;
+    let (gio_tx, _) = genio_for_inputs(&owner_inputs, asset_in_id, asset_out_id, amount_out, 250, &predicate_owner_b32addr).unwrap();
+    let signed_intent = sign_intent(test_sender_evm_sk(), &gio_tx).await;
+    let entry = client.submit(&signed_intent).await.unwrap();
+
+    helpers::display::print_separator_line(170, "(RELAY STREAM, ACCEPTED):");
+
+    let accepted = next_event(&mut events).await;
+    assert_eq!(accepted, IntentEvent::Accepted(entry.clone()));
+
+    helpers::display::print_separator_line(170, "(RELAY STREAM, FILL):");
+
+    let tx_id = fill_intent(
+        &provider,
+        config,
+        &swapverifier_instance,
+        &protocol_fee,
+        &mut gas_sponsor,
+        (&predicate_solver_bytecode, &predicate_solver_b32addr),
+        &accepted.entry().intent,
+    ).await.unwrap();
+    info!(tx_id = %tx_id, "intent filled from the relay stream");
+
+    let filled = next_event(&mut events).await;
+    assert_eq!(filled.entry().intent_id, entry.intent_id);
+    assert_eq!(filled.entry().status, IntentStatus::Filled);
+
+    let recipient_asset_out_bal = intent_swap::io::tools::get_asset_balance_for_address(
+        &provider,
+        &predicate_owner_b32addr,
+        asset_out_id,
+    ).await;
+    assert_eq!(recipient_asset_out_bal, amount_out);
+}
+
+
+/// publish an intent through the intent_messenger, and index it from the chain into a book.
+/// A second indexer on the same cursor resumes after the indexed blocks and finds nothing new.
+#[instrument(skip_all)]
+async fn run_intent_indexer(config: &Config) {
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        _predicate_solver_bytecode,
+        _predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &ProtocolFee::none(),
+        None,
+    ).await.unwrap();
+    let asset_in_id = *AssetId::from_bytes_ref(&asset_in_id);
+    let asset_out_id = *AssetId::from_bytes_ref(&asset_out_id);
+
+    let swapverifier_instance = SwapVerifier::new(giov_cid.clone(), get_fuel_wallet(&provider, config));
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+    let (gio_tx, _) = genio_for_inputs(&owner_inputs, asset_in_id, asset_out_id, 2_000_000_000, 250, &predicate_owner_b32addr).unwrap();
+    let signed_intent = sign_intent(test_sender_evm_sk(), &gio_tx).await;
+
+    // index from the current height, an external node has the intents of earlier runs.
+    let run_dir = new_run_dir("intent_indexer");
+    let cursor_path = run_dir.join("indexer_cursor.json");
+    let start_height = provider.latest_block_height().await.unwrap();
+    IndexerCursor { next_height: start_height }.save(&cursor_path).unwrap();
+
+    helpers::display::print_separator_line(170, "(INDEXER, PUBLISH):");
+
+    let tx_id = publish_intent_onchain(
+        &provider,
+        &config.polling,
+        &swapverifier_instance,
+        &mut gas_sponsor,
+        signed_intent.sender_address().unwrap(),
+        gio_tx,
+        signed_intent.signature_bytes().unwrap(),
+    ).await.unwrap();
+    info!(tx_id = %tx_id, "intent published on chain");
+
+    helpers::display::print_separator_line(170, "(INDEXER, INDEX):");
+
+    let binding = setup_owner_binding(config, giov_cid, &ProtocolFee::none()).unwrap();
+    let book = IntentBook::new();
+    let mut indexer = IntentIndexer::new(
+        provider.clone(),
+        swapverifier_instance.clone(),
+        config.artifacts.clone(),
+        binding.clone(),
+        cursor_path.clone(),
+    ).unwrap();
+    let indexed = indexer.index_new_blocks(&book).await.unwrap();
+    assert_eq!(indexed, vec![signed_intent.clone()]);
+
+    let open = book.list_open(None, None).await;
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].intent, signed_intent);
+
+    helpers::display::print_separator_line(170, "(INDEXER, RESUME):");
+
+    let mut resumed = IntentIndexer::new(
+        provider.clone(),
+        swapverifier_instance,
+        config.artifacts.clone(),
+        binding,
+        cursor_path.clone(),
+    ).unwrap();
+    assert_eq!(resumed.next_height(), indexer.next_height());
+    info!(height = resumed.next_height(), "indexer resumed");
+    assert!(resumed.index_new_blocks(&IntentBook::new()).await.unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&run_dir);
+}
+
+
+/// follow an intent through the store: pending, matched, submitted, and settled by a store
+/// reopened as after a restart. A reverted fill is covered by the store unit tests.
+#[instrument(skip_all)]
+async fn run_intent_store(config: &Config) {
+    let protocol_fee = ProtocolFee::from_config(config);
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &protocol_fee,
+        None,
+    ).await.unwrap();
+    let asset_in_id = *AssetId::from_bytes_ref(&asset_in_id);
+    let asset_out_id = *AssetId::from_bytes_ref(&asset_out_id);
+
+    let swapverifier_instance = SwapVerifier::new(giov_cid.clone(), get_fuel_wallet(&provider, config));
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let run_dir = new_run_dir("intent_store");
+    let store_path = run_dir.join("intent_store.sled");
+
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+    let (gio_tx, _) = genio_for_inputs(&owner_inputs, asset_in_id, asset_out_id, 2_000_000_000, 250, &predicate_owner_b32addr).unwrap();
+    let signed_intent = sign_intent(test_sender_evm_sk(), &gio_tx).await;
+    let intent_id = signed_intent.intent_id().unwrap();
+
+    helpers::display::print_separator_line(170, "(STORE, SUBMIT):");
+
+    {
+        let store = IntentStore::open(&store_path).unwrap();
+        assert_eq!(store.insert(&signed_intent).unwrap().state, IntentState::Pending);
+        assert!(matches!(store.insert(&signed_intent), Err(StoreError::Duplicate(_))));
+
+        store.mark_matched(&intent_id, &predicate_solver_b32addr).unwrap();
+        let tx_id = submit_fill(
+            &provider,
+            config,
+            &swapverifier_instance,
+            &protocol_fee,
+            &mut gas_sponsor,
+            (&predicate_solver_bytecode, &predicate_solver_b32addr),
+            &signed_intent,
+            0,
+        ).await.unwrap();
+        let submitted = store.mark_submitted(&intent_id, &tx_id).unwrap();
+        info!(intent_id = %submitted.intent_id, state = ?submitted.state, "fill recorded");
+
+        assert!(matches!(
+            store.transition(&intent_id, IntentState::Expired),
+            Err(StoreError::InvalidTransition { .. })
+        ));
+    }
+
+    helpers::display::print_separator_line(170, "(STORE, RESTART AND SETTLE):");
+
+    {
+        let store = IntentStore::open(&store_path).unwrap();
+        let tracked = store.resume_submitted(&provider, &config.polling).await.unwrap();
+        assert_eq!(tracked.len(), 1);
+        let IntentState::Settled { receipts, .. } = &tracked[0].state else {
+            panic!("expected the intent to settle, got {:?}", tracked[0].state);
+        };
+        assert!(!receipts.is_empty());
+    }
+
+    let store = IntentStore::open(&store_path).unwrap();
+    assert_eq!(store.get(&intent_id).unwrap().unwrap().state.name(), "settled");
+    assert!(store.list_open().unwrap().is_empty());
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(&run_dir);
+}
+
+
+/// the owner plans a transfer of one of the coins an open intent signed over: the preview
+/// names that intent only, and once the transfer is in a block the watcher invalidates it,
+/// with an event for the subscribers. The intent over the other coin stays pending.
+#[instrument(skip_all)]
+async fn run_utxo_watcher(config: &Config) {
+    let (
+        provider,
+        giov_cid,
+        predicate_owner_bytecode,
+        predicate_owner_b32addr,
+        _predicate_solver_bytecode,
+        predicate_solver_b32addr,
+        sponsor_account,
+        asset_in_id,
+        asset_out_id,
+    ) = setup_01::setup_case_01::setup_predicate_and_assets_sdk_provider(
+        config,
+        &ProtocolFee::none(),
+        None,
+    ).await.unwrap();
+    let asset_in_id = *AssetId::from_bytes_ref(&asset_in_id);
+    let asset_out_id = *AssetId::from_bytes_ref(&asset_out_id);
+
+    let mut gas_sponsor = GasSponsor::new(
+        sponsor_account,
+        config.gas_sponsor.gas_amount,
+        config.gas_sponsor.budget,
+    );
+
+    let run_dir = new_run_dir("utxo_watcher");
+    let store_path = run_dir.join("intent_store.sled");
+    let store = IntentStore::open(&store_path).unwrap();
+
+    // the owner holds two coins of asset_in, one intent is signed over each.
+    let (owner_inputs, _) = utxo_input_utils::find_inputs_by_resource_filter(
+        &provider,
+        &predicate_owner_bytecode,
+        None,
+        asset_in_id,
+        1_520_000_000,
+    ).await.unwrap();
+    assert_eq!(owner_inputs.len(), 2);
+
+    let mut intents = vec![];
+    for input in &owner_inputs {
+        let (gio_tx, _) = genio_for_inputs(
+            std::slice::from_ref(input),
+            asset_in_id,
+            asset_out_id,
+            1_000_000_000,
+            250,
+            &predicate_owner_b32addr,
+        ).unwrap();
+        let signed_intent = sign_intent(test_sender_evm_sk(), &gio_tx).await;
+        intents.push(store.insert(&signed_intent).unwrap());
+    }
+
+    let binding = setup_owner_binding(config, giov_cid, &ProtocolFee::none()).unwrap();
+    let mut watcher = UtxoWatcher::new(provider.clone(), store.clone(), config.artifacts.clone(), binding);
+    let mut events = watcher.subscribe();
+    assert!(watcher.on_new_block().await.unwrap().is_empty());
+
+    helpers::display::print_separator_line(170, "(WATCHER, PREVIEW TRANSFER):");
+
+    let transfer_input = owner_inputs[0].clone();
+    let (_, _, transfer_amount) = utxo_input_utils::get_input_txid_and_amount(&transfer_input).unwrap();
+    let affected = preview_transfer(&store, std::slice::from_ref(&transfer_input)).unwrap();
+    assert_eq!(affected.len(), 1);
+    assert_eq!(affected[0].intent_id, intents[0].intent_id);
+    info!(intent_id = %affected[0].intent_id, "the transfer would invalidate the intent");
+
+    helpers::display::print_separator_line(170, "(WATCHER, TRANSFER):");
+
+    let outputs = vec![
+        fuels::types::output::Output::Coin {
+            to: Address::from(&predicate_solver_b32addr),
+            amount: transfer_amount.unwrap(),
+            asset_id: asset_in_id,
+        },
+    ];
+    let owner_signer = EvmOwnerSigner::new(test_sender_evm_sk(), predicate_owner_b32addr.clone());
+    let stb = build_owner_transfer(vec![transfer_input], outputs, owner_signer);
+    let tx = gas_sponsor.sponsor(&provider, stb).await.unwrap();
+    let tx_id = provider.send_transaction(tx).await.unwrap();
+    assert!(matches!(wait_for_tx_success(&provider, &tx_id, &config.polling).await, TxStatusResult::Ok { .. }));
+
+    helpers::display::print_separator_line(170, "(WATCHER, INVALIDATED):");
+
+    let invalidated = watcher.on_new_block().await.unwrap();
+    assert_eq!(invalidated.len(), 1);
+    assert_eq!(invalidated[0].intent_id, intents[0].intent_id);
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+    info!(event = ?event, "watcher event");
+    assert_eq!(event, invalidated[0]);
+
+    let first_id: [u8; 32] = intent_swap::relay::intent_book::parse_hex("intent_id", &intents[0].intent_id).unwrap();
+    let second_id: [u8; 32] = intent_swap::relay::intent_book::parse_hex("intent_id", &intents[1].intent_id).unwrap();
+    assert_eq!(store.get(&first_id).unwrap().unwrap().state, IntentState::Invalidated);
+    assert_eq!(store.get(&second_id).unwrap().unwrap().state, IntentState::Pending);
+
+    // no new block, nothing to check.
+    assert!(watcher.on_new_block().await.unwrap().is_empty());
+
+    drop(watcher);
+    drop(store);
+    let _ = std::fs::remove_dir_all(&run_dir);
+}
+
+
+/// a new directory for the files of one scenario run, so runs never share a store or cursor.
+fn new_run_dir(scenario: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let dir = std::env::temp_dir().join(format!("intent-swap-{}-{}-{}", scenario, std::process::id(), nanos));
+    std::fs::create_dir(&dir).unwrap();
+    dir
+}
+
+/// start the relay on a free local port, and return a client for it.
+async fn start_relay(provider: &Provider, config: &Config, binding: OwnerBinding) -> RelayClient {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(serve(
+        listener,
+        RelayState {
+            book: IntentBook::new(),
+            provider: provider.clone(),
+            config: Arc::new(config.clone()),
+            binding,
+        },
+    ));
+    info!(relay_url = %relay_url, "intent relay started");
+    RelayClient::new(&relay_url)
+}
+
+async fn next_event<S: Stream<Item = IntentEvent> + Unpin>(events: &mut S) -> IntentEvent {
+    let event = tokio::time::timeout(Duration::from_secs(30), events.next())
+        .await
+        .expect("no intent event from the relay")
+        .expect("the relay closed the intent stream");
+    info!(event = ?event, "intent event");
+    event
+}
+
+/// the config of the scenarios, from `intent-swap.toml` and the environment.
+fn load_config() -> Config {
+    dotenv::dotenv().ok();
+    Config::load(None).unwrap()
+}
+
+#[tokio::test]
+async fn intent_relay_book() {
+    run_intent_relay_book(&load_config()).await;
+}
+
+#[tokio::test]
+async fn intent_relay_stream() {
+    run_intent_relay_stream(&load_config()).await;
+}
+
+#[tokio::test]
+async fn intent_indexer() {
+    run_intent_indexer(&load_config()).await;
+}
+
+#[tokio::test]
+async fn intent_store() {
+    run_intent_store(&load_config()).await;
+}
+
+#[tokio::test]
+async fn utxo_watcher() {
+    run_utxo_watcher(&load_config()).await;
+}